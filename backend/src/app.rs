@@ -10,4 +10,6 @@ pub struct AppState {
     pub notification: Arc<Mutex<Notification>>,
     pub domain: String,
     pub config: Config,
+    /// Client for outbound calls on behalf of webhooks, built by `target::http_client`
+    pub http_client: reqwest::Client,
 }
@@ -75,17 +75,25 @@ where
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
+        // Scripts and the CLI send the same token as `Authorization: Bearer <token>`
+        let bearer = parts
+            .headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+
         let cookies = parts
             .headers
             .get(axum::http::header::COOKIE)
             .and_then(|v| v.to_str().ok())
             .unwrap_or("");
 
-        let token = cookies
-            .split(';')
-            .find_map(|c| {
-                let c = c.trim();
-                c.strip_prefix("auth_token=")
+        let token = bearer
+            .or_else(|| {
+                cookies.split(';').find_map(|c| {
+                    let c = c.trim();
+                    c.strip_prefix("auth_token=")
+                })
             })
             .ok_or((StatusCode::UNAUTHORIZED, "Missing auth"))?;
 
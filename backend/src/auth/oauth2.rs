@@ -28,5 +28,5 @@ pub fn verify_csrf(key: &str, csrf: &str) -> bool {
         .lock()
         .unwrap()
         .remove(key)
-        .map_or(false, |v| v == csrf)
+        .is_some_and(|v| v == csrf)
 }
@@ -0,0 +1,289 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::Method;
+use clap::{Parser, Subcommand};
+
+use hookspy::capture::forward::{rewrite_headers, send_request, target_url, upstream_response};
+use hookspy::schema::webhook::{RequestHeader, UpstreamResponse, WebhookRequest};
+
+#[derive(Parser, Debug)]
+#[command(
+    author = "Kostiantyn Hantsov",
+    version,
+    about = "Hookspy CLI - work with captured webhooks from the terminal"
+)]
+struct Args {
+    #[arg(
+        short,
+        long,
+        env = "HOOKSPY_SERVER",
+        value_name = "URL",
+        help = "Hookspy server to connect to",
+        default_value = "http://localhost:3000"
+    )]
+    server: String,
+
+    #[arg(
+        short,
+        long,
+        env = "HOOKSPY_TOKEN",
+        value_name = "TOKEN",
+        help = "Session token, the value of the auth_token cookie after logging in"
+    )]
+    token: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Relay every request a webhook captures to a local URL and report the answers back
+    Forward {
+        #[arg(value_name = "WEBHOOK_ID")]
+        webhook_id: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "Local target; the captured sub-path and query string are appended to it"
+        )]
+        to: String,
+
+        #[arg(
+            long,
+            value_name = "MS",
+            help = "How long to wait for the local target",
+            default_value_t = 10_000
+        )]
+        timeout_ms: u64,
+    },
+}
+
+/// Longest wait between reconnection attempts.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// How long each `requests/next` call waits for a new request.
+const POLL_TIMEOUT: &str = "60s";
+
+struct Forwarder {
+    server: String,
+    token: String,
+    webhook_id: String,
+    to: String,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+/// What one `requests/next` call returned.
+enum Poll {
+    Request(Box<WebhookRequest>),
+    /// Nothing was captured before the call timed out
+    Nothing,
+    /// The request used as the cursor was deleted
+    CursorGone,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    match args.command {
+        Command::Forward {
+            webhook_id,
+            to,
+            timeout_ms,
+        } => {
+            let to_url = reqwest::Url::parse(&to)
+                .map_err(|err| anyhow::anyhow!("invalid --to URL {:?}: {}", to, err))?;
+            if !matches!(to_url.scheme(), "http" | "https") {
+                anyhow::bail!("--to must be an http or https URL");
+            }
+
+            let forwarder = Arc::new(Forwarder {
+                server: args.server.trim_end_matches('/').to_string(),
+                token: args.token,
+                webhook_id,
+                to,
+                timeout: Duration::from_millis(timeout_ms),
+                client: reqwest::Client::builder()
+                    .redirect(reqwest::redirect::Policy::none())
+                    .build()?,
+            });
+
+            forwarder.run().await
+        }
+    }
+}
+
+impl Forwarder {
+    /// Relays requests in the order they were captured. Each `requests/next` call
+    /// continues after the last request seen, so requests captured while the connection
+    /// was down are picked up once it is back, and none is relayed twice.
+    async fn run(self: Arc<Self>) -> anyhow::Result<()> {
+        let capture_url = self.check_webhook().await?;
+        println!("Forwarding {} -> {}", capture_url, self.to);
+
+        let mut after: Option<String> = None;
+        let mut delay = Duration::from_secs(1);
+
+        loop {
+            match self.next_request(after.as_deref()).await {
+                Ok(Poll::Request(request)) => {
+                    delay = Duration::from_secs(1);
+                    after = Some(request.id.clone());
+                    // Requests are relayed concurrently so a slow target does not hold up the rest
+                    tokio::spawn(self.clone().relay(*request));
+                }
+                Ok(Poll::Nothing) => delay = Duration::from_secs(1),
+                Ok(Poll::CursorGone) => {
+                    eprintln!(
+                        "The last relayed request was deleted, continuing from the newest one"
+                    );
+                    after = None;
+                }
+                Err(err) => {
+                    eprintln!("Connection failed: {}, retrying in {:?}", err, delay);
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Confirms the token is valid and owns the webhook; returns its capture URL.
+    async fn check_webhook(&self) -> anyhow::Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Webhook {
+            url: String,
+        }
+
+        let response = self
+            .client
+            .get(format!("{}/api/webhooks/{}", self.server, self.webhook_id))
+            .header("authorization", self.authorization())
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            200 => Ok(response.json::<Webhook>().await?.url),
+            401 => anyhow::bail!("the token was rejected; log in again and copy a fresh one"),
+            404 => anyhow::bail!("webhook {} not found", self.webhook_id),
+            status => anyhow::bail!("server answered {} when looking up the webhook", status),
+        }
+    }
+
+    /// Waits for the request captured after `after`, or after the newest one so far.
+    async fn next_request(&self, after: Option<&str>) -> anyhow::Result<Poll> {
+        let mut query = vec![("timeout", POLL_TIMEOUT)];
+        if let Some(after) = after {
+            query.push(("after", after));
+        }
+
+        let response = self
+            .client
+            .get(format!(
+                "{}/api/webhooks/{}/requests/next",
+                self.server, self.webhook_id
+            ))
+            .query(&query)
+            .header("authorization", self.authorization())
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            200 => Ok(Poll::Request(Box::new(response.json().await?))),
+            204 => Ok(Poll::Nothing),
+            404 if after.is_some() => Ok(Poll::CursorGone),
+            401 => anyhow::bail!("the token was rejected; log in again and copy a fresh one"),
+            status => anyhow::bail!("server answered {}", status),
+        }
+    }
+
+    async fn relay(self: Arc<Self>, request: WebhookRequest) {
+        let url = target_url(&self.to, request.path.as_deref(), request.query.as_deref());
+        let started = Instant::now();
+
+        let result = match Method::from_bytes(request.method.as_bytes()) {
+            Ok(method) => {
+                let headers = rewrite_headers(
+                    &RequestHeader::to_header_map(&RequestHeader::parse_list(&request.headers)),
+                    &[],
+                    &[],
+                );
+                send_request(
+                    &self.client,
+                    method,
+                    &url,
+                    headers,
+                    request.received_body(),
+                    self.timeout,
+                    // Relaying to a local server is the point here
+                    true,
+                )
+                .await
+            }
+            Err(_) => {
+                eprintln!(
+                    "Skipping request {} with method {:?}",
+                    request.id, request.method
+                );
+                return;
+            }
+        };
+
+        let local = upstream_response(url, &result, started.elapsed().as_micros() as u64);
+        print_result(&request, &local);
+
+        if let Err(err) = self.report(&request.id, &local).await {
+            eprintln!("Failed to report the result of {}: {}", request.id, err);
+        }
+    }
+
+    async fn report(&self, request_id: &str, local: &UpstreamResponse) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .put(format!(
+                "{}/api/webhooks/{}/requests/{}/local-response",
+                self.server, self.webhook_id, request_id
+            ))
+            .header("authorization", self.authorization())
+            .json(local)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("server answered {}", response.status());
+        }
+        Ok(())
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+}
+
+fn print_result(request: &WebhookRequest, local: &UpstreamResponse) {
+    let target = format!(
+        "{}{}",
+        request.path.as_deref().unwrap_or("/"),
+        request
+            .query
+            .as_deref()
+            .map(|q| format!("?{}", q))
+            .unwrap_or_default()
+    );
+    let latency_ms = local.latency_us as f64 / 1000.0;
+
+    match (local.status, local.error.as_ref()) {
+        (Some(status), _) => {
+            println!(
+                "{} {} -> {} ({:.1} ms)",
+                request.method, target, status, latency_ms
+            )
+        }
+        (None, Some(error)) => println!("{} {} -> failed: {}", request.method, target, error),
+        (None, None) => println!("{} {} -> no answer", request.method, target),
+    }
+}
@@ -0,0 +1,245 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{json, Map, Value};
+
+use crate::schema::webhook::{BodyFormat, QueryParam};
+
+/// Deepest element nesting turned into JSON, the same limit serde_json puts on JSON
+/// bodies; the tree is built and dropped recursively, so deeper documents stay raw.
+const MAX_XML_DEPTH: usize = 128;
+
+/// Returns the lowercased media type of a `Content-Type` value, without parameters.
+pub fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Detects the body format from its content type and, for form and XML bodies,
+/// builds a JSON representation of the parsed content.
+///
+/// JSON bodies need no separate representation, and a body that fails to parse
+/// gets no format at all.
+pub fn parse_body(content_type: &str, body: &[u8]) -> (Option<BodyFormat>, Option<Value>) {
+    let media_type = media_type(content_type);
+
+    if media_type == "application/json" || media_type.ends_with("+json") {
+        return match serde_json::from_slice::<Value>(body) {
+            Ok(_) => (Some(BodyFormat::Json), None),
+            Err(_) => (None, None),
+        };
+    }
+
+    if media_type == "application/x-www-form-urlencoded" {
+        return match std::str::from_utf8(body) {
+            Ok(text) => (
+                Some(BodyFormat::Form),
+                serde_json::to_value(QueryParam::parse_all(text)).ok(),
+            ),
+            Err(_) => (None, None),
+        };
+    }
+
+    if media_type == "application/xml" || media_type == "text/xml" || media_type.ends_with("+xml") {
+        return match std::str::from_utf8(body).ok().and_then(xml_to_json) {
+            Some(tree) => (Some(BodyFormat::Xml), Some(tree)),
+            None => (None, None),
+        };
+    }
+
+    if media_type.starts_with("multipart/") {
+        return (Some(BodyFormat::Multipart), None);
+    }
+
+    (None, None)
+}
+
+/// Converts an XML document into a tree of
+/// `{ "name", "attributes", "text", "children" }` objects rooted at the document element.
+/// Returns `None` for documents nested deeper than `MAX_XML_DEPTH`.
+fn xml_to_json(text: &str) -> Option<Value> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Value> = Vec::new();
+    let mut root = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(_) | Event::Empty(_)) if stack.len() == MAX_XML_DEPTH => return None,
+            Ok(Event::Start(start)) => stack.push(xml_element(&start)?),
+            Ok(Event::Empty(start)) => {
+                let element = xml_element(&start)?;
+                attach_element(&mut stack, &mut root, element);
+            }
+            Ok(Event::End(_)) => {
+                let element = stack.pop()?;
+                attach_element(&mut stack, &mut root, element);
+            }
+            Ok(Event::Text(text)) => append_text(&mut stack, &text.unescape().ok()?),
+            Ok(Event::CData(data)) => {
+                append_text(&mut stack, &String::from_utf8_lossy(&data.into_inner()))
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {} // declarations, comments, processing instructions, doctype
+            Err(_) => return None,
+        }
+    }
+
+    if !stack.is_empty() {
+        return None;
+    }
+
+    root
+}
+
+fn xml_element(start: &BytesStart) -> Option<Value> {
+    let mut attributes = Map::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.ok()?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+        let value = attribute.unescape_value().ok()?.to_string();
+        attributes.insert(key, Value::String(value));
+    }
+
+    Some(json!({
+        "name": String::from_utf8_lossy(start.name().as_ref()),
+        "attributes": attributes,
+        "text": "",
+        "children": [],
+    }))
+}
+
+fn attach_element(stack: &mut [Value], root: &mut Option<Value>, element: Value) {
+    match stack.last_mut() {
+        Some(parent) => {
+            if let Some(children) = parent["children"].as_array_mut() {
+                children.push(element);
+            }
+        }
+        None => {
+            root.get_or_insert(element);
+        }
+    }
+}
+
+fn append_text(stack: &mut [Value], text: &str) {
+    if let Some(Value::String(current)) = stack.last_mut().map(|element| &mut element["text"]) {
+        current.push_str(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested(depth: usize) -> String {
+        format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth))
+    }
+
+    #[test]
+    fn formats_follow_the_media_type() {
+        assert_eq!(
+            media_type(" Application/JSON; charset=utf-8"),
+            "application/json"
+        );
+        assert_eq!(
+            parse_body("application/json", b"{\"a\":1}"),
+            (Some(BodyFormat::Json), None)
+        );
+        assert_eq!(
+            parse_body("application/vnd.api+json", b"[1]"),
+            (Some(BodyFormat::Json), None)
+        );
+        assert_eq!(
+            parse_body("multipart/form-data; boundary=x", b""),
+            (Some(BodyFormat::Multipart), None)
+        );
+        assert_eq!(parse_body("text/plain", b"{}"), (None, None));
+    }
+
+    #[test]
+    fn bodies_that_fail_to_parse_get_no_format() {
+        assert_eq!(parse_body("application/json", b"{"), (None, None));
+        assert_eq!(
+            parse_body("application/x-www-form-urlencoded", b"\xff=1"),
+            (None, None)
+        );
+        assert_eq!(parse_body("text/xml", b"<a><b></a>"), (None, None));
+        assert_eq!(parse_body("application/xml", b""), (None, None));
+    }
+
+    #[test]
+    fn form_fields_keep_order_and_repeats() {
+        let (format, parsed) = parse_body(
+            "application/x-www-form-urlencoded",
+            b"b=2&a=1&b=3&text=hello+world%21",
+        );
+        assert_eq!(format, Some(BodyFormat::Form));
+        assert_eq!(
+            parsed.unwrap(),
+            json!([
+                { "name": "b", "value": "2" },
+                { "name": "a", "value": "1" },
+                { "name": "b", "value": "3" },
+                { "name": "text", "value": "hello world!" },
+            ])
+        );
+    }
+
+    #[test]
+    fn xml_becomes_an_element_tree() {
+        let (format, parsed) = parse_body(
+            "application/atom+xml",
+            br#"<?xml version="1.0"?>
+            <!-- order -->
+            <order id="7" state="paid">
+                <item sku="a&amp;b"/>
+                <note>fast &lt;please&gt;</note>
+                <raw><![CDATA[<kept>]]></raw>
+            </order>"#,
+        );
+        assert_eq!(format, Some(BodyFormat::Xml));
+        assert_eq!(
+            parsed.unwrap(),
+            json!({
+                "name": "order",
+                "attributes": { "id": "7", "state": "paid" },
+                "text": "",
+                "children": [
+                    { "name": "item", "attributes": { "sku": "a&b" }, "text": "", "children": [] },
+                    { "name": "note", "attributes": {}, "text": "fast <please>", "children": [] },
+                    { "name": "raw", "attributes": {}, "text": "<kept>", "children": [] },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn xml_up_to_the_depth_limit_is_parsed() {
+        let tree = xml_to_json(&nested(MAX_XML_DEPTH)).unwrap();
+        assert_eq!(tree["name"], "a");
+        assert_eq!(
+            xml_to_json("<a><b/></a>").unwrap()["children"][0]["name"],
+            "b"
+        );
+    }
+
+    #[test]
+    fn xml_deeper_than_the_limit_stays_raw() {
+        assert_eq!(xml_to_json(&nested(MAX_XML_DEPTH + 1)), None);
+        let empty_leaf = format!(
+            "{}<b/>{}",
+            "<a>".repeat(MAX_XML_DEPTH),
+            "</a>".repeat(MAX_XML_DEPTH)
+        );
+        assert_eq!(xml_to_json(&empty_leaf), None);
+        assert_eq!(
+            parse_body("application/xml", nested(500_000).as_bytes()),
+            (None, None)
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use axum::http::{header, HeaderMap};
+use percent_encoding::percent_decode;
+use serde_json::{Map, Value};
+
+use crate::capture::body::media_type;
+use crate::schema::webhook::{CloudEvent, CloudEventMode};
+
+/// Reads the CloudEvents carried by a captured request.
+///
+/// Binary mode is recognised by the `ce-specversion` header, structured and batched
+/// mode by their content types. `body` is the body with any `Content-Encoding`
+/// removed. Anything else, or a structured body that is not valid JSON, yields no
+/// events.
+pub fn parse_cloud_events(headers: &HeaderMap, body: &[u8]) -> Vec<CloudEvent> {
+    if headers.contains_key("ce-specversion") {
+        return vec![binary_event(headers)];
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(media_type)
+        .unwrap_or_default();
+
+    match content_type.as_str() {
+        "application/cloudevents+json" => serde_json::from_slice::<Map<String, Value>>(body)
+            .map(|event| vec![structured_event(0, CloudEventMode::Structured, event)])
+            .unwrap_or_default(),
+        "application/cloudevents-batch+json" => serde_json::from_slice::<Vec<Value>>(body)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, event)| match event {
+                Value::Object(event) => {
+                    Some(structured_event(position, CloudEventMode::Batch, event))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn binary_event(headers: &HeaderMap) -> CloudEvent {
+    let mut attributes = Map::new();
+    for (name, value) in headers.iter() {
+        if let Some(attribute) = name.as_str().strip_prefix("ce-") {
+            // Header values are percent-encoded; a repeated header keeps its first value
+            let value = percent_decode(value.as_bytes())
+                .decode_utf8_lossy()
+                .into_owned();
+            attributes.entry(attribute).or_insert(Value::String(value));
+        }
+    }
+
+    // The body is the event data, so its own content type describes it
+    if let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        attributes.insert(
+            "datacontenttype".to_string(),
+            Value::String(content_type.to_string()),
+        );
+    }
+
+    event_from_attributes(0, CloudEventMode::Binary, attributes, None)
+}
+
+fn structured_event(
+    position: usize,
+    mode: CloudEventMode,
+    mut event: Map<String, Value>,
+) -> CloudEvent {
+    let data = event.remove("data").map(attribute_text);
+    let data_base64 = event.remove("data_base64").map(attribute_text);
+
+    event_from_attributes(position, mode, event, data.or(data_base64))
+}
+
+fn event_from_attributes(
+    position: usize,
+    mode: CloudEventMode,
+    mut attributes: Map<String, Value>,
+    data: Option<String>,
+) -> CloudEvent {
+    let mut take = |name: &str| attributes.remove(name).map(attribute_text);
+
+    let specversion = take("specversion");
+    let id = take("id");
+    let source = take("source");
+    let event_type = take("type");
+    let subject = take("subject");
+    let time = take("time");
+    let datacontenttype = take("datacontenttype");
+    let dataschema = take("dataschema");
+
+    CloudEvent {
+        position: position as u64,
+        mode,
+        specversion,
+        id,
+        source,
+        event_type,
+        subject,
+        time,
+        datacontenttype,
+        dataschema,
+        extensions: attributes,
+        data,
+    }
+}
+
+/// Strings as they are, anything else as JSON text.
+fn attribute_text(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderName, HeaderValue};
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse(pairs: &[(&'static str, &str)], body: &str) -> Vec<CloudEvent> {
+        let headers: HeaderMap = pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect();
+        parse_cloud_events(&headers, body.as_bytes())
+    }
+
+    #[test]
+    fn binary_events_come_from_ce_headers() {
+        let events = parse(
+            &[
+                ("ce-specversion", "1.0"),
+                ("ce-id", "A234-1234"),
+                ("ce-source", "/mycontext"),
+                ("ce-type", "com.example.someevent"),
+                ("ce-subject", "caf%C3%A9"),
+                ("ce-traceparent", "00-abc"),
+                ("content-type", "application/json"),
+            ],
+            r#"{"a":1}"#,
+        );
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.mode, CloudEventMode::Binary);
+        assert_eq!(event.specversion.as_deref(), Some("1.0"));
+        assert_eq!(event.id.as_deref(), Some("A234-1234"));
+        assert_eq!(event.source.as_deref(), Some("/mycontext"));
+        assert_eq!(event.event_type.as_deref(), Some("com.example.someevent"));
+        assert_eq!(event.subject.as_deref(), Some("café"));
+        assert_eq!(event.datacontenttype.as_deref(), Some("application/json"));
+        assert_eq!(event.extensions.get("traceparent"), Some(&json!("00-abc")));
+        assert_eq!(event.data, None);
+    }
+
+    #[test]
+    fn structured_events_come_from_the_body() {
+        let events = parse(
+            &[(
+                "content-type",
+                "application/cloudevents+json; charset=utf-8",
+            )],
+            r#"{"specversion":"1.0","id":"1","source":"s","type":"t","time":"2024-01-01T00:00:00Z","data":{"n":1},"priority":3}"#,
+        );
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.mode, CloudEventMode::Structured);
+        assert_eq!(event.event_type.as_deref(), Some("t"));
+        assert_eq!(event.time.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(event.data.as_deref(), Some(r#"{"n":1}"#));
+        assert_eq!(event.extensions.get("priority"), Some(&json!(3)));
+        assert!(!event.extensions.contains_key("data"));
+
+        let binary_data = parse(
+            &[("content-type", "application/cloudevents+json")],
+            r#"{"type":"t","data_base64":"AAE="}"#,
+        );
+        assert_eq!(binary_data[0].data.as_deref(), Some("AAE="));
+    }
+
+    #[test]
+    fn batches_keep_positions_and_skip_non_objects() {
+        let events = parse(
+            &[("content-type", "application/cloudevents-batch+json")],
+            r#"[{"id":"a"},1,{"id":"b","data":"text"}]"#,
+        );
+
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| event.mode == CloudEventMode::Batch));
+        assert_eq!(events[0].id.as_deref(), Some("a"));
+        assert_eq!(events[0].position, 0);
+        assert_eq!(events[1].id.as_deref(), Some("b"));
+        assert_eq!(events[1].position, 2);
+        assert_eq!(events[1].data.as_deref(), Some("text"));
+    }
+
+    #[test]
+    fn other_requests_have_no_events() {
+        assert!(parse(&[("content-type", "application/json")], r#"{"type":"t"}"#).is_empty());
+        assert!(parse(&[("content-type", "application/cloudevents+json")], "[1]").is_empty());
+        assert!(parse(
+            &[("content-type", "application/cloudevents-batch+json")],
+            "not json"
+        )
+        .is_empty());
+    }
+}
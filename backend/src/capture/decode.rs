@@ -0,0 +1,144 @@
+use std::io::{self, Read};
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use tracing::warn;
+
+/// Upper bound on the decompressed size, so a small compressed body cannot
+/// expand into an unbounded allocation.
+const MAX_DECODED_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Undoes the codings listed in a `Content-Encoding` header (gzip, deflate, br).
+///
+/// Codings are removed in the reverse of the order they were applied. Returns `None`
+/// when the body carries no coding, or when a coding is unknown or fails to decode,
+/// in which case the body is kept as received.
+pub fn decode_content_encoding(content_encoding: &str, body: &[u8]) -> Option<Vec<u8>> {
+    let codings: Vec<String> = content_encoding
+        .split(',')
+        .map(|coding| coding.trim().to_ascii_lowercase())
+        .filter(|coding| !coding.is_empty() && coding != "identity")
+        .collect();
+
+    if codings.is_empty() {
+        return None;
+    }
+
+    let mut data = body.to_vec();
+    for coding in codings.iter().rev() {
+        data = match decode_one(coding, &data) {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                warn!("Failed to decode {} request body: {}", coding, err);
+                return None;
+            }
+        };
+    }
+
+    Some(data)
+}
+
+fn decode_one(coding: &str, data: &[u8]) -> io::Result<Vec<u8>> {
+    match coding {
+        "gzip" | "x-gzip" => read_limited(GzDecoder::new(data)),
+        // HTTP "deflate" is meant to be zlib-wrapped, but some senders use raw deflate
+        "deflate" => read_limited(ZlibDecoder::new(data))
+            .or_else(|_| read_limited(DeflateDecoder::new(data))),
+        "br" => read_limited(brotli::Decompressor::new(data, 4096)),
+        other => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("unsupported content encoding {}", other),
+        )),
+    }
+}
+
+fn read_limited(reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    reader.take(MAX_DECODED_SIZE + 1).read_to_end(&mut out)?;
+
+    if out.len() as u64 > MAX_DECODED_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "decoded body exceeds size limit",
+        ));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+
+    use super::*;
+
+    const TEXT: &[u8] = b"{\"event\":\"order.paid\"}";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+            writer.write_all(data).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn each_coding_is_undone() {
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(TEXT).unwrap();
+        let mut raw_deflate = DeflateEncoder::new(Vec::new(), Compression::default());
+        raw_deflate.write_all(TEXT).unwrap();
+
+        assert_eq!(decode_content_encoding("gzip", &gzip(TEXT)).unwrap(), TEXT);
+        assert_eq!(
+            decode_content_encoding("X-GZIP", &gzip(TEXT)).unwrap(),
+            TEXT
+        );
+        assert_eq!(
+            decode_content_encoding("deflate", &zlib.finish().unwrap()).unwrap(),
+            TEXT
+        );
+        assert_eq!(
+            decode_content_encoding("deflate", &raw_deflate.finish().unwrap()).unwrap(),
+            TEXT
+        );
+        assert_eq!(decode_content_encoding("br", &brotli(TEXT)).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn stacked_codings_are_undone_last_first() {
+        let body = brotli(&gzip(TEXT));
+        assert_eq!(
+            decode_content_encoding("gzip, identity, br", &body).unwrap(),
+            TEXT
+        );
+    }
+
+    #[test]
+    fn bodies_without_a_coding_or_that_fail_stay_as_received() {
+        assert_eq!(decode_content_encoding("", TEXT), None);
+        assert_eq!(decode_content_encoding("identity", TEXT), None);
+        assert_eq!(decode_content_encoding("gzip", TEXT), None);
+        assert_eq!(decode_content_encoding("compress", TEXT), None);
+    }
+
+    #[test]
+    fn bodies_that_expand_past_the_limit_are_refused() {
+        let bomb = gzip(&vec![0; MAX_DECODED_SIZE as usize + 1]);
+        assert_eq!(decode_content_encoding("gzip", &bomb), None);
+        let largest = gzip(&vec![0; MAX_DECODED_SIZE as usize]);
+        assert_eq!(
+            decode_content_encoding("gzip", &largest).map(|data| data.len()),
+            Some(MAX_DECODED_SIZE as usize)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::convert::Infallible;
+use std::io;
+use std::time::Duration;
+
+use axum::{
+    body::{Body, Bytes},
+    http::header,
+    response::Response,
+};
+use futures_util::{stream, StreamExt};
+use rand::Rng;
+
+use crate::schema::webhook::{FaultAction, FaultProfile, InjectedFault, ResponseConfig};
+
+/// Decides which fault, if any, to inject into the reply for one request.
+pub fn pick_fault(profile: &FaultProfile) -> Option<InjectedFault> {
+    let mut rng = rand::rng();
+
+    let jitter_ms = match profile.jitter_ms {
+        0 => 0,
+        max => rng.random_range(0..=max),
+    };
+    let latency_ms = profile.latency_ms.saturating_add(jitter_ms);
+
+    // A single roll covers every rate, so at most one action is picked
+    let roll = rng.random_range(0..100u32);
+    let mut threshold = 0;
+    let mut hit = |rate: u8| {
+        threshold += rate as u32;
+        roll < threshold
+    };
+
+    let action = if hit(profile.error_rate) {
+        Some(FaultAction::Error {
+            status: profile.error_status,
+        })
+    } else if hit(profile.reset_rate) {
+        Some(FaultAction::Reset)
+    } else if hit(profile.timeout_rate) {
+        Some(FaultAction::Timeout {
+            after_ms: profile.timeout_ms,
+        })
+    } else if hit(profile.slow_rate) {
+        Some(FaultAction::SlowBody {
+            chunk_bytes: profile.slow_chunk_bytes,
+            chunk_delay_ms: profile.slow_chunk_delay_ms,
+        })
+    } else {
+        None
+    };
+
+    if latency_ms == 0 && action.is_none() {
+        return None;
+    }
+
+    Some(InjectedFault { latency_ms, action })
+}
+
+/// Reply sent in place of the usual one for an injected error.
+pub fn error_reply(status: u16) -> ResponseConfig {
+    ResponseConfig {
+        status,
+        content_type: Some("text/plain; charset=utf-8".to_string()),
+        headers: Vec::new(),
+        body: format!("injected fault: {}", status),
+    }
+}
+
+/// Waits out the injected latency, then sends `reply` the way the fault calls for.
+///
+/// Error faults are expected to be applied to `reply` already, so that it can be
+/// recorded as sent.
+pub async fn apply_fault(fault: &InjectedFault, reply: Response) -> Response {
+    tokio::time::sleep(Duration::from_millis(fault.latency_ms)).await;
+
+    match fault.action {
+        Some(FaultAction::Reset) => dropped_connection(),
+        Some(FaultAction::Timeout { after_ms }) => {
+            tokio::time::sleep(Duration::from_millis(after_ms)).await;
+            dropped_connection()
+        }
+        Some(FaultAction::SlowBody {
+            chunk_bytes,
+            chunk_delay_ms,
+        }) => slow_body(reply, chunk_bytes, Duration::from_millis(chunk_delay_ms)).await,
+        Some(FaultAction::Error { .. }) | None => reply,
+    }
+}
+
+/// Reply whose body fails before a byte is written. The status line is only flushed
+/// with the first body chunk, so the server closes the connection without answering
+/// and the client sees it drop (covered by `tests/fault.rs`).
+fn dropped_connection() -> Response {
+    Response::new(Body::from_stream(stream::once(async {
+        Err::<Bytes, _>(io::Error::new(
+            io::ErrorKind::ConnectionReset,
+            "connection reset by fault profile",
+        ))
+    })))
+}
+
+/// Re-sends `reply` with its body split into chunks and a pause before each one after
+/// the first.
+async fn slow_body(reply: Response, chunk_bytes: usize, delay: Duration) -> Response {
+    let (mut parts, body) = reply.into_parts();
+
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return dropped_connection(),
+    };
+
+    // Without a length the body goes out with chunked transfer encoding
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    let chunks = (0..bytes.len())
+        .step_by(chunk_bytes.max(1))
+        .map(|start| bytes.slice(start..(start + chunk_bytes).min(bytes.len())))
+        .collect::<Vec<_>>();
+
+    let body =
+        stream::iter(chunks.into_iter().enumerate()).then(move |(index, chunk)| async move {
+            if index > 0 {
+                tokio::time::sleep(delay).await;
+            }
+            Ok::<_, Infallible>(chunk)
+        });
+
+    Response::from_parts(parts, Body::from_stream(body))
+}
@@ -0,0 +1,302 @@
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    response::Response,
+};
+
+use crate::capture::response::build_response;
+use crate::capture::target::{self, BlockedTarget};
+use crate::schema::webhook::{
+    BodyEncoding, ForwardTarget, RequestHeader, ResponseConfig, ResponseHeader, SentResponse,
+    UpstreamResponse,
+};
+
+/// Headers tied to one connection, which the HTTP client or server sets again.
+pub const CONNECTION_HEADERS: &[&str] = &[
+    "connection",
+    "content-length",
+    "host",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Largest response body read from an upstream, the same limit `decode` puts on
+/// decompressed bodies.
+pub const MAX_RESPONSE_SIZE: usize = 32 * 1024 * 1024;
+
+/// Why an outbound request got no answer.
+#[derive(Debug)]
+pub enum SendError {
+    /// The target is an address webhooks may not reach
+    Blocked(BlockedTarget),
+    /// The answer's body was larger than `MAX_RESPONSE_SIZE`
+    TooLarge,
+    Request(reqwest::Error),
+}
+
+impl SendError {
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, SendError::Request(err) if err.is_timeout())
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Blocked(blocked) => write!(f, "target refused: {}", blocked),
+            SendError::TooLarge => write!(
+                f,
+                "response body is larger than {} bytes",
+                MAX_RESPONSE_SIZE
+            ),
+            // Includes the underlying cause that reqwest wraps
+            SendError::Request(err) => {
+                write!(f, "{}", err)?;
+                let mut source = err.source();
+                while let Some(cause) = source {
+                    write!(f, ": {}", cause)?;
+                    source = cause.source();
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for SendError {
+    fn from(err: reqwest::Error) -> Self {
+        // The resolver's refusals arrive wrapped in a connect error
+        let mut source = err.source();
+        while let Some(cause) = source {
+            if let Some(blocked) = cause.downcast_ref::<BlockedTarget>() {
+                return SendError::Blocked(blocked.clone());
+            }
+            source = cause.source();
+        }
+        SendError::Request(err)
+    }
+}
+
+/// A captured request after it was relayed to the forward target.
+pub struct Forwarded {
+    pub upstream: UpstreamResponse,
+    /// Reply for the caller: the upstream's answer, or a gateway error when there was none
+    pub reply: Response,
+    pub sent: SentResponse,
+}
+
+/// Relays a captured request to `url`, built from `target` with `target_url`.
+///
+/// Never fails: when the upstream cannot be reached the error is recorded in
+/// `UpstreamResponse::error` and the caller gets a 502, or a 504 after a timeout.
+pub async fn forward_request(
+    client: &reqwest::Client,
+    allow_private: bool,
+    target: &ForwardTarget,
+    url: String,
+    method: &Method,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Forwarded {
+    let started = Instant::now();
+
+    let result = send_request(
+        client,
+        method.clone(),
+        &url,
+        rewrite_headers(headers, &target.remove_headers, &target.set_headers),
+        body,
+        Duration::from_millis(target.timeout_ms),
+        allow_private,
+    )
+    .await;
+
+    let latency_us = started.elapsed().as_micros() as u64;
+    let upstream = upstream_response(url, &result, latency_us);
+
+    match result {
+        Ok((status, headers, body)) => {
+            let mut reply = Response::new(Body::from(body.clone()));
+            *reply.status_mut() = status;
+            for (name, value) in headers.iter() {
+                if !CONNECTION_HEADERS.contains(&name.as_str()) {
+                    reply.headers_mut().append(name.clone(), value.clone());
+                }
+            }
+
+            let sent = SentResponse {
+                status: status.as_u16(),
+                headers: reply
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| ResponseHeader {
+                        name: name.to_string(),
+                        value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                    })
+                    .collect(),
+                body: String::from_utf8_lossy(&body).into_owned(),
+                error: None,
+            };
+
+            Forwarded {
+                upstream,
+                reply,
+                sent,
+            }
+        }
+        Err(err) => {
+            let status = if err.is_timeout() {
+                StatusCode::GATEWAY_TIMEOUT
+            } else {
+                StatusCode::BAD_GATEWAY
+            };
+            let error = err.to_string();
+
+            let config = ResponseConfig {
+                status: status.as_u16(),
+                content_type: Some("text/plain; charset=utf-8".to_string()),
+                headers: Vec::new(),
+                body: format!("upstream error: {}", error),
+            };
+
+            Forwarded {
+                upstream,
+                reply: build_response(&config),
+                sent: SentResponse {
+                    error: Some(error),
+                    ..SentResponse::from_config(&config)
+                },
+            }
+        }
+    }
+}
+
+/// Sends one outbound request and reads the whole answer within `timeout`.
+///
+/// Unless `allow_private` is set, a URL naming a loopback, private or link-local
+/// address is refused; host names are checked by the client from `target::http_client`.
+pub async fn send_request(
+    client: &reqwest::Client,
+    method: Method,
+    url: &str,
+    headers: HeaderMap,
+    body: impl Into<reqwest::Body>,
+    timeout: Duration,
+    allow_private: bool,
+) -> Result<(StatusCode, HeaderMap, Bytes), SendError> {
+    if !allow_private {
+        target::check_url(url).map_err(SendError::Blocked)?;
+    }
+
+    let mut response = client
+        .request(method, url)
+        .headers(headers)
+        .timeout(timeout)
+        .body(body)
+        .send()
+        .await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_RESPONSE_SIZE as u64)
+    {
+        return Err(SendError::TooLarge);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_RESPONSE_SIZE {
+            return Err(SendError::TooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok((status, headers, Bytes::from(body)))
+}
+
+/// Appends the captured sub-path and query string to the target's base URL.
+pub fn target_url(base: &str, path: Option<&str>, query: Option<&str>) -> String {
+    let mut url = match path {
+        Some(path) => format!("{}{}", base.trim_end_matches('/'), path),
+        None => base.to_string(),
+    };
+
+    if let Some(query) = query.filter(|q| !q.is_empty()) {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(query);
+    }
+
+    url
+}
+
+/// Received headers minus connection headers and `remove`, plus `set`.
+pub fn rewrite_headers(
+    received: &HeaderMap,
+    remove: &[String],
+    set: &[ResponseHeader],
+) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    for (name, value) in received.iter() {
+        let removed = remove
+            .iter()
+            .any(|removed| removed.eq_ignore_ascii_case(name.as_str()));
+        if !removed && !CONNECTION_HEADERS.contains(&name.as_str()) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+
+    // Rewrites are validated when saved, so invalid headers are only skipped here
+    for header in set.iter() {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(header.name.as_bytes()),
+            HeaderValue::from_str(&header.value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+
+    headers
+}
+
+/// Records what an upstream answered to a request sent with `send_request`.
+pub fn upstream_response(
+    url: String,
+    result: &Result<(StatusCode, HeaderMap, Bytes), SendError>,
+    latency_us: u64,
+) -> UpstreamResponse {
+    match result {
+        Ok((status, headers, body)) => {
+            let (text, body_encoding) = BodyEncoding::encode(body);
+            UpstreamResponse {
+                url,
+                status: Some(status.as_u16()),
+                headers: RequestHeader::from_header_map(headers),
+                body: text,
+                body_encoding,
+                latency_us,
+                error: None,
+            }
+        }
+        Err(err) => UpstreamResponse {
+            url,
+            status: None,
+            headers: Vec::new(),
+            body: String::new(),
+            body_encoding: BodyEncoding::Utf8,
+            latency_us,
+            error: Some(err.to_string()),
+        },
+    }
+}
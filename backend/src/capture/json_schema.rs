@@ -0,0 +1,117 @@
+use jsonschema::Validator;
+use serde_json::Value;
+
+use crate::schema::json_schema::{SchemaValidation, SchemaViolation};
+
+/// Most violations kept for one request; a badly wrong body can produce thousands.
+pub const MAX_SCHEMA_VIOLATIONS: usize = 50;
+
+/// Builds a validator for `schema`, picking the draft from its `$schema` keyword.
+///
+/// Remote `$ref`s are not fetched, so a schema relying on one is rejected here.
+pub fn compile_json_schema(schema: &Value) -> Result<Validator, String> {
+    jsonschema::validator_for(schema).map_err(|err| format!("invalid JSON Schema: {}", err))
+}
+
+/// Validates a parsed JSON body, collecting where and why it fails.
+pub fn validate_json_body(validator: &Validator, body: &Value) -> SchemaValidation {
+    let errors: Vec<SchemaViolation> = validator
+        .iter_errors(body)
+        .take(MAX_SCHEMA_VIOLATIONS)
+        .map(|err| SchemaViolation {
+            path: err.instance_path().as_str().to_string(),
+            schema_path: err.schema_path().as_str().to_string(),
+            message: err.to_string(),
+        })
+        .collect();
+
+    SchemaValidation {
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn order_schema() -> Validator {
+        compile_json_schema(&json!({
+            "type": "object",
+            "required": ["id", "items"],
+            "properties": {
+                "id": { "type": "string" },
+                "items": { "type": "array", "items": { "type": "integer", "minimum": 1 } }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matching_bodies_are_valid() {
+        let result = validate_json_body(&order_schema(), &json!({ "id": "o1", "items": [1, 2] }));
+        assert!(result.valid);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn violations_point_at_the_body_and_the_schema() {
+        let result = validate_json_body(&order_schema(), &json!({ "id": 7, "items": [1, 0] }));
+        assert!(!result.valid);
+        let mut paths: Vec<(&str, &str)> = result
+            .errors
+            .iter()
+            .map(|err| (err.path.as_str(), err.schema_path.as_str()))
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                ("/id", "/properties/id/type"),
+                ("/items/1", "/properties/items/items/minimum"),
+            ]
+        );
+
+        let missing = validate_json_body(&order_schema(), &json!({ "id": "o1" }));
+        assert_eq!(missing.errors.len(), 1);
+        assert!(missing.errors[0].message.contains("items"));
+    }
+
+    #[test]
+    fn violations_are_capped() {
+        let validator = compile_json_schema(&json!({ "items": { "type": "string" } })).unwrap();
+        let body = Value::Array(vec![json!(0); MAX_SCHEMA_VIOLATIONS * 2]);
+        let result = validate_json_body(&validator, &body);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), MAX_SCHEMA_VIOLATIONS);
+    }
+
+    #[test]
+    fn invalid_schemas_and_remote_refs_are_rejected() {
+        let err = compile_json_schema(&json!({ "type": "nonsense" })).unwrap_err();
+        assert!(err.starts_with("invalid JSON Schema: "), "{}", err);
+        assert!(compile_json_schema(&json!({ "$ref": "https://example.invalid/s.json" })).is_err());
+    }
+
+    #[test]
+    fn the_draft_follows_the_schema_keyword() {
+        // `exclusiveMinimum` was a boolean modifier in draft 4 and a number from draft 6
+        let draft4 = compile_json_schema(&json!({
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "minimum": 1,
+            "exclusiveMinimum": true
+        }))
+        .unwrap();
+        assert!(!validate_json_body(&draft4, &json!(1)).valid);
+        assert!(validate_json_body(&draft4, &json!(2)).valid);
+
+        let draft2020 = compile_json_schema(&json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "exclusiveMinimum": 1
+        }))
+        .unwrap();
+        assert!(!validate_json_body(&draft2020, &json!(1)).valid);
+    }
+}
@@ -0,0 +1,14 @@
+pub mod body;
+pub mod cloudevents;
+pub mod decode;
+pub mod fault;
+pub mod forward;
+pub mod json_schema;
+pub mod multipart;
+pub mod provider;
+pub mod response;
+pub mod script;
+pub mod signature;
+pub mod target;
+pub mod template;
+pub mod wire;
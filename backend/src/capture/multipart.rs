@@ -0,0 +1,133 @@
+use axum::body::Bytes;
+use futures_util::stream;
+use tracing::warn;
+
+use crate::schema::webhook::{NewWebhookRequestPart, RequestHeader};
+
+/// Splits a `multipart/form-data` body into its parts.
+///
+/// Returns an empty list when the body is not multipart. Parsing stops at the first
+/// malformed part, keeping whatever was read before it; the raw body is stored
+/// either way.
+pub async fn parse_multipart(content_type: &str, body: &Bytes) -> Vec<NewWebhookRequestPart> {
+    if !content_type
+        .to_ascii_lowercase()
+        .starts_with("multipart/form-data")
+    {
+        return Vec::new();
+    }
+
+    let boundary = match multer::parse_boundary(content_type) {
+        Ok(boundary) => boundary,
+        Err(err) => {
+            warn!("Failed to read multipart boundary: {}", err);
+            return Vec::new();
+        }
+    };
+
+    let body = body.clone();
+    let mut multipart = multer::Multipart::new(
+        stream::once(async move { Ok::<_, std::convert::Infallible>(body) }),
+        boundary,
+    );
+
+    let mut parts = Vec::new();
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(err) => {
+                warn!("Failed to parse multipart body: {}", err);
+                break;
+            }
+        };
+
+        let name = field.name().map(str::to_string);
+        let filename = field.file_name().map(str::to_string);
+        let content_type = field.content_type().map(|mime| mime.to_string());
+        let headers = serde_json::to_string(&RequestHeader::from_header_map(field.headers()))
+            .unwrap_or_else(|_| "[]".to_string());
+
+        match field.bytes().await {
+            Ok(data) => parts.push(NewWebhookRequestPart {
+                name,
+                filename,
+                content_type,
+                headers,
+                data: data.to_vec(),
+            }),
+            Err(err) => {
+                warn!("Failed to read multipart part: {}", err);
+                break;
+            }
+        }
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT_TYPE: &str = "multipart/form-data; boundary=XyZ";
+
+    fn body(parts: &[&str]) -> Bytes {
+        let mut body = String::new();
+        for part in parts {
+            body.push_str("--XyZ\r\n");
+            body.push_str(part);
+            body.push_str("\r\n");
+        }
+        body.push_str("--XyZ--\r\n");
+        Bytes::from(body)
+    }
+
+    #[tokio::test]
+    async fn fields_and_files_are_split_with_their_metadata() {
+        let parts = parse_multipart(
+            CONTENT_TYPE,
+            &body(&[
+                "Content-Disposition: form-data; name=\"event\"\r\n\r\norder.paid",
+                "Content-Disposition: form-data; name=\"invoice\"; filename=\"a.pdf\"\r\nContent-Type: application/pdf\r\n\r\n%PDF",
+            ]),
+        )
+        .await;
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name.as_deref(), Some("event"));
+        assert_eq!(parts[0].filename, None);
+        assert_eq!(parts[0].content_type, None);
+        assert_eq!(parts[0].data, b"order.paid");
+
+        assert_eq!(parts[1].name.as_deref(), Some("invoice"));
+        assert_eq!(parts[1].filename.as_deref(), Some("a.pdf"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("application/pdf"));
+        assert_eq!(parts[1].data, b"%PDF");
+        let headers = RequestHeader::parse_list(&parts[1].headers);
+        assert!(headers
+            .iter()
+            .any(|header| header.name == "content-type" && header.value == "application/pdf"));
+    }
+
+    #[tokio::test]
+    async fn other_content_types_have_no_parts() {
+        let multipart = body(&["Content-Disposition: form-data; name=\"a\"\r\n\r\n1"]);
+        assert!(parse_multipart("application/json", &multipart)
+            .await
+            .is_empty());
+        assert!(parse_multipart("multipart/form-data", &multipart)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn parts_before_a_malformed_one_are_kept() {
+        let truncated = Bytes::from(
+            "--XyZ\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--XyZ\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nunfinished",
+        );
+        let parts = parse_multipart(CONTENT_TYPE, &truncated).await;
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].name.as_deref(), Some("a"));
+    }
+}
@@ -0,0 +1,263 @@
+use axum::http::HeaderMap;
+use serde_json::Value;
+
+use crate::capture::body::media_type;
+use crate::schema::webhook::QueryParam;
+
+/// Sender and event recognised from a captured request.
+#[derive(Debug, PartialEq)]
+pub struct DetectedProvider {
+    /// Short lowercase name, e.g. `github` or `cloudevents`
+    pub provider: &'static str,
+    /// Event as the provider names it, e.g. `push` or `invoice.paid`
+    pub event_type: Option<String>,
+}
+
+/// Recognises well-known webhook senders by the headers they always send, falling back
+/// to the shape of the body.
+///
+/// `body` is the body with any `Content-Encoding` removed.
+pub fn detect_provider(headers: &HeaderMap, body: &[u8]) -> Option<DetectedProvider> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let content_type = media_type(header("content-type").unwrap_or(""));
+    let json = serde_json::from_slice::<Value>(body).ok();
+    let form = (content_type == "application/x-www-form-urlencoded")
+        .then(|| std::str::from_utf8(body).ok().map(QueryParam::parse_all))
+        .flatten()
+        .unwrap_or_default();
+
+    let json_str = |pointer: &str| {
+        json.as_ref()
+            .and_then(|json| json.pointer(pointer))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let form_field = |name: &str| {
+        form.iter()
+            .find(|param| param.name == name)
+            .map(|param| param.value.clone())
+    };
+    let detected = |provider: &'static str, event_type: Option<String>| {
+        Some(DetectedProvider {
+            provider,
+            event_type,
+        })
+    };
+
+    if let Some(event) = header("x-github-event") {
+        return detected("github", Some(event.to_string()));
+    }
+    if let Some(event) = header("x-gitlab-event") {
+        return detected("gitlab", Some(event.to_string()));
+    }
+    if let Some(event) = header("x-event-key").filter(|_| header("x-request-uuid").is_some()) {
+        return detected("bitbucket", Some(event.to_string()));
+    }
+    if let Some(topic) = header("x-shopify-topic") {
+        return detected("shopify", Some(topic.to_string()));
+    }
+    if let Some(resource) = header("sentry-hook-resource") {
+        return detected("sentry", Some(resource.to_string()));
+    }
+
+    // Binary mode carries the attributes in headers, structured mode in the body
+    if let Some(event) = header("ce-type") {
+        return detected("cloudevents", Some(event.to_string()));
+    }
+    if content_type == "application/cloudevents+json" {
+        return detected("cloudevents", json_str("/type"));
+    }
+    if content_type == "application/cloudevents-batch+json" {
+        // A batch names one event only when every event in it shares the type
+        let mut types = json
+            .as_ref()
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|event| event.get("type").and_then(Value::as_str));
+        let first = types.next().flatten();
+        let shared = first.filter(|first| types.all(|other| other == Some(*first)));
+        return detected("cloudevents", shared.map(str::to_string));
+    }
+
+    if header("stripe-signature").is_some() || json_str("/object").as_deref() == Some("event") {
+        if let Some(event) = json_str("/type") {
+            return detected("stripe", Some(event));
+        }
+    }
+
+    if header("x-slack-signature").is_some() {
+        let event = json_str("/event/type")
+            .or_else(|| json_str("/type"))
+            .or_else(|| form_field("command"));
+        return detected("slack", event);
+    }
+
+    if header("x-twilio-signature").is_some() {
+        let event = form_field("MessageStatus")
+            .or_else(|| form_field("SmsStatus"))
+            .or_else(|| form_field("CallStatus"));
+        return detected("twilio", event);
+    }
+
+    if header("paypal-transmission-id").is_some() {
+        return detected("paypal", json_str("/event_type"));
+    }
+
+    // Svix and the Standard Webhooks spec it grew into put the event in `type`
+    if header("svix-id").is_some() || header("webhook-id").is_some() {
+        return detected("svix", json_str("/type"));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderName, HeaderValue};
+
+    use super::*;
+
+    fn detect(
+        pairs: &[(&'static str, &str)],
+        body: &str,
+    ) -> Option<(&'static str, Option<String>)> {
+        let headers: HeaderMap = pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect();
+        detect_provider(&headers, body.as_bytes()).map(|d| (d.provider, d.event_type))
+    }
+
+    fn found(provider: &'static str, event: &str) -> Option<(&'static str, Option<String>)> {
+        Some((provider, Some(event.to_string())))
+    }
+
+    #[test]
+    fn event_headers_name_the_sender() {
+        assert_eq!(
+            detect(&[("x-github-event", "push")], "{}"),
+            found("github", "push")
+        );
+        assert_eq!(
+            detect(&[("x-gitlab-event", "Merge Request Hook")], "{}"),
+            found("gitlab", "Merge Request Hook")
+        );
+        assert_eq!(
+            detect(
+                &[("x-event-key", "repo:push"), ("x-request-uuid", "1")],
+                "{}"
+            ),
+            found("bitbucket", "repo:push")
+        );
+        assert_eq!(detect(&[("x-event-key", "repo:push")], "{}"), None);
+        assert_eq!(
+            detect(&[("x-shopify-topic", "orders/create")], "{}"),
+            found("shopify", "orders/create")
+        );
+        assert_eq!(
+            detect(&[("sentry-hook-resource", "issue")], "{}"),
+            found("sentry", "issue")
+        );
+        assert_eq!(detect(&[("x-github-event", " ")], "{}"), None);
+    }
+
+    #[test]
+    fn cloudevents_are_recognised_in_every_mode() {
+        assert_eq!(
+            detect(&[("ce-type", "com.example.created")], "{}"),
+            found("cloudevents", "com.example.created")
+        );
+        assert_eq!(
+            detect(
+                &[(
+                    "content-type",
+                    "application/cloudevents+json; charset=utf-8"
+                )],
+                r#"{"type":"com.example.created"}"#
+            ),
+            found("cloudevents", "com.example.created")
+        );
+
+        let batch = [("content-type", "application/cloudevents-batch+json")];
+        assert_eq!(
+            detect(&batch, r#"[{"type":"a"},{"type":"a"}]"#),
+            found("cloudevents", "a")
+        );
+        assert_eq!(
+            detect(&batch, r#"[{"type":"a"},{"type":"b"}]"#),
+            Some(("cloudevents", None))
+        );
+        assert_eq!(detect(&batch, "[]"), Some(("cloudevents", None)));
+    }
+
+    #[test]
+    fn body_shapes_identify_stripe_slack_and_twilio() {
+        assert_eq!(
+            detect(&[], r#"{"object":"event","type":"invoice.paid"}"#),
+            found("stripe", "invoice.paid")
+        );
+        assert_eq!(detect(&[], r#"{"object":"charge","type":"x"}"#), None);
+
+        assert_eq!(
+            detect(
+                &[("x-slack-signature", "v0=1")],
+                r#"{"type":"event_callback","event":{"type":"app_mention"}}"#
+            ),
+            found("slack", "app_mention")
+        );
+        assert_eq!(
+            detect(
+                &[
+                    ("x-slack-signature", "v0=1"),
+                    ("content-type", "application/x-www-form-urlencoded"),
+                ],
+                "command=%2Fdeploy&text=now"
+            ),
+            found("slack", "/deploy")
+        );
+
+        assert_eq!(
+            detect(
+                &[
+                    ("x-twilio-signature", "abc"),
+                    ("content-type", "application/x-www-form-urlencoded"),
+                ],
+                "CallSid=CA1&CallStatus=ringing"
+            ),
+            found("twilio", "ringing")
+        );
+    }
+
+    #[test]
+    fn paypal_and_svix_read_the_body_for_the_event() {
+        assert_eq!(
+            detect(
+                &[("paypal-transmission-id", "1")],
+                r#"{"event_type":"PAYMENT.CAPTURE.COMPLETED"}"#
+            ),
+            found("paypal", "PAYMENT.CAPTURE.COMPLETED")
+        );
+        assert_eq!(
+            detect(&[("webhook-id", "msg_1")], r#"{"type":"user.created"}"#),
+            found("svix", "user.created")
+        );
+        assert_eq!(
+            detect(&[("svix-id", "msg_1")], "not json"),
+            Some(("svix", None))
+        );
+        assert_eq!(detect(&[("content-type", "application/json")], "{}"), None);
+    }
+}
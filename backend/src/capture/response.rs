@@ -0,0 +1,46 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    response::Response,
+};
+use tracing::warn;
+
+use crate::schema::webhook::ResponseConfig;
+
+/// Builds the reply configured for a webhook.
+///
+/// Configs are validated when saved, so anything that no longer parses is
+/// skipped with a warning rather than failing the capture.
+pub fn build_response(config: &ResponseConfig) -> Response {
+    let mut response = Response::new(Body::from(config.body.clone()));
+
+    *response.status_mut() = StatusCode::from_u16(config.status).unwrap_or_else(|_| {
+        warn!("Invalid configured status code {}", config.status);
+        StatusCode::OK
+    });
+
+    let headers = response.headers_mut();
+
+    if let Some(content_type) = &config.content_type {
+        match HeaderValue::from_str(content_type) {
+            Ok(value) => {
+                headers.insert(header::CONTENT_TYPE, value);
+            }
+            Err(_) => warn!("Invalid configured content type {:?}", content_type),
+        }
+    }
+
+    for configured in config.headers.iter() {
+        match (
+            HeaderName::from_bytes(configured.name.as_bytes()),
+            HeaderValue::from_str(&configured.value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.append(name, value);
+            }
+            _ => warn!("Invalid configured response header {:?}", configured.name),
+        }
+    }
+
+    response
+}
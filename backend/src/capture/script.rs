@@ -0,0 +1,260 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use rhai::{Dynamic, Engine, Scope};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::capture::template::TemplateRequest;
+use crate::schema::webhook::{ResponseConfig, ResponseHeader};
+
+/// Wall-clock budget for one script run.
+const SCRIPT_TIMEOUT: Duration = Duration::from_millis(250);
+/// Rhai operation budget for one script run, bounding CPU use independently of the clock.
+const SCRIPT_MAX_OPERATIONS: u64 = 1_000_000;
+/// Upper bound for strings, arrays and maps created by a script.
+const SCRIPT_MAX_SIZE: usize = 1_048_576;
+
+/// What a webhook script decided for one request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ScriptOutcome {
+    /// Reply to send instead of the response configuration, if the script returned one
+    pub response: Option<ResponseConfig>,
+    /// Whether the request should be stored; scripts can drop noise such as health checks
+    pub store: bool,
+    pub tags: Vec<String>,
+    /// Compile or runtime error; the request is then handled as if there were no script
+    pub error: Option<String>,
+    /// Output of `print` and `debug` calls
+    pub logs: Vec<String>,
+}
+
+impl ScriptOutcome {
+    fn failed(error: String, logs: Vec<String>) -> Self {
+        ScriptOutcome {
+            response: None,
+            store: true,
+            tags: Vec::new(),
+            error: Some(error),
+            logs,
+        }
+    }
+}
+
+/// Map a script may evaluate to. Every key is optional; a script that evaluates to `()`
+/// leaves the request to the response configuration.
+#[derive(Deserialize)]
+struct ScriptResult {
+    status: Option<u16>,
+    content_type: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<serde_json::Value>,
+    store: Option<bool>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Engine with resource limits and without access to anything outside the script.
+fn engine(logs: Rc<RefCell<Vec<String>>>) -> Engine {
+    let mut engine = Engine::new();
+
+    engine.set_max_operations(SCRIPT_MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(SCRIPT_MAX_SIZE);
+    engine.set_max_array_size(SCRIPT_MAX_SIZE);
+    engine.set_max_map_size(SCRIPT_MAX_SIZE);
+    engine.disable_symbol("eval");
+
+    let started = Instant::now();
+    engine.on_progress(move |_| {
+        if started.elapsed() > SCRIPT_TIMEOUT {
+            Some(Dynamic::from(format!(
+                "script exceeded {} ms",
+                SCRIPT_TIMEOUT.as_millis()
+            )))
+        } else {
+            None
+        }
+    });
+
+    let print_logs = logs.clone();
+    engine.on_print(move |line| print_logs.borrow_mut().push(line.to_string()));
+    engine.on_debug(move |line, _, position| {
+        logs.borrow_mut().push(format!("{} {}", position, line))
+    });
+
+    engine
+}
+
+/// Checks that `source` parses.
+pub fn compile_script(source: &str) -> Result<(), String> {
+    engine(Rc::default())
+        .compile(source)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// Runs a webhook script with `request` in scope.
+///
+/// Blocks for up to `SCRIPT_TIMEOUT`, so async callers should use `spawn_blocking`.
+pub fn run_script(source: &str, request: &TemplateRequest) -> ScriptOutcome {
+    let logs: Rc<RefCell<Vec<String>>> = Rc::default();
+    let engine = engine(logs.clone());
+    let take_logs = || logs.borrow_mut().drain(..).collect::<Vec<_>>();
+
+    let ast = match engine.compile(source) {
+        Ok(ast) => ast,
+        Err(err) => return ScriptOutcome::failed(err.to_string(), take_logs()),
+    };
+
+    let request = match rhai::serde::to_dynamic(request) {
+        Ok(request) => request,
+        Err(err) => return ScriptOutcome::failed(err.to_string(), take_logs()),
+    };
+
+    let mut scope = Scope::new();
+    scope.push_constant("request", request);
+
+    let result = match engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast) {
+        Ok(result) => result,
+        Err(err) => return ScriptOutcome::failed(err.to_string(), take_logs()),
+    };
+
+    if result.is_unit() {
+        return ScriptOutcome {
+            response: None,
+            store: true,
+            tags: Vec::new(),
+            error: None,
+            logs: take_logs(),
+        };
+    }
+
+    let result: ScriptResult = match rhai::serde::from_dynamic(&result) {
+        Ok(result) => result,
+        Err(err) => {
+            return ScriptOutcome::failed(
+                format!("script must evaluate to a map or (): {}", err),
+                take_logs(),
+            )
+        }
+    };
+
+    let has_response =
+        result.status.is_some() || result.body.is_some() || !result.headers.is_empty();
+
+    let response = has_response.then(|| {
+        // Structured bodies are sent as JSON
+        let (body, default_content_type) = match result.body {
+            None => (String::new(), None),
+            Some(serde_json::Value::String(text)) => (text, None),
+            Some(value) => (value.to_string(), Some("application/json".to_string())),
+        };
+
+        ResponseConfig {
+            status: result.status.unwrap_or(200),
+            content_type: result.content_type.or(default_content_type),
+            headers: result
+                .headers
+                .into_iter()
+                .map(|(name, value)| ResponseHeader { name, value })
+                .collect(),
+            body,
+        }
+    });
+
+    if let Some(Err(err)) = response.as_ref().map(ResponseConfig::validate) {
+        return ScriptOutcome::failed(err, take_logs());
+    }
+
+    ScriptOutcome {
+        response,
+        store: result.store.unwrap_or(true),
+        tags: result.tags,
+        error: None,
+        logs: take_logs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str) -> ScriptOutcome {
+        let request = TemplateRequest::new(
+            "POST",
+            Some("/health"),
+            None,
+            &[],
+            br#"{"event":"order.paid"}"#,
+        );
+        run_script(source, &request)
+    }
+
+    fn error(source: &str) -> String {
+        let outcome = run(source);
+        assert!(outcome.store, "failed scripts keep the request");
+        assert_eq!(outcome.response, None);
+        outcome.error.expect("the script should fail")
+    }
+
+    #[test]
+    fn endless_loops_run_out_of_operations() {
+        let err = error("loop { }");
+        assert!(err.contains("operations"), "{}", err);
+    }
+
+    #[test]
+    fn deep_recursion_is_stopped() {
+        let err = error("fn down(n) { down(n + 1) } down(0)");
+        assert!(err.to_lowercase().contains("stack"), "{}", err);
+    }
+
+    #[test]
+    fn growing_strings_arrays_and_maps_are_capped() {
+        let err = error(r#"let s = "x"; loop { s += s; }"#);
+        assert!(err.contains("string"), "{}", err);
+        let err = error("let a = [0]; loop { a += a; }");
+        assert!(err.to_lowercase().contains("array"), "{}", err);
+    }
+
+    #[test]
+    fn eval_is_unavailable() {
+        assert!(compile_script(r#"eval("1")"#).is_err());
+        assert!(run(r#"eval("1")"#).error.is_some());
+    }
+
+    #[test]
+    fn maps_become_replies_tags_and_store_decisions() {
+        let outcome = run(r#"
+            print("seen " + request.path);
+            #{ status: 202, body: #{ ok: true }, headers: #{ "x-by": "script" }, tags: ["paid"] }
+            "#);
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.logs, ["seen /health"]);
+        assert_eq!(outcome.tags, ["paid"]);
+        let response = outcome.response.unwrap();
+        assert_eq!(response.status, 202);
+        assert_eq!(response.content_type.as_deref(), Some("application/json"));
+        assert_eq!(response.body, r#"{"ok":true}"#);
+        assert_eq!(response.headers[0].value, "script");
+
+        let dropped = run(r#"if request.path == "/health" { #{ store: false } }"#);
+        assert!(!dropped.store);
+        assert_eq!(dropped.response, None);
+
+        let untouched = run("()");
+        assert_eq!(untouched.error, None);
+        assert!(untouched.store);
+    }
+
+    #[test]
+    fn results_that_are_not_valid_replies_are_errors() {
+        assert!(error("42").contains("must evaluate to a map"));
+        error("#{ status: 42 }");
+    }
+}
@@ -0,0 +1,512 @@
+use axum::http::{header, HeaderMap, Uri};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::schema::signature::{
+    SignatureCheck, SignatureConfig, SignatureProvider, SignatureStatus,
+};
+
+/// Longest signed payload kept with a check; the body itself is stored anyway.
+const MAX_SIGNED_PAYLOAD_CHARS: usize = 1024;
+
+/// The URL the sender called, which Twilio signs.
+///
+/// Path and query are taken as received, before any decoding. Scheme and host come from
+/// `X-Forwarded-Proto` and `X-Forwarded-Host` when a proxy set them, then from the
+/// `Host` header, and fall back to the configured `domain`.
+pub fn request_url(domain: &str, uri: &Uri, headers: &HeaderMap) -> String {
+    let (domain_scheme, domain_host) = domain
+        .trim_end_matches('/')
+        .split_once("://")
+        .unwrap_or(("http", domain));
+
+    let scheme = first_value(headers, "x-forwarded-proto").unwrap_or(domain_scheme);
+    let host = first_value(headers, "x-forwarded-host")
+        .or_else(|| first_value(headers, header::HOST.as_str()))
+        .or_else(|| uri.authority().map(|authority| authority.as_str()))
+        .unwrap_or(domain_host);
+    let path_and_query = uri.path_and_query().map_or("/", |pq| pq.as_str());
+
+    format!("{}://{}{}", scheme, host, path_and_query)
+}
+
+/// First entry of a possibly comma-separated header, as proxies append their own.
+fn first_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    header_str(headers, name)
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Computes the signature `config` expects for a captured request and compares it with
+/// the one received.
+///
+/// `body` is the body exactly as received and `url` the capture URL the sender called,
+/// query string included, which Twilio signs.
+pub fn verify_signature(
+    config: &SignatureConfig,
+    headers: &HeaderMap,
+    body: &[u8],
+    url: &str,
+) -> SignatureCheck {
+    let header_name = config.header();
+    let received = headers
+        .get(header_name.as_str())
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).trim().to_string());
+
+    let check = SignatureCheck {
+        provider: config.provider,
+        status: SignatureStatus::Missing,
+        header: header_name,
+        received: received.clone(),
+        expected: None,
+        signed_payload: None,
+        detail: None,
+    };
+
+    let secret = config.secret.as_bytes();
+
+    match config.provider {
+        SignatureProvider::Github => {
+            let expected = format!("sha256={}", to_hex(&hmac_sha256(secret, body)));
+            finish(check, body, expected, |received, expected| {
+                received.eq_ignore_ascii_case(expected)
+            })
+        }
+        SignatureProvider::Shopify => {
+            let expected = BASE64.encode(hmac_sha256(secret, body));
+            finish(check, body, expected, |received, expected| {
+                received == expected
+            })
+        }
+        SignatureProvider::HmacSha256 => {
+            let digest = hmac_sha256(secret, body);
+            let hex = to_hex(&digest);
+            let base64 = BASE64.encode(digest);
+            // Shown in the received form: prefixed or not, hex unless base64 was sent
+            let prefixed = received
+                .as_deref()
+                .is_some_and(|r| r.to_ascii_lowercase().starts_with("sha256="));
+            let sent_base64 = received
+                .as_deref()
+                .is_some_and(|r| strip_sha256_prefix(r) == base64);
+            let expected = match (prefixed, sent_base64) {
+                (true, true) => format!("sha256={}", base64),
+                (true, false) => format!("sha256={}", hex),
+                (false, true) => base64.clone(),
+                (false, false) => hex.clone(),
+            };
+            finish(check, body, expected, |received, _| {
+                let signature = strip_sha256_prefix(received);
+                signature.eq_ignore_ascii_case(&hex) || signature == base64
+            })
+        }
+        SignatureProvider::Slack => {
+            let Some(timestamp) = header_str(headers, "x-slack-request-timestamp") else {
+                return SignatureCheck {
+                    detail: Some("no X-Slack-Request-Timestamp header".to_string()),
+                    ..check
+                };
+            };
+
+            let payload = [format!("v0:{}:", timestamp).as_bytes(), body].concat();
+            let expected = format!("v0={}", to_hex(&hmac_sha256(secret, &payload)));
+            finish(check, &payload, expected, |received, expected| {
+                received.eq_ignore_ascii_case(expected)
+            })
+        }
+        SignatureProvider::Stripe => {
+            let Some(received) = received else {
+                return check;
+            };
+
+            let mut timestamp = None;
+            let mut signatures = Vec::new();
+            for (key, value) in received.split(',').filter_map(|item| item.split_once('=')) {
+                match key.trim() {
+                    "t" => timestamp = Some(value.trim().to_string()),
+                    "v1" => signatures.push(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+
+            let Some(timestamp) = timestamp else {
+                return SignatureCheck {
+                    detail: Some("no t= timestamp in the Stripe-Signature header".to_string()),
+                    ..check
+                };
+            };
+
+            let payload = [format!("{}.", timestamp).as_bytes(), body].concat();
+            let expected = to_hex(&hmac_sha256(secret, &payload));
+            let verified = signatures
+                .iter()
+                .any(|signature| signature.eq_ignore_ascii_case(&expected));
+
+            SignatureCheck {
+                status: if verified {
+                    SignatureStatus::Verified
+                } else {
+                    SignatureStatus::Mismatched
+                },
+                detail: signatures
+                    .is_empty()
+                    .then(|| "no v1= signature in the Stripe-Signature header".to_string()),
+                expected: Some(format!("t={},v1={}", timestamp, expected)),
+                signed_payload: Some(printable(&payload)),
+                ..check
+            }
+        }
+        SignatureProvider::Twilio => {
+            let payload = twilio_payload(headers, body, url);
+            let expected = BASE64.encode(hmac_sha1(secret, payload.as_bytes()));
+            finish(check, payload.as_bytes(), expected, |received, expected| {
+                received == expected
+            })
+        }
+    }
+}
+
+/// Fills in the expected signature and compares it with the received one, if any.
+fn finish(
+    check: SignatureCheck,
+    payload: &[u8],
+    expected: String,
+    matches: impl Fn(&str, &str) -> bool,
+) -> SignatureCheck {
+    let status = match check.received.as_deref() {
+        Some(received) if matches(received, &expected) => SignatureStatus::Verified,
+        Some(_) => SignatureStatus::Mismatched,
+        None => SignatureStatus::Missing,
+    };
+
+    SignatureCheck {
+        status,
+        expected: Some(expected),
+        signed_payload: Some(printable(payload)),
+        ..check
+    }
+}
+
+/// Twilio signs the full URL followed by every form field, sorted by name, with each
+/// name and value appended without separators. JSON bodies are covered by a
+/// `bodySHA256` query parameter instead, so only the URL is signed.
+fn twilio_payload(headers: &HeaderMap, body: &[u8], url: &str) -> String {
+    let is_form = header_str(headers, header::CONTENT_TYPE.as_str()).is_some_and(|ct| {
+        ct.to_ascii_lowercase()
+            .starts_with("application/x-www-form-urlencoded")
+    });
+
+    let mut payload = url.to_string();
+    if is_form {
+        let mut fields: Vec<(String, String)> = form_urlencoded::parse(body)
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        fields.sort();
+        for (name, value) in fields {
+            payload.push_str(&name);
+            payload.push_str(&value);
+        }
+    }
+    payload
+}
+
+fn hmac_sha256(secret: &[u8], message: &[u8]) -> Vec<u8> {
+    // HMAC takes keys of any length, so this cannot fail
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hmac_sha1(secret: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn strip_sha256_prefix(signature: &str) -> &str {
+    match signature.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("sha256=") => &signature[7..],
+        _ => signature,
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn printable(payload: &[u8]) -> String {
+    let text = String::from_utf8_lossy(payload);
+    match text.char_indices().nth(MAX_SIGNED_PAYLOAD_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn config(provider: SignatureProvider, secret: &str) -> SignatureConfig {
+        SignatureConfig {
+            provider,
+            secret: secret.to_string(),
+            header: None,
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    axum::http::HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn status(
+        config: &SignatureConfig,
+        pairs: &[(&'static str, &str)],
+        body: &str,
+    ) -> SignatureStatus {
+        verify_signature(config, &headers(pairs), body.as_bytes(), "").status
+    }
+
+    #[test]
+    fn github_matches_the_documented_example() {
+        let github = config(SignatureProvider::Github, "It's a Secret to Everybody");
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+        let check = verify_signature(
+            &github,
+            &headers(&[("x-hub-signature-256", signature)]),
+            b"Hello, World!",
+            "",
+        );
+        assert_eq!(check.status, SignatureStatus::Verified);
+        assert_eq!(check.expected.as_deref(), Some(signature));
+        assert_eq!(check.signed_payload.as_deref(), Some("Hello, World!"));
+
+        assert_eq!(
+            status(
+                &github,
+                &[("x-hub-signature-256", signature)],
+                "Hello, World?"
+            ),
+            SignatureStatus::Mismatched
+        );
+        assert_eq!(
+            status(&github, &[], "Hello, World!"),
+            SignatureStatus::Missing
+        );
+    }
+
+    #[test]
+    fn shopify_compares_base64_exactly() {
+        let shopify = config(SignatureProvider::Shopify, "shpss");
+        let signature = "hFJO6VJKe4kBKbdqB4Y0kbIIEQ8QBuyXm5SaI/IGi9o=";
+        assert_eq!(
+            status(
+                &shopify,
+                &[("x-shopify-hmac-sha256", signature)],
+                r#"{"id":1}"#
+            ),
+            SignatureStatus::Verified
+        );
+        assert_eq!(
+            status(
+                &shopify,
+                &[("x-shopify-hmac-sha256", &signature.to_lowercase())],
+                r#"{"id":1}"#
+            ),
+            SignatureStatus::Mismatched
+        );
+    }
+
+    #[test]
+    fn slack_signs_the_timestamp_with_the_body() {
+        let slack = config(SignatureProvider::Slack, "8f742231b10e8888abcd99yyyzzz85a5");
+        let signature = "v0=2abb93eb2a4c84c7a0c6bee974ec55ead7be6c67e71c1f78a343520bc07b0a07";
+        let check = verify_signature(
+            &slack,
+            &headers(&[("x-slack-signature", signature)]),
+            b"token=abc",
+            "",
+        );
+        assert_eq!(
+            check.detail.as_deref(),
+            Some("no X-Slack-Request-Timestamp header")
+        );
+        assert_eq!(check.status, SignatureStatus::Missing);
+
+        let check = verify_signature(
+            &slack,
+            &headers(&[
+                ("x-slack-signature", signature),
+                ("x-slack-request-timestamp", "1531420618"),
+            ]),
+            b"token=abc",
+            "",
+        );
+        assert_eq!(check.status, SignatureStatus::Verified);
+        assert_eq!(
+            check.signed_payload.as_deref(),
+            Some("v0:1531420618:token=abc")
+        );
+        assert_eq!(
+            status(
+                &slack,
+                &[
+                    ("x-slack-signature", signature),
+                    ("x-slack-request-timestamp", "1531420619"),
+                ],
+                "token=abc"
+            ),
+            SignatureStatus::Mismatched
+        );
+    }
+
+    #[test]
+    fn stripe_accepts_any_v1_signature_for_the_timestamp() {
+        let stripe = config(SignatureProvider::Stripe, "whsec_test");
+        let body = r#"{"id":"evt_1"}"#;
+        let valid = "c89214b5b5da833daed6f0b8c5bb6bd58cea9022bd80ccc78230f3942d632925";
+
+        let header = format!("t=1700000000,v1=00ff,v1={}", valid);
+        let check = verify_signature(
+            &stripe,
+            &headers(&[("stripe-signature", &header)]),
+            body.as_bytes(),
+            "",
+        );
+        assert_eq!(check.status, SignatureStatus::Verified);
+        assert_eq!(check.expected, Some(format!("t=1700000000,v1={}", valid)));
+
+        assert_eq!(
+            status(
+                &stripe,
+                &[("stripe-signature", "t=1700000001,v1=00ff")],
+                body
+            ),
+            SignatureStatus::Mismatched
+        );
+        let check = verify_signature(
+            &stripe,
+            &headers(&[("stripe-signature", &format!("v1={}", valid))]),
+            body.as_bytes(),
+            "",
+        );
+        assert_eq!(check.status, SignatureStatus::Missing);
+        assert!(check.detail.unwrap().contains("t= timestamp"));
+    }
+
+    #[test]
+    fn twilio_matches_the_documented_example() {
+        let twilio = config(SignatureProvider::Twilio, "12345");
+        let body = "CallSid=CA1234567890ABCDE&Caller=%2B12349013030&Digits=1234&From=%2B12349013030&To=%2B18005551212";
+        let check = verify_signature(
+            &twilio,
+            &headers(&[
+                ("x-twilio-signature", "0/KCTR6DLpKmkAf8muzZqo1nDgQ="),
+                ("content-type", "application/x-www-form-urlencoded"),
+            ]),
+            body.as_bytes(),
+            "https://mycompany.com/myapp.php?foo=1&bar=2",
+        );
+        assert_eq!(check.status, SignatureStatus::Verified);
+    }
+
+    #[test]
+    fn generic_hmac_takes_hex_or_base64_with_an_optional_prefix() {
+        let generic = SignatureConfig {
+            header: Some("X-Hook-Signature".to_string()),
+            ..config(SignatureProvider::HmacSha256, "secret")
+        };
+        let hex = "03def589620c813f198fd03d7967e292b163ef0435ebf43071ce0e9519763cb7";
+        let base64 = "A971iWIMgT8Zj9A9eWfikrFj7wQ16/Qwcc4OlRl2PLc=";
+        let body = r#"{"id":1}"#;
+
+        for signature in [
+            hex.to_string(),
+            hex.to_uppercase(),
+            format!("sha256={}", hex),
+            base64.to_string(),
+            format!("SHA256={}", base64),
+        ] {
+            let check = verify_signature(
+                &generic,
+                &headers(&[("x-hook-signature", &signature)]),
+                body.as_bytes(),
+                "",
+            );
+            assert_eq!(check.status, SignatureStatus::Verified, "{}", signature);
+            assert_eq!(check.header, "x-hook-signature");
+        }
+
+        let check = verify_signature(
+            &generic,
+            &headers(&[("x-hook-signature", &format!("sha256={}", base64))]),
+            body.as_bytes(),
+            "",
+        );
+        assert_eq!(check.expected, Some(format!("sha256={}", base64)));
+        assert_eq!(
+            status(&generic, &[("x-hook-signature", "sha256=00")], body),
+            SignatureStatus::Mismatched
+        );
+    }
+
+    #[test]
+    fn request_urls_prefer_forwarded_headers() {
+        let uri: Uri = "/api/capture/abc?x=1".parse().unwrap();
+        assert_eq!(
+            request_url("https://hooks.example.com/", &uri, &HeaderMap::new()),
+            "https://hooks.example.com/api/capture/abc?x=1"
+        );
+        assert_eq!(
+            request_url(
+                "https://hooks.example.com",
+                &uri,
+                &headers(&[("host", "internal:3000")])
+            ),
+            "https://internal:3000/api/capture/abc?x=1"
+        );
+        assert_eq!(
+            request_url(
+                "http://localhost",
+                &uri,
+                &headers(&[
+                    ("host", "internal:3000"),
+                    ("x-forwarded-proto", "https"),
+                    ("x-forwarded-host", "public.example.com, proxy.local"),
+                ])
+            ),
+            "https://public.example.com/api/capture/abc?x=1"
+        );
+    }
+
+    #[test]
+    fn long_payloads_are_cut_short() {
+        let body = "é".repeat(MAX_SIGNED_PAYLOAD_CHARS + 10);
+        let check = verify_signature(
+            &config(SignatureProvider::Github, "s"),
+            &HeaderMap::new(),
+            body.as_bytes(),
+            "",
+        );
+        let payload = check.signed_payload.unwrap();
+        assert_eq!(payload.chars().count(), MAX_SIGNED_PAYLOAD_CHARS + 1);
+        assert!(payload.ends_with('…'));
+    }
+}
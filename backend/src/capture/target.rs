@@ -0,0 +1,147 @@
+//! Keeps calls made on behalf of webhooks (forwarding, fan-out and replay) off the
+//! server's own network. Loopback, private and link-local addresses, which include
+//! cloud metadata endpoints such as 169.254.169.254, are refused unless the operator
+//! sets `ALLOW_PRIVATE_TARGETS`.
+//!
+//! IP literals are checked in the URL before sending; host names are checked by the
+//! client's resolver, so a name cannot point somewhere else once it was checked.
+
+use std::error::Error;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// A target outbound calls for webhooks may not reach.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockedTarget(pub String);
+
+impl fmt::Display for BlockedTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is a loopback, private or link-local address", self.0)
+    }
+}
+
+impl Error for BlockedTarget {}
+
+/// Client for outbound calls on behalf of webhooks; redirects are passed back, not
+/// followed, and host names resolving to non-public addresses are refused unless
+/// `allow_private` is set.
+pub fn http_client(allow_private: bool) -> reqwest::Client {
+    let builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+    let builder = if allow_private {
+        builder
+    } else {
+        builder.dns_resolver(std::sync::Arc::new(PublicResolver))
+    };
+    builder.build().expect("Client should build")
+}
+
+/// Refuses URLs whose host is a non-public IP literal or `localhost`. Other host names
+/// are left to the resolver, and unparsable URLs to the client.
+pub fn check_url(url: &str) -> Result<(), BlockedTarget> {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return Ok(());
+    };
+
+    let Some(host) = url.host_str() else {
+        return Ok(());
+    };
+    let public = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(ip) => is_public(ip),
+        Err(_) => {
+            let domain = host.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+    };
+
+    if public {
+        Ok(())
+    } else {
+        Err(BlockedTarget(host.to_string()))
+    }
+}
+
+/// Whether `ip` is reachable from anywhere, rather than only from inside a network.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // 0.0.0.0/8 reaches the host itself, 100.64.0.0/10 is carrier-grade NAT
+    let this_network = first == 0;
+    let shared = first == 100 && (64..128).contains(&second);
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || this_network
+        || shared)
+}
+
+/// Resolves with the system resolver and refuses names with any non-public address.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.iter().any(|addr| !is_public(addr.ip())) {
+                return Err(Box::new(BlockedTarget(name.as_str().to_string())) as _);
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn urls_with_internal_hosts_are_refused() {
+        assert!(check_url("http://169.254.169.254/latest/meta-data").is_err());
+        assert!(check_url("http://[::1]:8080/").is_err());
+        assert!(check_url("http://localhost:3000/").is_err());
+        assert!(check_url("http://api.localhost/").is_err());
+        assert_eq!(check_url("https://example.com/hook"), Ok(()));
+        assert_eq!(check_url("https://1.1.1.1/"), Ok(()));
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::format::{Item, StrftimeItems};
+use hmac::{Hmac, Mac};
+use minijinja::{value::Serde, Environment, ErrorKind, UndefinedBehavior};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::schema::webhook::{
+    BodyEncoding, QueryParam, RequestHeader, ResponseConfig, ResponseHeader, WebhookRequest,
+};
+
+/// The incoming request as seen by response templates and scripts, under the `request` name.
+#[derive(Serialize)]
+pub struct TemplateRequest {
+    pub method: String,
+    pub path: String,
+    /// Raw query string, without the leading `?`
+    pub raw_query: String,
+    /// Query parameters by name; the first value wins for repeated names
+    pub query: BTreeMap<String, String>,
+    /// Headers by lower-cased name; repeated headers are joined with `", "`
+    pub headers: BTreeMap<String, String>,
+    /// Body as text, with invalid UTF-8 replaced
+    pub body: String,
+    /// Body parsed as JSON, when it is JSON
+    pub json: Option<serde_json::Value>,
+}
+
+impl TemplateRequest {
+    pub fn new(
+        method: &str,
+        path: Option<&str>,
+        raw_query: Option<&str>,
+        headers: &[RequestHeader],
+        body: &[u8],
+    ) -> Self {
+        let mut query = BTreeMap::new();
+        for param in QueryParam::parse_all(raw_query.unwrap_or_default()) {
+            query.entry(param.name).or_insert(param.value);
+        }
+
+        let mut header_map: BTreeMap<String, String> = BTreeMap::new();
+        for header in headers.iter() {
+            let value = String::from_utf8_lossy(&header.value_bytes()).into_owned();
+            header_map
+                .entry(header.name.to_ascii_lowercase())
+                .and_modify(|joined| {
+                    joined.push_str(", ");
+                    joined.push_str(&value);
+                })
+                .or_insert(value);
+        }
+
+        TemplateRequest {
+            method: method.to_string(),
+            path: path.unwrap_or("/").to_string(),
+            raw_query: raw_query.unwrap_or_default().to_string(),
+            query,
+            headers: header_map,
+            body: String::from_utf8_lossy(body).into_owned(),
+            json: serde_json::from_slice(body).ok(),
+        }
+    }
+
+    /// Rebuilds the template view of a request that was already captured.
+    pub fn from_stored(request: &WebhookRequest) -> Self {
+        let body = match request.body_encoding {
+            BodyEncoding::Utf8 => request.body.as_bytes().to_vec(),
+            BodyEncoding::Base64 => BASE64.decode(&request.body).unwrap_or_default(),
+        };
+
+        TemplateRequest::new(
+            &request.method,
+            request.path.as_deref(),
+            request.query.as_deref(),
+            &RequestHeader::parse_list(&request.headers),
+            &body,
+        )
+    }
+}
+
+/// Template environment with the helpers available to response templates:
+///
+/// * `uuid()` - a random v4 UUID
+/// * `now()` / `now("%s")` - the current UTC time, RFC 3339 or a strftime format
+/// * `hmac(secret, message)` / `hmac(secret, message, "base64")` - HMAC-SHA256, hex by default
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Chainable);
+
+    env.add_function("uuid", || uuid::Uuid::new_v4().to_string());
+
+    env.add_function("now", |format: Option<String>| {
+        let now = chrono::Utc::now();
+        match format {
+            Some(format) => {
+                // chrono panics while formatting with an invalid specifier
+                check_strftime(&format)
+                    .map_err(|err| minijinja::Error::new(ErrorKind::InvalidOperation, err))?;
+                Ok(now.format(&format).to_string())
+            }
+            None => Ok(now.to_rfc3339()),
+        }
+    });
+
+    env.add_function(
+        "hmac",
+        |secret: String, message: String, encoding: Option<String>| {
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|err| {
+                minijinja::Error::new(ErrorKind::InvalidOperation, err.to_string())
+            })?;
+            mac.update(message.as_bytes());
+            let digest = mac.finalize().into_bytes();
+
+            match encoding.as_deref() {
+                None | Some("hex") => Ok(digest.iter().map(|b| format!("{:02x}", b)).collect()),
+                Some("base64") => Ok(BASE64.encode(digest)),
+                Some(other) => Err(minijinja::Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!("unknown hmac encoding {:?}", other),
+                )),
+            }
+        },
+    );
+
+    env
+}
+
+fn check_strftime(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err(format!("invalid strftime format {:?}", format));
+    }
+    Ok(())
+}
+
+/// Formats passed to `now` as string literals in `source`; formats computed at render
+/// time are only checked then.
+fn literal_now_formats(source: &str) -> Vec<&str> {
+    let mut formats = Vec::new();
+    for (start, call) in source.match_indices("now(") {
+        let preceded_by_name = source[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        if preceded_by_name {
+            continue;
+        }
+
+        let rest = source[start + call.len()..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        if let Some(end) = rest[1..].find(quote) {
+            formats.push(&rest[1..1 + end]);
+        }
+    }
+    formats
+}
+
+/// Compiles every template in `config`, reporting the first syntax error or invalid
+/// `now` format.
+pub fn validate_templates(config: &ResponseConfig) -> Result<(), String> {
+    let env = environment();
+
+    let check = |field: &str, source: &str| {
+        env.template_from_str(source)
+            .map_err(|err| format!("{}: {}", field, err))?;
+        literal_now_formats(source)
+            .into_iter()
+            .try_for_each(check_strftime)
+            .map_err(|err| format!("{}: {}", field, err))
+    };
+
+    check("body", &config.body)?;
+    if let Some(content_type) = &config.content_type {
+        check("content type", content_type)?;
+    }
+    for header in config.headers.iter() {
+        check(&format!("header {}", header.name), &header.value)?;
+    }
+
+    Ok(())
+}
+
+/// Renders the body, content type and header values of `config` for one request.
+pub fn render_response(
+    config: &ResponseConfig,
+    request: &TemplateRequest,
+) -> Result<ResponseConfig, String> {
+    let env = environment();
+    let ctx = minijinja::context! { request => minijinja::Value::from(Serde(request)) };
+
+    let render = |field: &str, source: &str| {
+        env.render_str(source, &ctx)
+            .map_err(|err| format!("{}: {}", field, err))
+    };
+
+    let content_type = config
+        .content_type
+        .as_deref()
+        .map(|content_type| render("content type", content_type))
+        .transpose()?;
+
+    let headers = config
+        .headers
+        .iter()
+        .map(|header| {
+            Ok(ResponseHeader {
+                name: header.name.clone(),
+                value: render(&format!("header {}", header.name), &header.value)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(ResponseConfig {
+        status: config.status,
+        content_type,
+        headers,
+        body: render("body", &config.body)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> TemplateRequest {
+        TemplateRequest::new(
+            "POST",
+            Some("/orders"),
+            Some("id=7&id=8&q=a+b"),
+            &[
+                RequestHeader::from_raw("X-Trace", b"1"),
+                RequestHeader::from_raw("x-trace", b"2"),
+                RequestHeader::from_raw("Content-Type", b"application/json"),
+            ],
+            br#"{"order":{"id":42}}"#,
+        )
+    }
+
+    fn config(body: &str) -> ResponseConfig {
+        ResponseConfig {
+            status: 200,
+            content_type: None,
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    fn render(body: &str) -> Result<String, String> {
+        render_response(&config(body), &request()).map(|rendered| rendered.body)
+    }
+
+    #[test]
+    fn request_view_groups_query_and_headers() {
+        let request = request();
+        assert_eq!(request.path, "/orders");
+        assert_eq!(request.raw_query, "id=7&id=8&q=a+b");
+        assert_eq!(request.query["id"], "7");
+        assert_eq!(request.query["q"], "a b");
+        assert_eq!(request.headers["x-trace"], "1, 2");
+        assert_eq!(request.json.as_ref().unwrap()["order"]["id"], 42);
+
+        let plain = TemplateRequest::new("GET", None, None, &[], b"not json");
+        assert_eq!(plain.path, "/");
+        assert_eq!(plain.json, None);
+    }
+
+    #[test]
+    fn every_field_is_rendered_with_the_request() {
+        let rendered = render_response(
+            &ResponseConfig {
+                status: 201,
+                content_type: Some("{{ request.headers['content-type'] }}".to_string()),
+                headers: vec![ResponseHeader {
+                    name: "x-order".to_string(),
+                    value: "{{ request.json.order.id }}".to_string(),
+                }],
+                body: "{{ request.method }} {{ request.path }} {{ request.query.id }}".to_string(),
+            },
+            &request(),
+        )
+        .unwrap();
+
+        assert_eq!(rendered.status, 201);
+        assert_eq!(rendered.content_type.as_deref(), Some("application/json"));
+        assert_eq!(rendered.headers[0].value, "42");
+        assert_eq!(rendered.body, "POST /orders 7");
+    }
+
+    #[test]
+    fn missing_values_render_empty() {
+        assert_eq!(render("[{{ request.json.missing.deeper }}]").unwrap(), "[]");
+    }
+
+    #[test]
+    fn helpers_generate_ids_times_and_signatures() {
+        let id = render("{{ uuid() }}").unwrap();
+        assert!(uuid::Uuid::parse_str(&id).is_ok(), "{}", id);
+
+        let now = render("{{ now() }}").unwrap();
+        assert!(
+            chrono::DateTime::parse_from_rfc3339(&now).is_ok(),
+            "{}",
+            now
+        );
+        let year = render("{{ now('%Y') }}").unwrap();
+        assert_eq!(year, chrono::Utc::now().format("%Y").to_string());
+
+        let message = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            render(&format!("{{{{ hmac('key', '{}') }}}}", message)).unwrap(),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(
+            render(&format!("{{{{ hmac('key', '{}', 'base64') }}}}", message)).unwrap(),
+            "97yD9DBThCSxMpjmqm+xQ+9NWaFJRhdZl0edvC0aPNg="
+        );
+    }
+
+    #[test]
+    fn helper_errors_name_the_field() {
+        let err = render("{{ hmac('key', 'message', 'base32') }}").unwrap_err();
+        assert!(err.starts_with("body:"), "{}", err);
+        assert!(err.contains("unknown hmac encoding"), "{}", err);
+
+        // Formats computed while rendering are only checked then
+        let err = render("{% set format = '%Q' %}{{ now(format) }}").unwrap_err();
+        assert!(err.contains("invalid strftime format"), "{}", err);
+    }
+
+    #[test]
+    fn validation_reports_syntax_errors_and_bad_now_formats() {
+        assert_eq!(validate_templates(&config("{{ now('%Y-%m-%d') }}")), Ok(()));
+
+        let err = validate_templates(&config("{{ request.method ")).unwrap_err();
+        assert!(err.starts_with("body:"), "{}", err);
+
+        let err = validate_templates(&ResponseConfig {
+            headers: vec![ResponseHeader {
+                name: "x-time".to_string(),
+                value: "{{ now(\"%Q\") }}".to_string(),
+            }],
+            ..config("")
+        })
+        .unwrap_err();
+        assert!(err.starts_with("header x-time:"), "{}", err);
+        assert!(err.contains("invalid strftime format"), "{}", err);
+    }
+
+    #[test]
+    fn only_literal_now_calls_are_collected() {
+        assert_eq!(
+            literal_now_formats("{{ now('%Y') }} {{ now() }} {{ snow('%x') }} {{ now(fmt) }}"),
+            ["%Y"]
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Serves the app while keeping the bytes each HTTP/1 connection receives, so captured
+//! headers can be stored as sent: in wire order and with their original case.
+//!
+//! hyper parses request heads into a `HeaderMap`, which groups values by name and
+//! lowercases names. Each connection's reads are copied into a log, and before a
+//! request is handed to the router its head is found in the log and attached as a
+//! `WireHead` extension. HTTP/2 connections carry no such head and fall back to the
+//! `HeaderMap` order.
+
+use std::convert::Infallible;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{ConnectInfo, FromRequestParts, Request},
+    http::{request::Parts, HeaderMap, Method},
+    Router,
+};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tower::ServiceExt;
+use tracing::{debug, error};
+
+use crate::schema::webhook::RequestHeader;
+
+/// Most headers hyper accepts in one request.
+const MAX_HEADERS: usize = 100;
+
+/// Size at which a connection's log is cut back to its last `LOG_KEEP` bytes.
+const LOG_LIMIT: usize = 1024 * 1024;
+
+/// More than hyper's largest request head, so a head being read is never cut.
+const LOG_KEEP: usize = 512 * 1024;
+
+/// Headers of a request in the order and case they were sent.
+#[derive(Clone, Debug)]
+pub struct WireHead(pub Vec<RequestHeader>);
+
+/// A request's headers as a map for lookups, and as the list to store.
+pub struct CapturedHeaders {
+    pub map: HeaderMap,
+    /// In wire order with names as sent, or grouped by name when the head was not seen
+    pub list: Vec<RequestHeader>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CapturedHeaders
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let list = match parts.extensions.remove::<WireHead>() {
+            Some(WireHead(list)) => list,
+            None => RequestHeader::from_header_map(&parts.headers),
+        };
+        Ok(CapturedHeaders {
+            map: parts.headers.clone(),
+            list,
+        })
+    }
+}
+
+/// Serves `app` on `listener` like `axum::serve` with `ConnectInfo<SocketAddr>`, adding
+/// a `WireHead` to every HTTP/1 request.
+pub async fn serve(listener: TcpListener, app: Router) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                // Usually out of file descriptors; give connections time to close
+                error!("Failed to accept connection: {}", err);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let io = TokioIo::new(LoggedStream {
+            inner: stream,
+            log: log.clone(),
+        });
+        let app = app.clone();
+
+        tokio::spawn(async move {
+            let service =
+                hyper::service::service_fn(move |request: Request<hyper::body::Incoming>| {
+                    let mut request = request.map(Body::new);
+                    request.extensions_mut().insert(ConnectInfo(addr));
+                    if let Some(head) = take_head(&log, request.method(), request.headers()) {
+                        request.extensions_mut().insert(WireHead(head));
+                    }
+                    app.clone().oneshot(request)
+                });
+
+            if let Err(err) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(io, service)
+                .await
+            {
+                debug!("Connection from {} ended: {}", addr, err);
+            }
+        });
+    }
+}
+
+/// Finds the head of the request hyper just parsed in `log` and removes everything up
+/// to its end. The head is the first one after the previous request's that has the
+/// same method and exactly the headers hyper saw, so a body that merely looks like a
+/// request head is skipped.
+fn take_head(
+    log: &Mutex<Vec<u8>>,
+    method: &Method,
+    headers: &HeaderMap,
+) -> Option<Vec<RequestHeader>> {
+    let mut log = log.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let prefix = format!("{} ", method);
+
+    let mut start = 0;
+    while let Some(offset) = find(&log[start..], prefix.as_bytes()) {
+        start += offset;
+        if let Some((end, head)) = parse_head(&log[start..], method, headers) {
+            log.drain(..start + end);
+            return Some(head);
+        }
+        start += 1;
+    }
+    None
+}
+
+fn parse_head(
+    bytes: &[u8],
+    method: &Method,
+    headers: &HeaderMap,
+) -> Option<(usize, Vec<RequestHeader>)> {
+    let mut parsed = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut request = httparse::Request::new(&mut parsed);
+    let httparse::Status::Complete(end) = request.parse(bytes).ok()? else {
+        return None;
+    };
+    if request.method != Some(method.as_str()) || request.headers.len() != headers.len() {
+        return None;
+    }
+
+    let all_seen = request.headers.iter().all(|header| {
+        headers
+            .get_all(header.name)
+            .iter()
+            .any(|value| value.as_bytes() == header.value)
+    });
+    all_seen.then(|| {
+        let list = request
+            .headers
+            .iter()
+            .map(|header| RequestHeader::from_raw(header.name, header.value))
+            .collect();
+        (end, list)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// A TCP stream that copies everything read from it into `log`.
+struct LoggedStream {
+    inner: TcpStream,
+    log: Arc<Mutex<Vec<u8>>>,
+}
+
+impl AsyncRead for LoggedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            let mut log = self
+                .log
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            log.extend_from_slice(&buf.filled()[filled..]);
+            if log.len() > LOG_LIMIT {
+                let cut = log.len() - LOG_KEEP;
+                log.drain(..cut);
+            }
+        }
+        result
+    }
+}
+
+impl AsyncWrite for LoggedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
@@ -9,6 +9,9 @@ pub struct Config {
     pub jwt_secret: String,
     pub sweep_interval_seconds: u64,
     pub webhook_retention_days: u64,
+    pub delivery_poll_interval_ms: u64,
+    /// Lets forwarding, fan-out and replay reach loopback, private and link-local addresses
+    pub allow_private_targets: bool,
 }
 
 // parse env variables and init Config
@@ -32,6 +35,16 @@ pub fn init_config() -> Config {
         .parse()
         .expect("WEBHOOK_RETENTION_DAYS must be a valid integer");
 
+    let delivery_poll_interval_ms = std::env::var("DELIVERY_POLL_INTERVAL_MS")
+        .unwrap_or_else(|_| "1000".to_string())
+        .parse()
+        .expect("DELIVERY_POLL_INTERVAL_MS must be a valid integer");
+
+    let allow_private_targets = std::env::var("ALLOW_PRIVATE_TARGETS")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .expect("ALLOW_PRIVATE_TARGETS must be true or false");
+
     Config {
         admin_email,
         oauth_client_id,
@@ -42,5 +55,7 @@ pub fn init_config() -> Config {
         jwt_secret,
         sweep_interval_seconds,
         webhook_retention_days,
+        delivery_poll_interval_ms,
+        allow_private_targets,
     }
 }
@@ -0,0 +1,112 @@
+use chrono::Utc;
+
+use crate::inference::Shape;
+use crate::model::baseline::StoredBaseline;
+
+pub struct BaselineDao;
+
+impl BaselineDao {
+    /// Baseline of one event type; `event_type` is empty for requests without one.
+    pub async fn get_baseline(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        event_type: &str,
+    ) -> anyhow::Result<Option<StoredBaseline>> {
+        let mut rows = db
+            .query(
+                "SELECT event_type, shape, pinned, updated_at FROM webhook_baselines WHERE webhook_id = ? AND event_type = ?",
+                turso::params![webhook_id, event_type],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(baseline_from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Baselines of every event type, in event type order.
+    pub async fn get_baselines(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+    ) -> anyhow::Result<Vec<StoredBaseline>> {
+        let mut rows = db
+            .query(
+                "SELECT event_type, shape, pinned, updated_at FROM webhook_baselines WHERE webhook_id = ? ORDER BY event_type",
+                turso::params![webhook_id],
+            )
+            .await?;
+
+        let mut baselines = Vec::new();
+        while let Some(row) = rows.next().await? {
+            baselines.push(baseline_from_row(&row)?);
+        }
+
+        Ok(baselines)
+    }
+
+    /// Creates or replaces the baseline of one event type.
+    pub async fn save_baseline(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        event_type: &str,
+        shape: &Shape,
+        pinned: bool,
+    ) -> anyhow::Result<StoredBaseline> {
+        let updated_at = Utc::now().to_rfc3339();
+
+        db.execute(
+            "DELETE FROM webhook_baselines WHERE webhook_id = ? AND event_type = ?",
+            turso::params![webhook_id, event_type],
+        )
+        .await?;
+
+        db.execute(
+            "INSERT INTO webhook_baselines (webhook_id, event_type, shape, pinned, updated_at) VALUES (?, ?, ?, ?, ?)",
+            turso::params![
+                webhook_id,
+                event_type,
+                serde_json::to_string(shape)?,
+                pinned as i64,
+                updated_at.as_str()
+            ],
+        )
+        .await?;
+
+        self.get_baseline(db, webhook_id, event_type)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("baseline not saved"))
+    }
+
+    /// Forgets the baseline of one event type; returns whether there was one.
+    pub async fn delete_baseline(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        event_type: &str,
+    ) -> anyhow::Result<bool> {
+        let deleted = db
+            .execute(
+                "DELETE FROM webhook_baselines WHERE webhook_id = ? AND event_type = ?",
+                turso::params![webhook_id, event_type],
+            )
+            .await?;
+
+        Ok(deleted > 0)
+    }
+}
+
+fn baseline_from_row(row: &turso::Row) -> anyhow::Result<StoredBaseline> {
+    let shape: String = row.get(1)?;
+    let pinned: i64 = row.get(2)?;
+
+    Ok(StoredBaseline {
+        event_type: row.get(0)?,
+        shape: serde_json::from_str(&shape)?,
+        pinned: pinned != 0,
+        updated_at: row.get(3)?,
+    })
+}
@@ -0,0 +1,348 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    capture::target,
+    model::delivery::{AttemptOutcome, DueDelivery},
+    schema::delivery::{retry_backoff_ms, Delivery, DeliveryAttempt, DeliveryStatus, FanOutConfig},
+};
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, request_id, url, status, attempts, attempt_limit, next_attempt_at, created_at, updated_at, last_error";
+
+pub struct DeliveryDao;
+
+impl DeliveryDao {
+    /// Queues a captured request for every fan-out target, due immediately.
+    ///
+    /// Unless `allow_private` is set, targets naming a loopback, private or link-local
+    /// address are recorded as dead right away, with the reason as their last error.
+    pub async fn enqueue_deliveries(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_id: &str,
+        config: &FanOutConfig,
+        allow_private: bool,
+    ) -> anyhow::Result<()> {
+        let now = Utc::now().to_rfc3339();
+
+        for url in config.targets.iter() {
+            let refused = if allow_private {
+                None
+            } else {
+                target::check_url(url).err()
+            };
+            let (status, next_attempt_at) = match refused {
+                Some(_) => (DeliveryStatus::Dead, None),
+                None => (DeliveryStatus::Pending, Some(now.clone())),
+            };
+
+            db.execute(
+                "INSERT INTO deliveries (id, webhook_id, request_id, url, timeout_ms, initial_backoff_ms, status, attempts, attempt_limit, next_attempt_at, created_at, updated_at, last_error) VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?, ?, ?, ?)",
+                turso::params![
+                    Uuid::new_v4().to_string(),
+                    webhook_id,
+                    request_id,
+                    url.as_str(),
+                    config.timeout_ms as i64,
+                    config.initial_backoff_ms as i64,
+                    status.as_str(),
+                    config.max_attempts as i64,
+                    next_attempt_at,
+                    now.clone(),
+                    now.clone(),
+                    refused.map(|blocked| format!("target refused: {}", blocked))
+                ],
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Pending deliveries whose next attempt is due by `now`, oldest first.
+    pub async fn get_due_deliveries(
+        &self,
+        db: turso::Connection,
+        now: DateTime<Utc>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<DueDelivery>> {
+        let mut rows = db
+            .query(
+                "SELECT id, webhook_id, request_id, url, timeout_ms, initial_backoff_ms, attempts, attempt_limit FROM deliveries WHERE status = ? AND next_attempt_at <= ? ORDER BY next_attempt_at LIMIT ?",
+                turso::params![
+                    DeliveryStatus::Pending.as_str(),
+                    now.to_rfc3339(),
+                    limit as i64
+                ],
+            )
+            .await?;
+
+        let mut due = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            due.push(DueDelivery {
+                id: row.get(0)?,
+                webhook_id: row.get(1)?,
+                request_id: row.get(2)?,
+                url: row.get(3)?,
+                timeout_ms: row.get::<i64>(4)? as u64,
+                initial_backoff_ms: row.get::<i64>(5)? as u64,
+                attempts: row.get::<i64>(6)? as u32,
+                attempt_limit: row.get::<i64>(7)? as u32,
+            });
+        }
+
+        Ok(due)
+    }
+
+    /// Logs an attempt and moves the delivery on: delivered on a 2xx answer, dead once
+    /// the attempts run out, otherwise due again after the backoff.
+    pub async fn record_delivery_attempt(
+        &self,
+        db: turso::Connection,
+        delivery: &DueDelivery,
+        outcome: AttemptOutcome,
+    ) -> anyhow::Result<DeliveryStatus> {
+        let attempt = delivery.attempts + 1;
+        let delivered = outcome.status.is_some_and(|s| (200..300).contains(&s));
+        let now = Utc::now();
+
+        let (status, next_attempt_at) = if delivered {
+            (DeliveryStatus::Delivered, None)
+        } else if outcome.refused || attempt >= delivery.attempt_limit {
+            (DeliveryStatus::Dead, None)
+        } else {
+            let backoff = retry_backoff_ms(delivery.initial_backoff_ms, attempt);
+            let next = now + chrono::Duration::milliseconds(backoff as i64);
+            (DeliveryStatus::Pending, Some(next.to_rfc3339()))
+        };
+
+        db.execute(
+            "INSERT INTO delivery_attempts (id, delivery_id, attempt, started_at, status, latency_us, error, response_body) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            turso::params![
+                Uuid::new_v4().to_string(),
+                delivery.id.as_str(),
+                attempt as i64,
+                outcome.started_at,
+                outcome.status.map(|s| s as i64),
+                outcome.latency_us as i64,
+                outcome.error.clone(),
+                outcome.response_body
+            ],
+        )
+        .await?;
+
+        db.execute(
+            "UPDATE deliveries SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ?, updated_at = ? WHERE id = ?",
+            turso::params![
+                status.as_str(),
+                attempt as i64,
+                next_attempt_at,
+                outcome.error,
+                now.to_rfc3339(),
+                delivery.id.as_str()
+            ],
+        )
+        .await?;
+
+        Ok(status)
+    }
+
+    /// Dead-letters a delivery that can no longer be attempted, e.g. because its request
+    /// was swept.
+    pub async fn abandon_delivery(
+        &self,
+        db: turso::Connection,
+        delivery_id: &str,
+        error: &str,
+    ) -> anyhow::Result<()> {
+        db.execute(
+            "UPDATE deliveries SET status = ?, next_attempt_at = NULL, last_error = ?, updated_at = ? WHERE id = ?",
+            turso::params![
+                DeliveryStatus::Dead.as_str(),
+                error,
+                Utc::now().to_rfc3339(),
+                delivery_id
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Lists a webhook's deliveries, newest first, with their attempt logs.
+    pub async fn get_deliveries(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        status: Option<DeliveryStatus>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<Delivery>> {
+        let mut rows = match status {
+            Some(status) => {
+                db.query(
+                    format!(
+                        "SELECT {} FROM deliveries WHERE webhook_id = ? AND status = ? ORDER BY created_at DESC LIMIT ?",
+                        DELIVERY_COLUMNS
+                    ),
+                    turso::params![webhook_id, status.as_str(), limit as i64],
+                )
+                .await?
+            }
+            None => {
+                db.query(
+                    format!(
+                        "SELECT {} FROM deliveries WHERE webhook_id = ? ORDER BY created_at DESC LIMIT ?",
+                        DELIVERY_COLUMNS
+                    ),
+                    turso::params![webhook_id, limit as i64],
+                )
+                .await?
+            }
+        };
+
+        let mut deliveries = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            deliveries.push(delivery_from_row(&row)?);
+        }
+
+        drop(rows);
+
+        for delivery in deliveries.iter_mut() {
+            delivery.attempt_log = self.get_delivery_attempts(db.clone(), &delivery.id).await?;
+        }
+
+        Ok(deliveries)
+    }
+
+    pub async fn get_delivery(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        delivery_id: &str,
+    ) -> anyhow::Result<Delivery> {
+        let mut rows = db
+            .query(
+                format!(
+                    "SELECT {} FROM deliveries WHERE webhook_id = ? AND id = ?",
+                    DELIVERY_COLUMNS
+                ),
+                turso::params![webhook_id, delivery_id],
+            )
+            .await?;
+
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("delivery not found"))?;
+
+        let mut delivery = delivery_from_row(&row)?;
+        drop(rows);
+
+        delivery.attempt_log = self.get_delivery_attempts(db, delivery_id).await?;
+
+        Ok(delivery)
+    }
+
+    async fn get_delivery_attempts(
+        &self,
+        db: turso::Connection,
+        delivery_id: &str,
+    ) -> anyhow::Result<Vec<DeliveryAttempt>> {
+        let mut rows = db
+            .query(
+                "SELECT attempt, started_at, status, latency_us, error, response_body FROM delivery_attempts WHERE delivery_id = ? ORDER BY attempt",
+                turso::params![delivery_id],
+            )
+            .await?;
+
+        let mut attempts = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            attempts.push(DeliveryAttempt {
+                attempt: row.get::<i64>(0)? as u32,
+                started_at: row.get(1)?,
+                status: row.get::<Option<i64>>(2)?.map(|s| s as u16),
+                latency_us: row.get::<i64>(3)? as u64,
+                error: row.get(4)?,
+                response_body: row.get(5)?,
+            });
+        }
+
+        Ok(attempts)
+    }
+
+    /// Puts a dead-lettered delivery back in the queue with a fresh set of attempts.
+    ///
+    /// Returns whether a dead delivery was found.
+    pub async fn retry_delivery(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        delivery_id: &str,
+        extra_attempts: u32,
+    ) -> anyhow::Result<bool> {
+        let now = Utc::now().to_rfc3339();
+
+        let updated = db
+            .execute(
+                "UPDATE deliveries SET status = ?, attempt_limit = attempts + ?, next_attempt_at = ?, updated_at = ? WHERE webhook_id = ? AND id = ? AND status = ?",
+                turso::params![
+                    DeliveryStatus::Pending.as_str(),
+                    extra_attempts as i64,
+                    now.clone(),
+                    now,
+                    webhook_id,
+                    delivery_id,
+                    DeliveryStatus::Dead.as_str()
+                ],
+            )
+            .await?;
+
+        Ok(updated > 0)
+    }
+
+    /// Deletes a delivery and its attempt log. Returns whether it existed.
+    pub async fn discard_delivery(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        delivery_id: &str,
+    ) -> anyhow::Result<bool> {
+        db.execute(
+            "DELETE FROM delivery_attempts WHERE delivery_id IN (SELECT id FROM deliveries WHERE webhook_id = ? AND id = ?)",
+            turso::params![webhook_id, delivery_id],
+        )
+        .await?;
+
+        let deleted = db
+            .execute(
+                "DELETE FROM deliveries WHERE webhook_id = ? AND id = ?",
+                turso::params![webhook_id, delivery_id],
+            )
+            .await?;
+
+        Ok(deleted > 0)
+    }
+}
+
+fn delivery_from_row(row: &turso::Row) -> anyhow::Result<Delivery> {
+    let status: String = row.get(4)?;
+
+    Ok(Delivery {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        request_id: row.get(2)?,
+        url: row.get(3)?,
+        status: DeliveryStatus::parse(&status)
+            .ok_or_else(|| anyhow::anyhow!("unknown delivery status {:?}", status))?,
+        attempts: row.get::<i64>(5)? as u32,
+        attempt_limit: row.get::<i64>(6)? as u32,
+        next_attempt_at: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+        last_error: row.get(10)?,
+        attempt_log: Vec::new(),
+    })
+}
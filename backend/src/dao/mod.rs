@@ -1,2 +1,5 @@
+pub mod baseline;
+pub mod delivery;
+pub mod replay;
 pub mod user;
 pub mod webhook;
@@ -0,0 +1,63 @@
+use crate::schema::replay::Replay;
+
+pub struct ReplayDao;
+
+impl ReplayDao {
+    pub async fn create_replay(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        replay: &Replay,
+    ) -> anyhow::Result<()> {
+        db.execute(
+            "INSERT INTO webhook_request_replays (id, webhook_id, request_id, replayed_at, method, headers, body_override, response) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            turso::params![
+                replay.id.as_str(),
+                webhook_id,
+                replay.request_id.as_str(),
+                replay.replayed_at.as_str(),
+                replay.method.as_str(),
+                serde_json::to_string(&replay.headers)?,
+                replay.body_override.clone(),
+                serde_json::to_string(&replay.response)?
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replays of a captured request, newest first.
+    pub async fn get_replays(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_id: &str,
+    ) -> anyhow::Result<Vec<Replay>> {
+        let mut rows = db
+            .query(
+                "SELECT id, request_id, replayed_at, method, headers, body_override, response FROM webhook_request_replays WHERE webhook_id = ? AND request_id = ? ORDER BY replayed_at DESC",
+                turso::params![webhook_id, request_id],
+            )
+            .await?;
+
+        let mut replays = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            let headers: String = row.get(4)?;
+            let response: String = row.get(6)?;
+
+            replays.push(Replay {
+                id: row.get(0)?,
+                request_id: row.get(1)?,
+                replayed_at: row.get(2)?,
+                method: row.get(3)?,
+                headers: serde_json::from_str(&headers)?,
+                body_override: row.get(5)?,
+                response: serde_json::from_str(&response)?,
+            });
+        }
+
+        Ok(replays)
+    }
+}
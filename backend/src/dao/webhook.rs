@@ -1,22 +1,40 @@
 use anyhow::Ok;
-use chrono::{DateTime, Offset};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::DateTime;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::{
     model::{
         stats::{Stats, UserWebhookStats},
-        webhook::Webhook,
+        webhook::{CaptureSettings, RequestPayload, Webhook},
+    },
+    schema::expectation::{ScanFilter, ScannedRequest},
+    schema::json_schema::SchemaValidation,
+    schema::webhook::{
+        BodyEncoding, BodyFormat, CaptureSetting, CloudEvent, CloudEventMode, NewWebhookRequest,
+        QueryParam, RequestFilter, RequestHeader, SentResponse, SequencePosition, UpstreamResponse,
+        WebhookRequest, WebhookRequestPart,
     },
-    schema::webhook::WebhookRequest,
 };
 
+/// Columns read by `webhook_request_from_row`, in order.
+const WEBHOOK_REQUEST_COLUMNS: &str = "id, webhook_id, method, path, query, headers, CASE WHEN body_raw IS NULL THEN body END, body_raw, received_at, caller_ip, duration_us, content_encoding, body_decoded, body_format, parsed_body, response_status, response_headers, response_body, response_error, tags, script_error, fault, sequence_hit, sequence_step, upstream, local_response, signature, provider, event_type, schema_valid, schema_errors, drift";
+
 pub struct WebhookDao {
     pub domain: String,
 }
 
 impl WebhookDao {
     pub fn construct_url(&self, domain: &str, id: &str) -> String {
-        format!("{}/api/webhooks/{}", domain, id)
+        format!("{}/api/hooks/{}", domain, id)
+    }
+
+    pub fn construct_part_url(&self, webhook_id: &str, request_id: &str, part_id: &str) -> String {
+        format!(
+            "/api/webhooks/{}/requests/{}/parts/{}",
+            webhook_id, request_id, part_id
+        )
     }
 
     pub async fn create_webhook(
@@ -43,6 +61,15 @@ impl WebhookDao {
             created_at,
             last_seen_at: None,
             has_unread: false,
+            response: None,
+            script: None,
+            fault_profile: None,
+            sequence: None,
+            sequence_cursor: 0,
+            forward: None,
+            fanout: None,
+            signature: None,
+            json_schema: None,
         })
     }
 
@@ -59,7 +86,10 @@ impl WebhookDao {
                             SELECT 1 FROM webhook_requests wr
                             WHERE wr.webhook_id = w.id
                             AND (w.last_seen_at IS NULL OR wr.received_at > w.last_seen_at)
-                        ) as has_unread
+                        ) as has_unread,
+                        w.response_config, w.script, w.fault_profile,
+                        w.sequence, w.sequence_cursor, w.forward_target, w.fanout,
+                        w.signature, w.json_schema
                  FROM webhooks w WHERE w.user_id = ? AND w.id = ?",
                 turso::params![user_id, id],
             )
@@ -79,6 +109,15 @@ impl WebhookDao {
         let created_at: String = row.get(2)?;
         let last_seen_at: Option<String> = row.get(3)?;
         let has_unread: bool = row.get::<i64>(4)? != 0;
+        let response = parse_json_setting(row.get(5)?);
+        let script: Option<String> = row.get(6)?;
+        let fault_profile = parse_json_setting(row.get(7)?);
+        let sequence = parse_json_setting(row.get(8)?);
+        let sequence_cursor = row.get::<i64>(9)? as u64;
+        let forward = parse_json_setting(row.get(10)?);
+        let fanout = parse_json_setting(row.get(11)?);
+        let signature = parse_json_setting(row.get(12)?);
+        let json_schema = parse_json_setting(row.get(13)?);
         let url = self.construct_url(&self.domain, &id);
 
         let webhook = Webhook {
@@ -88,6 +127,15 @@ impl WebhookDao {
             created_at,
             last_seen_at,
             has_unread,
+            response,
+            script,
+            fault_profile,
+            sequence,
+            sequence_cursor,
+            forward,
+            fanout,
+            signature,
+            json_schema,
         };
 
         Ok(webhook)
@@ -105,7 +153,10 @@ impl WebhookDao {
                             SELECT 1 FROM webhook_requests wr
                             WHERE wr.webhook_id = w.id
                             AND (w.last_seen_at IS NULL OR wr.received_at > w.last_seen_at)
-                        ) as has_unread
+                        ) as has_unread,
+                        w.response_config, w.script, w.fault_profile,
+                        w.sequence, w.sequence_cursor, w.forward_target, w.fanout,
+                        w.signature, w.json_schema
                  FROM webhooks w WHERE w.user_id = ? ORDER BY w.created_at DESC LIMIT 100",
                 turso::params![user_id],
             )
@@ -119,6 +170,15 @@ impl WebhookDao {
             let created_at: String = row.get(2)?;
             let last_seen_at: Option<String> = row.get(3)?;
             let has_unread: bool = row.get::<i64>(4)? != 0;
+            let response = parse_json_setting(row.get(5)?);
+            let script: Option<String> = row.get(6)?;
+            let fault_profile = parse_json_setting(row.get(7)?);
+            let sequence = parse_json_setting(row.get(8)?);
+            let sequence_cursor = row.get::<i64>(9)? as u64;
+            let forward = parse_json_setting(row.get(10)?);
+            let fanout = parse_json_setting(row.get(11)?);
+            let signature = parse_json_setting(row.get(12)?);
+            let json_schema = parse_json_setting(row.get(13)?);
             let url = self.construct_url(&self.domain, &id);
 
             let webhook = Webhook {
@@ -128,6 +188,15 @@ impl WebhookDao {
                 created_at,
                 last_seen_at,
                 has_unread,
+                response,
+                script,
+                fault_profile,
+                sequence,
+                sequence_cursor,
+                forward,
+                fanout,
+                signature,
+                json_schema,
             };
 
             webhooks.push(webhook);
@@ -157,6 +226,106 @@ impl WebhookDao {
         Ok(user_id)
     }
 
+    /// Stores one capture setting; `None` values restore the default behaviour.
+    pub async fn update_capture_setting(
+        &self,
+        db: turso::Connection,
+        user_id: &str,
+        webhook_id: &str,
+        setting: CaptureSetting,
+    ) -> anyhow::Result<()> {
+        db.execute(
+            format!(
+                "UPDATE webhooks SET {} = ? WHERE id = ? AND user_id = ?",
+                setting.column()
+            ),
+            turso::params![setting.value()?, webhook_id, user_id],
+        )
+        .await?;
+
+        // A new sequence starts from its first step
+        if let CaptureSetting::Sequence(_) = setting {
+            self.reset_sequence_cursor(db, user_id, webhook_id).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn reset_sequence_cursor(
+        &self,
+        db: turso::Connection,
+        user_id: &str,
+        webhook_id: &str,
+    ) -> anyhow::Result<()> {
+        db.execute(
+            "UPDATE webhooks SET sequence_cursor = 0 WHERE id = ? AND user_id = ?",
+            turso::params![webhook_id, user_id],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Claims the next hit of a webhook's response sequence and returns its number.
+    ///
+    /// Callers hold the database lock, so reading and bumping the cursor cannot interleave.
+    pub async fn next_sequence_hit(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+    ) -> anyhow::Result<u64> {
+        let mut rows = db
+            .query(
+                "SELECT sequence_cursor FROM webhooks WHERE id = ?",
+                turso::params![webhook_id],
+            )
+            .await?;
+
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("webhook not found"))?;
+        let hit: i64 = row.get(0)?;
+        drop(rows);
+
+        db.execute(
+            "UPDATE webhooks SET sequence_cursor = ? WHERE id = ?",
+            turso::params![hit + 1, webhook_id],
+        )
+        .await?;
+
+        Ok(hit as u64)
+    }
+
+    /// Loads what the public capture endpoint needs for a webhook, without an owner check.
+    pub async fn get_capture_settings(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+    ) -> anyhow::Result<CaptureSettings> {
+        let mut rows = db
+            .query(
+                "SELECT response_config, script, fault_profile, sequence, forward_target, fanout, signature, json_schema FROM webhooks WHERE id = ?",
+                turso::params![webhook_id],
+            )
+            .await?;
+
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("webhook not found"))?;
+
+        Ok(CaptureSettings {
+            response: parse_json_setting(row.get(0)?),
+            script: row.get(1)?,
+            faults: parse_json_setting(row.get(2)?),
+            sequence: parse_json_setting(row.get(3)?),
+            forward: parse_json_setting(row.get(4)?),
+            fanout: parse_json_setting(row.get(5)?),
+            signature: parse_json_setting(row.get(6)?),
+            json_schema: parse_json_setting(row.get(7)?),
+        })
+    }
+
     pub async fn mark_as_seen(
         &self,
         db: turso::Connection,
@@ -178,6 +347,49 @@ impl WebhookDao {
         user_id: &str,
         id: &str,
     ) -> anyhow::Result<()> {
+        // Queued deliveries would otherwise keep firing for a webhook that is gone
+        db.execute(
+            "DELETE FROM delivery_attempts WHERE delivery_id IN (SELECT d.id FROM deliveries d JOIN webhooks w ON w.id = d.webhook_id WHERE w.user_id = ? AND w.id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM deliveries WHERE webhook_id IN (SELECT id FROM webhooks WHERE user_id = ? AND id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_request_replays WHERE webhook_id IN (SELECT id FROM webhooks WHERE user_id = ? AND id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_baselines WHERE webhook_id IN (SELECT id FROM webhooks WHERE user_id = ? AND id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_request_parts WHERE request_id IN (SELECT wr.id FROM webhook_requests wr JOIN webhooks w ON w.id = wr.webhook_id WHERE w.user_id = ? AND w.id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_request_events WHERE request_id IN (SELECT wr.id FROM webhook_requests wr JOIN webhooks w ON w.id = wr.webhook_id WHERE w.user_id = ? AND w.id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_requests WHERE webhook_id IN (SELECT id FROM webhooks WHERE user_id = ? AND id = ?)",
+            turso::params![user_id, id],
+        )
+        .await?;
+
         db.execute(
             "DELETE FROM webhooks WHERE user_id = ? AND id = ?",
             turso::params![user_id, id],
@@ -190,11 +402,7 @@ impl WebhookDao {
     pub async fn create_webhook_request(
         &self,
         db: turso::Connection,
-        webhook_id: String,
-        headers_json: String,
-        body: String,
-        caller_ip: Option<String>,
-        duration_us: Option<u64>,
+        request: NewWebhookRequest,
     ) -> anyhow::Result<WebhookRequest> {
         let id = uuid::Uuid::new_v4().to_string();
         let received_at = chrono::Utc::now().to_rfc3339();
@@ -203,7 +411,7 @@ impl WebhookDao {
         let mut rows = db
             .query(
                 "SELECT id FROM webhooks WHERE id = ?",
-                turso::params![webhook_id.clone()],
+                turso::params![request.webhook_id.clone()],
             )
             .await?;
 
@@ -211,30 +419,274 @@ impl WebhookDao {
             return Err(anyhow::anyhow!("webhook not found".to_string()));
         }
 
+        let tags = (!request.tags.is_empty())
+            .then(|| serde_json::to_string(&request.tags))
+            .transpose()?;
+
         db.execute(
-            "INSERT INTO webhook_requests (id, webhook_id, method, headers, body, received_at, caller_ip, duration_us) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO webhook_requests (id, webhook_id, method, path, query, headers, body, body_raw, received_at, caller_ip, content_encoding, body_decoded, body_format, parsed_body, tags, script_error, fault, sequence_hit, sequence_step, upstream, signature, provider, event_type, schema_valid, schema_errors, drift) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             turso::params![
                 id.clone(),
-                webhook_id.clone(),
-                "POST",
-                headers_json.clone(),
-                body.clone(),
+                request.webhook_id.clone(),
+                request.method.clone(),
+                request.path.clone(),
+                request.query.clone(),
+                request.headers.clone(),
+                // Only rows captured before body_raw existed keep their body as text
+                "",
+                request.body.clone(),
                 received_at.clone(),
-                caller_ip.clone(),
-                duration_us.map(|d| d as i64)
+                request.caller_ip.clone(),
+                request.content_encoding.clone(),
+                request.decoded_body.clone(),
+                request.body_format.map(|f| f.as_str()),
+                request.parsed_body.as_ref().map(|v| v.to_string()),
+                tags,
+                request.script_error.clone(),
+                request
+                    .fault
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                request.sequence.as_ref().map(|p| p.hit as i64),
+                request.sequence.as_ref().map(|p| p.step as i64),
+                request
+                    .upstream
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                request
+                    .signature
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                request.provider.clone(),
+                request.event_type.clone(),
+                request.schema_validation.as_ref().map(|v| v.valid as i64),
+                request
+                    .schema_validation
+                    .as_ref()
+                    .map(|v| serde_json::to_string(&v.errors))
+                    .transpose()?,
+                (!request.drift.is_empty())
+                    .then(|| serde_json::to_string(&request.drift))
+                    .transpose()?
             ],
         )
         .await?;
 
-        Ok(WebhookRequest {
-            id,
-            webhook_id: webhook_id.clone(),
-            method: "POST".to_string(),
-            headers: headers_json,
-            body,
-            received_at,
-            caller_ip,
-            duration_us,
+        for (position, part) in request.parts.into_iter().enumerate() {
+            db.execute(
+                "INSERT INTO webhook_request_parts (id, request_id, position, name, filename, content_type, headers, data) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                turso::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    id.clone(),
+                    position as i64,
+                    part.name,
+                    part.filename,
+                    part.content_type,
+                    part.headers,
+                    part.data
+                ],
+            )
+            .await?;
+        }
+
+        for event in request.cloud_events {
+            db.execute(
+                "INSERT INTO webhook_request_events (id, request_id, position, mode, specversion, event_id, source, type, subject, time, datacontenttype, dataschema, extensions, data) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                turso::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    id.clone(),
+                    event.position as i64,
+                    event.mode.as_str(),
+                    event.specversion,
+                    event.id,
+                    event.source,
+                    event.event_type,
+                    event.subject,
+                    event.time,
+                    event.datacontenttype,
+                    event.dataschema,
+                    serde_json::to_string(&event.extensions)?,
+                    event.data
+                ],
+            )
+            .await?;
+        }
+
+        self.get_webhook_request(db, &request.webhook_id, &id).await
+    }
+
+    /// Records the reply sent for a captured request, if any.
+    pub async fn finish_webhook_request(
+        &self,
+        db: turso::Connection,
+        request_id: &str,
+        response: Option<&SentResponse>,
+    ) -> anyhow::Result<()> {
+        db.execute(
+            "UPDATE webhook_requests SET response_status = ?, response_headers = ?, response_body = ?, response_error = ? WHERE id = ?",
+            turso::params![
+                response.map(|r| r.status as i64),
+                response.map(|r| serde_json::to_string(&r.headers)).transpose()?,
+                response.map(|r| r.body.clone()),
+                response.and_then(|r| r.error.clone()),
+                request_id
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Records how long handling the request took, once its notifications are out.
+    pub async fn set_request_duration(
+        &self,
+        db: turso::Connection,
+        request_id: &str,
+        duration_us: u64,
+    ) -> anyhow::Result<()> {
+        db.execute(
+            "UPDATE webhook_requests SET duration_us = ? WHERE id = ?",
+            turso::params![duration_us as i64, request_id],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Stores what a local target answered when `hookspy-cli forward` relayed the request.
+    ///
+    /// Returns whether the request was found.
+    pub async fn set_local_response(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_id: &str,
+        response: &UpstreamResponse,
+    ) -> anyhow::Result<bool> {
+        let updated = db
+            .execute(
+                "UPDATE webhook_requests SET local_response = ? WHERE webhook_id = ? AND id = ?",
+                turso::params![serde_json::to_string(response)?, webhook_id, request_id],
+            )
+            .await?;
+        Ok(updated > 0)
+    }
+
+    pub async fn get_webhook_request(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_id: &str,
+    ) -> anyhow::Result<WebhookRequest> {
+        let mut rows = db
+            .query(
+                format!(
+                    "SELECT {} FROM webhook_requests WHERE webhook_id = ? AND id = ?",
+                    WEBHOOK_REQUEST_COLUMNS
+                ),
+                turso::params![webhook_id, request_id],
+            )
+            .await?;
+
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("webhook request not found"))?;
+
+        let mut request = webhook_request_from_row(&row)?;
+
+        request.parts = self
+            .get_webhook_request_parts(db.clone(), webhook_id, &[request.id.clone()])
+            .await?
+            .remove(&request.id)
+            .unwrap_or_default();
+        request.cloud_events = self
+            .get_cloud_events(db, &[request.id.clone()])
+            .await?
+            .remove(&request.id)
+            .unwrap_or_default();
+
+        Ok(request)
+    }
+
+    /// `(received_at, id)` of the newest captured request, the cursor that only
+    /// requests captured later come after.
+    pub async fn get_latest_request_cursor(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+    ) -> anyhow::Result<Option<(String, String)>> {
+        let mut rows = db
+            .query(
+                "SELECT received_at, id FROM webhook_requests WHERE webhook_id = ? ORDER BY received_at DESC, id DESC LIMIT 1",
+                turso::params![webhook_id],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some((row.get(0)?, row.get(1)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Oldest request captured after the one received at `received_at` with id
+    /// `request_id`; requests received at the same instant are ordered by id.
+    pub async fn get_next_webhook_request(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        received_at: &str,
+        request_id: &str,
+    ) -> anyhow::Result<Option<WebhookRequest>> {
+        let mut rows = db
+            .query(
+                "SELECT id FROM webhook_requests WHERE webhook_id = ? AND (received_at > ? OR (received_at = ? AND id > ?)) ORDER BY received_at, id LIMIT 1",
+                turso::params![webhook_id, received_at, received_at, request_id],
+            )
+            .await?;
+
+        let Some(row) = rows.next().await? else {
+            return Ok(None);
+        };
+        let next_id: String = row.get(0)?;
+        drop(rows);
+
+        Ok(Some(
+            self.get_webhook_request(db, webhook_id, &next_id).await?,
+        ))
+    }
+
+    /// Loads a captured request exactly as it was received, for sending it on again.
+    pub async fn get_request_payload(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_id: &str,
+    ) -> anyhow::Result<RequestPayload> {
+        let mut rows = db
+            .query(
+                "SELECT method, path, query, headers, CASE WHEN body_raw IS NULL THEN body END, body_raw FROM webhook_requests WHERE webhook_id = ? AND id = ?",
+                turso::params![webhook_id, request_id],
+            )
+            .await?;
+
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("webhook request not found"))?;
+
+        let headers: String = row.get(3)?;
+        let body: Option<String> = row.get(4)?;
+        let body_raw: Option<Vec<u8>> = row.get(5)?;
+
+        Ok(RequestPayload {
+            method: row.get(0)?,
+            path: row.get(1)?,
+            query: row.get(2)?,
+            headers: RequestHeader::parse_list(&headers),
+            // Rows captured before body_raw existed only have the text column
+            body: body_raw.unwrap_or_else(|| body.unwrap_or_default().into_bytes()),
         })
     }
 
@@ -242,47 +694,400 @@ impl WebhookDao {
         &self,
         db: turso::Connection,
         webhook_id: &str,
+        filter: &RequestFilter,
         offset: u64,
         limit: u64,
     ) -> anyhow::Result<Vec<WebhookRequest>> {
+        let mut conditions = String::new();
+        let mut params: Vec<turso::Value> = vec![webhook_id.into()];
+        if let Some(provider) = filter.provider.as_deref() {
+            conditions.push_str(" AND provider = ?");
+            params.push(provider.into());
+        }
+        if let Some(event_type) = filter.event_type.as_deref() {
+            conditions.push_str(" AND event_type = ?");
+            params.push(event_type.into());
+        }
+        if let Some(valid) = filter.schema_valid {
+            conditions.push_str(" AND schema_valid = ?");
+            params.push((valid as i64).into());
+        }
+        match filter.drifted {
+            Some(true) => conditions.push_str(" AND drift IS NOT NULL"),
+            Some(false) => conditions.push_str(" AND drift IS NULL"),
+            None => {}
+        }
+        params.push((offset as i64).into());
+        params.push((limit as i64).into());
+
         let mut rows = db
             .query(
-                "SELECT id, webhook_id, method, headers, body, received_at, caller_ip, duration_us FROM webhook_requests WHERE webhook_id = ? ORDER BY received_at DESC LIMIT ?, ?",
-                turso::params![webhook_id, offset, limit],
+                format!(
+                    "SELECT {} FROM webhook_requests WHERE webhook_id = ?{} ORDER BY received_at DESC LIMIT ?, ?",
+                    WEBHOOK_REQUEST_COLUMNS, conditions
+                ),
+                params,
             )
             .await?;
 
         let mut requests = Vec::new();
         while let Some(row) = rows.next().await? {
-            let id = row.get(0)?;
-            let webhook_id = row.get(1)?;
-            let method = row.get(2)?;
-            let headers = row.get(3)?;
-            let body = row.get(4)?;
-            let received_at = row.get(5)?;
-            let caller_ip = row.get(6)?;
-            let duration_us: Option<i64> = row.get(7)?;
-
-            requests.push(WebhookRequest {
-                id,
-                webhook_id,
-                method,
-                headers,
-                body,
-                received_at,
-                caller_ip,
-                duration_us: duration_us.map(|d| d as u64),
+            requests.push(webhook_request_from_row(&row)?);
+        }
+
+        let request_ids: Vec<String> = requests.iter().map(|r| r.id.clone()).collect();
+        let mut parts = self
+            .get_webhook_request_parts(db.clone(), webhook_id, &request_ids)
+            .await?;
+
+        let mut cloud_events = self.get_cloud_events(db, &request_ids).await?;
+
+        for request in requests.iter_mut() {
+            request.parts = parts.remove(&request.id).unwrap_or_default();
+            request.cloud_events = cloud_events.remove(&request.id).unwrap_or_default();
+        }
+
+        Ok(requests)
+    }
+
+    /// Lists the requests `filter` selects, newest first, with only the columns an
+    /// expectation checks.
+    pub async fn scan_webhook_requests(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        filter: &ScanFilter,
+        limit: u64,
+    ) -> anyhow::Result<Vec<ScannedRequest>> {
+        let mut route = Vec::new();
+        let mut params: Vec<turso::Value> = vec![webhook_id.into()];
+        if let Some(method) = filter.method.as_deref() {
+            route.push("upper(method) = ?");
+            params.push(method.to_ascii_uppercase().into());
+        }
+        if let Some(path) = filter.path.as_deref() {
+            route.push("ltrim(coalesce(path, ''), '/') = ?");
+            params.push(path.into());
+        }
+
+        let mut conditions = String::new();
+        match (route.is_empty(), filter.other_routes) {
+            (true, true) => return Ok(Vec::new()),
+            (true, false) => {}
+            (false, false) => conditions = format!(" AND {}", route.join(" AND ")),
+            (false, true) => conditions = format!(" AND NOT ({})", route.join(" AND ")),
+        }
+        // Stored times are all UTC RFC 3339, so they compare as text
+        if let Some(since) = filter.since.as_deref() {
+            conditions.push_str(" AND received_at >= ?");
+            params.push(since.into());
+        }
+        params.push((limit as i64).into());
+
+        let mut rows = db
+            .query(
+                format!(
+                    "SELECT id, method, path, received_at, headers, CASE WHEN body_raw IS NULL THEN body END, body_raw, body_decoded FROM webhook_requests WHERE webhook_id = ?{} ORDER BY received_at DESC LIMIT ?",
+                    conditions
+                ),
+                params,
+            )
+            .await?;
+
+        let mut requests = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let body: Option<String> = row.get(5)?;
+            let body_raw: Option<Vec<u8>> = row.get(6)?;
+            let body_decoded: Option<Vec<u8>> = row.get(7)?;
+
+            requests.push(ScannedRequest {
+                id: row.get(0)?,
+                method: row.get(1)?,
+                path: row.get(2)?,
+                received_at: row.get(3)?,
+                headers: row.get(4)?,
+                body: body_decoded
+                    .or(body_raw)
+                    .unwrap_or_else(|| body.unwrap_or_default().into_bytes()),
             });
         }
 
         Ok(requests)
     }
 
+    /// Loads the multipart parts of the given requests, grouped by request id and
+    /// ordered by position. Part bytes are only read for plain form fields.
+    async fn get_webhook_request_parts(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_ids: &[String],
+    ) -> anyhow::Result<HashMap<String, Vec<WebhookRequestPart>>> {
+        let mut parts: HashMap<String, Vec<WebhookRequestPart>> = HashMap::new();
+
+        if request_ids.is_empty() {
+            return Ok(parts);
+        }
+
+        let placeholders = vec!["?"; request_ids.len()].join(", ");
+        let mut rows = db
+            .query(
+                format!(
+                    "SELECT id, request_id, position, name, filename, content_type, headers, length(data), CASE WHEN filename IS NULL THEN data END FROM webhook_request_parts WHERE request_id IN ({}) ORDER BY position",
+                    placeholders
+                ),
+                request_ids.to_vec(),
+            )
+            .await?;
+
+        while let Some(row) = rows.next().await? {
+            let id: String = row.get(0)?;
+            let request_id: String = row.get(1)?;
+            let position: i64 = row.get(2)?;
+            let size: i64 = row.get(7)?;
+            let data: Option<Vec<u8>> = row.get(8)?;
+
+            let part = WebhookRequestPart {
+                download_url: self.construct_part_url(webhook_id, &request_id, &id),
+                id,
+                position: position as u64,
+                name: row.get(3)?,
+                filename: row.get(4)?,
+                content_type: row.get(5)?,
+                headers: row.get(6)?,
+                size: size as u64,
+                text: data.and_then(|data| String::from_utf8(data).ok()),
+            };
+
+            parts.entry(request_id).or_default().push(part);
+        }
+
+        Ok(parts)
+    }
+
+    /// Loads the CloudEvents of the given requests, grouped by request id and ordered
+    /// by position.
+    async fn get_cloud_events(
+        &self,
+        db: turso::Connection,
+        request_ids: &[String],
+    ) -> anyhow::Result<HashMap<String, Vec<CloudEvent>>> {
+        let mut events: HashMap<String, Vec<CloudEvent>> = HashMap::new();
+
+        if request_ids.is_empty() {
+            return Ok(events);
+        }
+
+        let placeholders = vec!["?"; request_ids.len()].join(", ");
+        let mut rows = db
+            .query(
+                format!(
+                    "SELECT request_id, position, mode, specversion, event_id, source, type, subject, time, datacontenttype, dataschema, extensions, data FROM webhook_request_events WHERE request_id IN ({}) ORDER BY position",
+                    placeholders
+                ),
+                request_ids.to_vec(),
+            )
+            .await?;
+
+        while let Some(row) = rows.next().await? {
+            let request_id: String = row.get(0)?;
+            let position: i64 = row.get(1)?;
+            let mode: String = row.get(2)?;
+            let extensions: String = row.get(11)?;
+
+            let Some(mode) = CloudEventMode::parse(&mode) else {
+                continue;
+            };
+
+            let event = CloudEvent {
+                position: position as u64,
+                mode,
+                specversion: row.get(3)?,
+                id: row.get(4)?,
+                source: row.get(5)?,
+                event_type: row.get(6)?,
+                subject: row.get(7)?,
+                time: row.get(8)?,
+                datacontenttype: row.get(9)?,
+                dataschema: row.get(10)?,
+                extensions: serde_json::from_str(&extensions).unwrap_or_default(),
+                data: row.get(12)?,
+            };
+
+            events.entry(request_id).or_default().push(event);
+        }
+
+        Ok(events)
+    }
+
+    /// Returns the filename, content type and raw bytes of a multipart part.
+    pub async fn get_webhook_request_part_data(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        request_id: &str,
+        part_id: &str,
+    ) -> anyhow::Result<(Option<String>, Option<String>, Vec<u8>)> {
+        let mut rows = db
+            .query(
+                "SELECT p.filename, p.content_type, p.data FROM webhook_request_parts p
+                 JOIN webhook_requests r ON r.id = p.request_id
+                 WHERE p.id = ? AND p.request_id = ? AND r.webhook_id = ?",
+                turso::params![part_id, request_id, webhook_id],
+            )
+            .await?;
+
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("part not found"))?;
+
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    }
+
+    /// Parses the JSON bodies of the newest captured requests, optionally only those
+    /// of one event type. Bodies that no longer parse are skipped.
+    pub async fn get_json_bodies(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        event_type: Option<&str>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        match event_type {
+            Some(event_type) => {
+                self.query_json_bodies(
+                    db,
+                    webhook_id,
+                    " AND event_type = ?",
+                    Some(event_type),
+                    limit,
+                )
+                .await
+            }
+            None => {
+                self.query_json_bodies(db, webhook_id, "", None, limit)
+                    .await
+            }
+        }
+    }
+
+    /// Like `get_json_bodies`, but `None` keeps only requests without an event type,
+    /// the ones a baseline stored under no event type applies to.
+    pub async fn get_event_json_bodies(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        event_type: Option<&str>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        match event_type {
+            Some(_) => {
+                self.get_json_bodies(db, webhook_id, event_type, limit)
+                    .await
+            }
+            None => {
+                self.query_json_bodies(db, webhook_id, " AND event_type IS NULL", None, limit)
+                    .await
+            }
+        }
+    }
+
+    async fn query_json_bodies(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+        conditions: &str,
+        event_type: Option<&str>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let mut params: Vec<turso::Value> = vec![webhook_id.into()];
+        if let Some(event_type) = event_type {
+            params.push(event_type.into());
+        }
+        params.push((limit as i64).into());
+
+        let mut rows = db
+            .query(
+                format!(
+                    "SELECT CASE WHEN body_raw IS NULL THEN body END, body_raw, body_decoded FROM webhook_requests WHERE webhook_id = ? AND body_format = 'json'{} ORDER BY received_at DESC LIMIT ?",
+                    conditions
+                ),
+                params,
+            )
+            .await?;
+
+        let mut bodies = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let body: Option<String> = row.get(0)?;
+            let body_raw: Option<Vec<u8>> = row.get(1)?;
+            let body_decoded: Option<Vec<u8>> = row.get(2)?;
+
+            let bytes = body_decoded
+                .or(body_raw)
+                .unwrap_or_else(|| body.unwrap_or_default().into_bytes());
+            bodies.extend(serde_json::from_slice(&bytes).ok());
+        }
+
+        Ok(bodies)
+    }
+
+    /// Distinct event types detected among a webhook's captured requests.
+    pub async fn get_event_types(
+        &self,
+        db: turso::Connection,
+        webhook_id: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut rows = db
+            .query(
+                "SELECT DISTINCT event_type FROM webhook_requests WHERE webhook_id = ? AND event_type IS NOT NULL ORDER BY event_type",
+                turso::params![webhook_id],
+            )
+            .await?;
+
+        let mut event_types = Vec::new();
+        while let Some(row) = rows.next().await? {
+            event_types.push(row.get(0)?);
+        }
+
+        Ok(event_types)
+    }
+
     pub async fn delete_old_webhook_requests(
         &self,
         db: turso::Connection,
         before: DateTime<chrono::Utc>,
     ) -> anyhow::Result<u64> {
+        db.execute(
+            "DELETE FROM webhook_request_parts WHERE request_id IN (SELECT id FROM webhook_requests WHERE received_at < ?)",
+            turso::params![before.to_rfc3339()],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_request_events WHERE request_id IN (SELECT id FROM webhook_requests WHERE received_at < ?)",
+            turso::params![before.to_rfc3339()],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM webhook_request_replays WHERE request_id IN (SELECT id FROM webhook_requests WHERE received_at < ?)",
+            turso::params![before.to_rfc3339()],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM delivery_attempts WHERE delivery_id IN (SELECT d.id FROM deliveries d JOIN webhook_requests wr ON wr.id = d.request_id WHERE wr.received_at < ?)",
+            turso::params![before.to_rfc3339()],
+        )
+        .await?;
+
+        db.execute(
+            "DELETE FROM deliveries WHERE request_id IN (SELECT id FROM webhook_requests WHERE received_at < ?)",
+            turso::params![before.to_rfc3339()],
+        )
+        .await?;
+
         let rows_deleted = db
             .execute(
                 "DELETE FROM webhook_requests WHERE received_at < ?",
@@ -350,3 +1155,110 @@ impl WebhookDao {
         })
     }
 }
+
+fn parse_json_setting<T: serde::de::DeserializeOwned>(raw: Option<String>) -> Option<T> {
+    raw.and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+fn webhook_request_from_row(row: &turso::Row) -> anyhow::Result<WebhookRequest> {
+    let query: Option<String> = row.get(4)?;
+    let body: Option<String> = row.get(6)?;
+    let body_raw: Option<Vec<u8>> = row.get(7)?;
+    let duration_us: Option<i64> = row.get(10)?;
+    let content_encoding: Option<String> = row.get(11)?;
+    let body_decoded: Option<Vec<u8>> = row.get(12)?;
+    let body_format: Option<String> = row.get(13)?;
+    let parsed_body: Option<String> = row.get(14)?;
+    let response_status: Option<i64> = row.get(15)?;
+    let response_headers: Option<String> = row.get(16)?;
+    let tags: Option<String> = row.get(19)?;
+    let sequence_hit: Option<i64> = row.get(22)?;
+    let sequence_step: Option<i64> = row.get(23)?;
+    let schema_valid: Option<i64> = row.get(29)?;
+    let schema_errors: Option<String> = row.get(30)?;
+    let drift: Option<String> = row.get(31)?;
+
+    let query_params = query
+        .as_deref()
+        .map(QueryParam::parse_all)
+        .unwrap_or_default();
+
+    // Rows captured before body_raw existed only have the text column
+    let (body, body_encoding, raw_body) = match (body_decoded, body_raw) {
+        (Some(decoded), raw) => {
+            let (body, body_encoding) = BodyEncoding::encode(&decoded);
+            (body, body_encoding, raw.map(|raw| BASE64.encode(raw)))
+        }
+        (None, Some(raw)) => {
+            let (body, body_encoding) = BodyEncoding::encode(&raw);
+            (body, body_encoding, None)
+        }
+        (None, None) => (body.unwrap_or_default(), BodyEncoding::Utf8, None),
+    };
+
+    Ok(WebhookRequest {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        method: row.get(2)?,
+        path: row.get(3)?,
+        query,
+        query_params,
+        headers: row.get(5)?,
+        body,
+        body_encoding,
+        content_encoding,
+        raw_body,
+        body_format: body_format.as_deref().and_then(BodyFormat::parse),
+        parsed_body: parsed_body.and_then(|v| serde_json::from_str(&v).ok()),
+        received_at: row.get(8)?,
+        caller_ip: row.get(9)?,
+        duration_us: duration_us.map(|d| d as u64),
+        response: match response_status {
+            Some(status) => Some(SentResponse {
+                status: status as u16,
+                headers: response_headers
+                    .and_then(|h| serde_json::from_str(&h).ok())
+                    .unwrap_or_default(),
+                body: row.get::<Option<String>>(17)?.unwrap_or_default(),
+                error: row.get(18)?,
+            }),
+            None => None,
+        },
+        tags: tags
+            .and_then(|t| serde_json::from_str(&t).ok())
+            .unwrap_or_default(),
+        script_error: row.get(20)?,
+        fault: row
+            .get::<Option<String>>(21)?
+            .and_then(|f| serde_json::from_str(&f).ok()),
+        sequence: match (sequence_hit, sequence_step) {
+            (Some(hit), Some(step)) => Some(SequencePosition {
+                hit: hit as u64,
+                step: step as usize,
+            }),
+            _ => None,
+        },
+        upstream: row
+            .get::<Option<String>>(24)?
+            .and_then(|u| serde_json::from_str(&u).ok()),
+        local_response: row
+            .get::<Option<String>>(25)?
+            .and_then(|l| serde_json::from_str(&l).ok()),
+        signature: row
+            .get::<Option<String>>(26)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        provider: row.get(27)?,
+        event_type: row.get(28)?,
+        schema_validation: schema_valid.map(|valid| SchemaValidation {
+            valid: valid != 0,
+            errors: schema_errors
+                .and_then(|e| serde_json::from_str(&e).ok())
+                .unwrap_or_default(),
+        }),
+        drift: drift
+            .and_then(|d| serde_json::from_str(&d).ok())
+            .unwrap_or_default(),
+        parts: Vec::new(),
+        cloud_events: Vec::new(),
+    })
+}
@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+use axum::http::Method;
+use futures_util::future::join_all;
+
+use crate::{
+    app::AppState,
+    capture::forward::{rewrite_headers, send_request, target_url, SendError},
+    dao::{delivery::DeliveryDao, webhook::WebhookDao},
+    model::{
+        delivery::{AttemptOutcome, DueDelivery},
+        webhook::RequestPayload,
+    },
+    schema::{delivery::DeliveryStatus, webhook::RequestHeader},
+};
+
+/// Deliveries attempted per poll; the rest wait for the next one.
+const BATCH_SIZE: u64 = 50;
+
+/// Bytes of a target's response body kept in the attempt log.
+const MAX_LOGGED_BODY: usize = 4096;
+
+pub async fn run_delivery_worker(state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        deliver_due(&state).await;
+    }
+}
+
+async fn deliver_due(state: &AppState) {
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let due = {
+        let db = state.db.lock().await;
+
+        let due = match DeliveryDao
+            .get_due_deliveries(db.clone(), chrono::Utc::now(), BATCH_SIZE)
+            .await
+        {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("Error loading due deliveries: {:?}", e);
+                return;
+            }
+        };
+
+        let mut ready = Vec::new();
+        for delivery in due {
+            match webhook_dao
+                .get_request_payload(db.clone(), &delivery.webhook_id, &delivery.request_id)
+                .await
+            {
+                Ok(payload) => ready.push((delivery, payload)),
+                Err(e) => {
+                    let error = format!("captured request is gone: {}", e);
+                    if let Err(e) = DeliveryDao
+                        .abandon_delivery(db.clone(), &delivery.id, &error)
+                        .await
+                    {
+                        tracing::error!("Error abandoning delivery {}: {:?}", delivery.id, e);
+                    }
+                }
+            }
+        }
+        ready
+    };
+
+    if due.is_empty() {
+        return;
+    }
+
+    // Targets are called without holding the database lock
+    let outcomes = join_all(
+        due.iter()
+            .map(|(delivery, payload)| attempt(state, delivery, payload)),
+    )
+    .await;
+
+    let db = state.db.lock().await;
+
+    for ((delivery, _), outcome) in due.iter().zip(outcomes) {
+        match DeliveryDao
+            .record_delivery_attempt(db.clone(), delivery, outcome)
+            .await
+        {
+            Ok(DeliveryStatus::Dead) => {
+                tracing::warn!(
+                    "Delivery {} to {} dead-lettered after {} attempts",
+                    delivery.id,
+                    delivery.url,
+                    delivery.attempts + 1
+                );
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Error recording delivery {}: {:?}", delivery.id, e),
+        }
+    }
+}
+
+/// Sends a captured request to one fan-out target.
+async fn attempt(
+    state: &AppState,
+    delivery: &DueDelivery,
+    payload: &RequestPayload,
+) -> AttemptOutcome {
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let started = Instant::now();
+
+    let url = target_url(
+        &delivery.url,
+        payload.path.as_deref(),
+        payload.query.as_deref(),
+    );
+    let method = Method::from_bytes(payload.method.as_bytes()).unwrap_or(Method::POST);
+
+    let result = send_request(
+        &state.http_client,
+        method,
+        &url,
+        rewrite_headers(&RequestHeader::to_header_map(&payload.headers), &[], &[]),
+        payload.body.clone(),
+        Duration::from_millis(delivery.timeout_ms),
+        state.config.allow_private_targets,
+    )
+    .await;
+
+    let latency_us = started.elapsed().as_micros() as u64;
+
+    match result {
+        Ok((status, _, body)) => {
+            let logged = &body[..body.len().min(MAX_LOGGED_BODY)];
+            AttemptOutcome {
+                started_at,
+                status: Some(status.as_u16()),
+                latency_us,
+                error: (!status.is_success()).then(|| format!("target answered with {}", status)),
+                response_body: Some(String::from_utf8_lossy(logged).into_owned()),
+                refused: false,
+            }
+        }
+        Err(err) => AttemptOutcome {
+            started_at,
+            status: None,
+            latency_us,
+            error: Some(err.to_string()),
+            response_body: None,
+            refused: matches!(err, SendError::Blocked(_)),
+        },
+    }
+}
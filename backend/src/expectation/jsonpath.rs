@@ -0,0 +1,182 @@
+use serde_json::Value;
+
+/// A JSONPath limited to child access: `$`, `.key`, `['key']`, `[0]` and `[*]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// Every element of an array, or every value of an object
+    Wildcard,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<JsonPath, String> {
+        let invalid = |reason: &str| format!("invalid JSONPath {:?}: {}", path, reason);
+
+        let rest = path
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| invalid("must start with $"))?;
+        let chars: Vec<char> = rest.chars().collect();
+        let mut segments = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    i += 1;
+                    match chars.get(i) {
+                        Some('.') => return Err(invalid("recursive descent is not supported")),
+                        Some('*') => {
+                            segments.push(Segment::Wildcard);
+                            i += 1;
+                        }
+                        _ => {
+                            let start = i;
+                            while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                                i += 1;
+                            }
+                            if start == i {
+                                return Err(invalid("empty key"));
+                            }
+                            segments.push(Segment::Key(chars[start..i].iter().collect()));
+                        }
+                    }
+                }
+                '[' => {
+                    i += 1;
+                    match chars.get(i) {
+                        Some('*') => {
+                            segments.push(Segment::Wildcard);
+                            i += 1;
+                        }
+                        Some(&quote @ ('\'' | '"')) => {
+                            i += 1;
+                            let start = i;
+                            while i < chars.len() && chars[i] != quote {
+                                i += 1;
+                            }
+                            if i == chars.len() {
+                                return Err(invalid("unterminated quoted key"));
+                            }
+                            segments.push(Segment::Key(chars[start..i].iter().collect()));
+                            i += 1;
+                        }
+                        _ => {
+                            let start = i;
+                            while i < chars.len() && chars[i].is_ascii_digit() {
+                                i += 1;
+                            }
+                            let index: String = chars[start..i].iter().collect();
+                            let index = index
+                                .parse()
+                                .map_err(|_| invalid("expected an index, a quoted key or *"))?;
+                            segments.push(Segment::Index(index));
+                        }
+                    }
+                    if chars.get(i) != Some(&']') {
+                        return Err(invalid("expected ]"));
+                    }
+                    i += 1;
+                }
+                _ => return Err(invalid("expected . or [")),
+            }
+        }
+
+        Ok(JsonPath { segments })
+    }
+
+    /// Every node the path selects in `value`; empty when it leads nowhere.
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let mut nodes = vec![value];
+        for segment in self.segments.iter() {
+            nodes = nodes
+                .into_iter()
+                .flat_map(|node| match (segment, node) {
+                    (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+                    (Segment::Index(index), Value::Array(items)) => {
+                        items.get(*index).into_iter().collect()
+                    }
+                    (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                    (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                    _ => Vec::new(),
+                })
+                .collect();
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn select(path: &str, value: &Value) -> Vec<Value> {
+        JsonPath::parse(path)
+            .unwrap()
+            .select(value)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn parses_every_supported_segment() {
+        let path = JsonPath::parse(" $.data['the key'][\"other\"][2].*[*] ").unwrap();
+        assert_eq!(
+            path.segments,
+            [
+                Segment::Key("data".to_string()),
+                Segment::Key("the key".to_string()),
+                Segment::Key("other".to_string()),
+                Segment::Index(2),
+                Segment::Wildcard,
+                Segment::Wildcard,
+            ]
+        );
+        assert_eq!(JsonPath::parse("$").unwrap().segments, []);
+    }
+
+    #[test]
+    fn selects_keys_indexes_and_wildcards() {
+        let value = json!({
+            "event": "order.paid",
+            "items": [{ "sku": "a" }, { "sku": "b" }],
+            "totals": { "net": 10, "tax": 2 },
+        });
+
+        assert_eq!(select("$", &value), std::slice::from_ref(&value));
+        assert_eq!(select("$.event", &value), [json!("order.paid")]);
+        assert_eq!(select("$.items[1].sku", &value), [json!("b")]);
+        assert_eq!(select("$.items[*].sku", &value), [json!("a"), json!("b")]);
+        assert_eq!(select("$.totals.*", &value), [json!(10), json!(2)]);
+        assert!(select("$.items[5]", &value).is_empty());
+        assert!(select("$.event.missing", &value).is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_or_malformed_paths() {
+        for (path, reason) in [
+            ("event", "must start with $"),
+            ("$..event", "recursive descent is not supported"),
+            ("$.", "empty key"),
+            ("$.a..b", "recursive descent is not supported"),
+            ("$['key", "unterminated quoted key"),
+            ("$[x]", "expected an index, a quoted key or *"),
+            ("$[-1]", "expected an index, a quoted key or *"),
+            ("$[0", "expected ]"),
+            ("$[*x]", "expected ]"),
+            ("$event", "expected . or ["),
+        ] {
+            let err = JsonPath::parse(path).unwrap_err();
+            assert!(err.ends_with(reason), "{}: {}", path, err);
+        }
+    }
+}
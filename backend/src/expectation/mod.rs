@@ -0,0 +1,248 @@
+pub mod jsonpath;
+
+use chrono::{DateTime, Utc};
+
+use crate::expectation::jsonpath::JsonPath;
+use crate::schema::expectation::{
+    ExpectationReport, ExpectationRequest, ExpectedCount, NearMiss, RequestSummary, ScanFilter,
+    ScannedRequest,
+};
+use crate::schema::webhook::RequestHeader;
+
+/// Most near misses listed in a report.
+pub const MAX_NEAR_MISSES: usize = 5;
+
+/// An expectation with its JSONPaths parsed, checked against captured requests.
+pub struct Expectation {
+    request: ExpectationRequest,
+    json_paths: Vec<JsonPath>,
+    /// Start of the time window in the UTC form captured times are stored in: `since`,
+    /// or when the expectation was compiled unless `history` is set
+    since: Option<String>,
+}
+
+impl Expectation {
+    pub fn compile(request: ExpectationRequest) -> Result<Expectation, String> {
+        request.validate()?;
+        let json_paths = request
+            .json
+            .iter()
+            .map(|matcher| JsonPath::parse(&matcher.path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let since = match request.since.as_deref() {
+            Some(since) => DateTime::parse_from_rfc3339(since)
+                .ok()
+                .map(|since| since.with_timezone(&Utc).to_rfc3339()),
+            None if request.history => None,
+            None => Some(Utc::now().to_rfc3339()),
+        };
+
+        Ok(Expectation {
+            request,
+            json_paths,
+            since,
+        })
+    }
+
+    pub fn timeout_ms(&self) -> u64 {
+        self.request.timeout_ms
+    }
+
+    /// Requests in the time window with the expected method and path, or with
+    /// `other_routes`, those that miss either of them.
+    pub fn scan_filter(&self, other_routes: bool) -> ScanFilter {
+        ScanFilter {
+            since: self.since.clone(),
+            method: self.request.method.clone(),
+            path: self
+                .request
+                .path
+                .as_deref()
+                .map(|path| path.trim_start_matches('/').to_string()),
+            other_routes,
+        }
+    }
+
+    /// Report on `candidates`, the requests on the expected route, or `None` while
+    /// more requests could still change the outcome before the timeout. `others` are
+    /// requests on other routes, only checked for near misses. Both are newest first,
+    /// as listed by the DAO; `truncated` tells that older candidates were left out.
+    pub fn evaluate(
+        &self,
+        candidates: &[ScannedRequest],
+        others: &[ScannedRequest],
+        truncated: bool,
+        timed_out: bool,
+        waited_ms: u64,
+    ) -> Option<ExpectationReport> {
+        let mut matches = Vec::new();
+        let mut near_misses = Vec::new();
+        for request in candidates.iter().rev() {
+            let failures = self.failures(request);
+            if failures.is_empty() {
+                matches.push(summarize(request));
+            } else {
+                near_misses.push(NearMiss {
+                    request: summarize(request),
+                    failures,
+                });
+            }
+        }
+        near_misses.extend(others.iter().filter_map(|request| {
+            let failures = self.failures(request);
+            (!failures.is_empty()).then(|| NearMiss {
+                request: summarize(request),
+                failures,
+            })
+        }));
+
+        let matched = matches.len() as u64;
+        let passed = match self.request.count {
+            ExpectedCount::AtLeast(n) if matched >= n => true,
+            ExpectedCount::Exactly(n) if matched > n => false,
+            ExpectedCount::None if matched > 0 => false,
+            _ if !timed_out => return None,
+            ExpectedCount::Exactly(n) => matched == n,
+            ExpectedCount::AtLeast(_) => false,
+            ExpectedCount::None => true,
+        };
+
+        // Closest first, ties oldest first
+        near_misses.sort_by(|a, b| {
+            (a.failures.len(), &a.request.received_at)
+                .cmp(&(b.failures.len(), &b.request.received_at))
+        });
+        near_misses.truncate(MAX_NEAR_MISSES);
+
+        let mut message = format!(
+            "expected {}, got {}",
+            self.request.count.describe(),
+            matched
+        );
+        if truncated {
+            message.push_str(&format!(
+                " (only the newest {} requests were checked)",
+                candidates.len()
+            ));
+        }
+
+        Some(ExpectationReport {
+            passed,
+            message,
+            count: self.request.count,
+            matched,
+            waited_ms,
+            matches,
+            near_misses,
+            truncated,
+        })
+    }
+
+    /// Why `request` fails each matcher; empty when it matches them all.
+    fn failures(&self, request: &ScannedRequest) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        if let Some(method) = self.request.method.as_deref() {
+            if !request.method.eq_ignore_ascii_case(method) {
+                failures.push(format!(
+                    "method: expected {}, got {}",
+                    method.to_ascii_uppercase(),
+                    request.method
+                ));
+            }
+        }
+
+        if let Some(path) = self.request.path.as_deref() {
+            let expected = normalize_path(Some(path));
+            let actual = normalize_path(request.path.as_deref());
+            if expected != actual {
+                failures.push(format!("path: expected {}, got {}", expected, actual));
+            }
+        }
+
+        if !self.request.headers.is_empty() {
+            let headers = RequestHeader::parse_list(&request.headers);
+            for matcher in self.request.headers.iter() {
+                let values: Vec<String> = headers
+                    .iter()
+                    .filter(|header| header.name.eq_ignore_ascii_case(&matcher.name))
+                    .map(|header| String::from_utf8_lossy(&header.value_bytes()).into_owned())
+                    .collect();
+                if values.is_empty() {
+                    failures.push(format!("header {}: missing", matcher.name));
+                } else if let Some(expected) = matcher.value.as_deref() {
+                    if !values.iter().any(|value| value == expected) {
+                        failures.push(format!(
+                            "header {}: expected {:?}, got {}",
+                            matcher.name,
+                            expected,
+                            quote_all(&values)
+                        ));
+                    }
+                }
+            }
+        }
+
+        let body = &request.body;
+
+        if !self.request.json.is_empty() {
+            let payload = serde_json::from_slice::<serde_json::Value>(body).ok();
+            for (matcher, path) in self.request.json.iter().zip(self.json_paths.iter()) {
+                let Some(payload) = payload.as_ref() else {
+                    failures.push(format!("{}: body is not JSON", matcher.path));
+                    continue;
+                };
+                let selected = path.select(payload);
+                if selected.is_empty() {
+                    failures.push(format!("{}: not found", matcher.path));
+                } else if let Some(expected) = matcher.equals.as_ref() {
+                    if !selected.contains(&expected) {
+                        failures.push(format!(
+                            "{}: expected {}, got {}",
+                            matcher.path,
+                            expected,
+                            selected
+                                .iter()
+                                .map(|value| value.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(needle) = self.request.body_contains.as_deref() {
+            if !String::from_utf8_lossy(body).contains(needle) {
+                failures.push(format!("body: does not contain {:?}", needle));
+            }
+        }
+
+        failures
+    }
+}
+
+fn summarize(request: &ScannedRequest) -> RequestSummary {
+    RequestSummary {
+        id: request.id.clone(),
+        method: request.method.clone(),
+        path: request.path.clone(),
+        received_at: request.received_at.clone(),
+    }
+}
+
+/// Sub-path with a leading slash, `/` when there is none.
+fn normalize_path(path: Option<&str>) -> String {
+    match path.map(|path| path.trim_start_matches('/')) {
+        Some(path) if !path.is_empty() => format!("/{}", path),
+        _ => "/".to_string(),
+    }
+}
+
+fn quote_all(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| format!("{:?}", value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -15,12 +15,12 @@ use crate::{dao::webhook::WebhookDao, model::stats::Stats};
         (status = 200, description = "Admin statistics", body = Stats),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
-    security(("cookie_auth" = [])),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "admin"
 )]
 pub async fn get_stats(
     State(state): State<AppState>,
-    AdminUser(user): AdminUser,
+    AdminUser(_user): AdminUser,
 ) -> Result<Json<Stats>, ApiError> {
     let db = state.db.lock().await;
 
@@ -0,0 +1,173 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use tracing::error;
+
+use crate::dao::delivery::DeliveryDao;
+use crate::dao::webhook::WebhookDao;
+#[allow(unused_imports)]
+use crate::handlers::error::{ApiError, ErrorBody};
+use crate::model::webhook::Webhook;
+use crate::schema::delivery::{Delivery, DeliveryStatus};
+use crate::{app::AppState, auth::jwt::AuthUser};
+
+#[derive(serde::Deserialize)]
+pub struct DeliveryParams {
+    pub status: Option<DeliveryStatus>,
+    pub size: Option<u64>,
+}
+
+/// List the fan-out deliveries queued for a webhook
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/deliveries",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("status" = Option<DeliveryStatus>, Query, description = "Only deliveries in this state, e.g. `dead` for the dead-letter queue"),
+        ("size" = Option<u64>, Query, description = "Number of deliveries to return (default 100, max 1000)"),
+    ),
+    responses(
+        (status = 200, description = "Deliveries, newest first, with their attempt logs", body = Vec<Delivery>),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn get_webhook_deliveries(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<DeliveryParams>,
+) -> Result<Json<Vec<Delivery>>, ApiError> {
+    let size = params.size.unwrap_or(100).min(1000);
+
+    let db = state.db.lock().await;
+
+    get_owned_webhook(&state, db.clone(), &user.sub, &webhook_id).await?;
+
+    DeliveryDao
+        .get_deliveries(db.clone(), &webhook_id, params.status, size)
+        .await
+        .map(Json)
+        .map_err(|err| {
+            error!("Failed to fetch deliveries: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to fetch deliveries".to_string())
+        })
+}
+
+/// Requeue a dead-lettered delivery
+///
+/// The delivery is attempted again right away and gets the webhook's current
+/// `max_attempts`, or a single attempt when fan-out has since been turned off.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{webhook_id}/deliveries/{delivery_id}/retry",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("delivery_id" = String, Path, description = "Unique delivery identifier"),
+    ),
+    responses(
+        (status = 200, description = "Delivery queued again", body = Delivery),
+        (status = 400, description = "Delivery is not dead-lettered", body = ErrorBody),
+        (status = 404, description = "Webhook or delivery not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn retry_webhook_delivery(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, delivery_id)): Path<(String, String)>,
+) -> Result<Json<Delivery>, ApiError> {
+    let db = state.db.lock().await;
+
+    let webhook = get_owned_webhook(&state, db.clone(), &user.sub, &webhook_id).await?;
+
+    let attempts = webhook.fanout.map_or(1, |fanout| fanout.max_attempts);
+
+    let retried = DeliveryDao
+        .retry_delivery(db.clone(), &webhook_id, &delivery_id, attempts)
+        .await
+        .map_err(|err| {
+            error!("Failed to retry delivery: {} {}", delivery_id, err);
+            ApiError::InternalServerError("failed to retry delivery".to_string())
+        })?;
+
+    let delivery = DeliveryDao
+        .get_delivery(db.clone(), &webhook_id, &delivery_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to get delivery: {} {}", delivery_id, err);
+            ApiError::NotFound("delivery not found".to_string())
+        })?;
+
+    if !retried {
+        return Err(ApiError::BadRequest(
+            "only dead-lettered deliveries can be retried".to_string(),
+        ));
+    }
+
+    Ok(Json(delivery))
+}
+
+/// Discard a delivery and its attempt log
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/deliveries/{delivery_id}",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("delivery_id" = String, Path, description = "Unique delivery identifier"),
+    ),
+    responses(
+        (status = 200, description = "Delivery discarded"),
+        (status = 404, description = "Webhook or delivery not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn discard_webhook_delivery(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, delivery_id)): Path<(String, String)>,
+) -> Result<(), ApiError> {
+    let db = state.db.lock().await;
+
+    get_owned_webhook(&state, db.clone(), &user.sub, &webhook_id).await?;
+
+    let discarded = DeliveryDao
+        .discard_delivery(db.clone(), &webhook_id, &delivery_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to discard delivery: {} {}", delivery_id, err);
+            ApiError::InternalServerError("failed to discard delivery".to_string())
+        })?;
+
+    if !discarded {
+        return Err(ApiError::NotFound("delivery not found".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn get_owned_webhook(
+    state: &AppState,
+    db: turso::Connection,
+    user_id: &str,
+    webhook_id: &str,
+) -> Result<Webhook, ApiError> {
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    webhook_dao
+        .get_webhook(db, user_id, webhook_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to get webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
+        })
+}
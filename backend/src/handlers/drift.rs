@@ -0,0 +1,196 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use tracing::error;
+
+use crate::dao::baseline::BaselineDao;
+use crate::dao::webhook::WebhookDao;
+#[allow(unused_imports)]
+use crate::handlers::error::{ApiError, ErrorBody};
+use crate::inference::Shape;
+use crate::model::webhook::Webhook;
+use crate::schema::drift::{Baseline, BaselineParams, PinBaselineRequest};
+use crate::{app::AppState, auth::jwt::AuthUser};
+
+/// Newest captured JSON bodies a baseline is pinned from when no payloads are given.
+const PIN_SAMPLES: u64 = 200;
+
+/// List the baseline shapes captured JSON bodies are checked against
+///
+/// Each event type gets its own baseline, learned from its first captured payloads
+/// unless pinned. Once learned, every JSON body that adds a field, lacks a field the
+/// baseline always had, or changes a type is flagged with its `drift`.
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/baselines",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Baselines, by event type", body = Vec<Baseline>),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn get_webhook_baselines(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Vec<Baseline>>, ApiError> {
+    let db = state.db.lock().await;
+
+    get_owned_webhook(&state, db.clone(), &user.sub, &webhook_id).await?;
+
+    let baselines = BaselineDao
+        .get_baselines(db.clone(), &webhook_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch baselines: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to fetch baselines".to_string())
+        })?;
+
+    Ok(Json(
+        baselines
+            .iter()
+            .map(|baseline| baseline.to_baseline())
+            .collect(),
+    ))
+}
+
+/// Pin the baseline shape of an event type
+///
+/// The baseline is built from `payloads`, or from the newest captured JSON bodies of
+/// the event type, and is no longer learned from new requests.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/baselines",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = PinBaselineRequest,
+    responses(
+        (status = 200, description = "Baseline pinned", body = Baseline),
+        (status = 400, description = "No payloads to build the baseline from", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn pin_webhook_baseline(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<PinBaselineRequest>,
+) -> Result<Json<Baseline>, ApiError> {
+    let db = state.db.lock().await;
+
+    get_owned_webhook(&state, db.clone(), &user.sub, &webhook_id).await?;
+
+    let event_type = payload.event_type.as_deref();
+    let payloads = match payload.payloads {
+        Some(payloads) => payloads,
+        None => {
+            let webhook_dao = WebhookDao {
+                domain: state.domain.clone(),
+            };
+            webhook_dao
+                .get_event_json_bodies(db.clone(), &webhook_id, event_type, PIN_SAMPLES)
+                .await
+                .map_err(|err| {
+                    error!("Failed to fetch request bodies: {} {}", webhook_id, err);
+                    ApiError::InternalServerError("failed to fetch request bodies".to_string())
+                })?
+        }
+    };
+
+    if payloads.is_empty() {
+        return Err(ApiError::BadRequest(
+            "no JSON payloads to build the baseline from".to_string(),
+        ));
+    }
+
+    BaselineDao
+        .save_baseline(
+            db.clone(),
+            &webhook_id,
+            event_type.unwrap_or_default(),
+            &Shape::infer(&payloads),
+            true,
+        )
+        .await
+        .map(|baseline| Json(baseline.to_baseline()))
+        .map_err(|err| {
+            error!("Failed to save baseline: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to save baseline".to_string())
+        })
+}
+
+/// Forget the baseline shape of an event type
+///
+/// The next captured payloads of the event type are learned as a new baseline.
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/baselines",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("event_type" = Option<String>, Query, description = "Event type of the baseline; omit for requests without one"),
+    ),
+    responses(
+        (status = 200, description = "Baseline removed"),
+        (status = 404, description = "Webhook or baseline not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_baseline(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<BaselineParams>,
+) -> Result<(), ApiError> {
+    let db = state.db.lock().await;
+
+    get_owned_webhook(&state, db.clone(), &user.sub, &webhook_id).await?;
+
+    let deleted = BaselineDao
+        .delete_baseline(
+            db.clone(),
+            &webhook_id,
+            params.event_type.as_deref().unwrap_or_default(),
+        )
+        .await
+        .map_err(|err| {
+            error!("Failed to delete baseline: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to delete baseline".to_string())
+        })?;
+
+    if !deleted {
+        return Err(ApiError::NotFound("baseline not found".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn get_owned_webhook(
+    state: &AppState,
+    db: turso::Connection,
+    user_id: &str,
+    webhook_id: &str,
+) -> Result<Webhook, ApiError> {
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    webhook_dao
+        .get_webhook(db, user_id, webhook_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to get webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
+        })
+}
@@ -0,0 +1,150 @@
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use tokio::sync::mpsc;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::dao::webhook::WebhookDao;
+use crate::expectation::{Expectation, MAX_NEAR_MISSES};
+#[allow(unused_imports)]
+use crate::handlers::error::{ApiError, ErrorBody};
+use crate::schema::expectation::{ExpectationReport, ExpectationRequest, ScannedRequest};
+use crate::{app::AppState, auth::jwt::AuthUser};
+
+/// Newest captured requests on the expected route an expectation is checked against.
+const SCAN_LIMIT: u64 = 1000;
+
+/// Longest wait between two checks; new requests wake the handler sooner.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wait until the webhook's captured requests meet an expectation
+///
+/// Blocks until enough matching requests have arrived, or until `timeout_ms` has
+/// passed, then reports whether the expectation held. Only requests captured after
+/// the call starts count, unless `since` gives an earlier time or `history` lets every
+/// stored request count. `exactly` and `none` counts wait for the full timeout unless
+/// they are broken sooner. Requests that failed only some of the matchers are listed
+/// as near misses, with the reason for each failure. Only the newest 1000 requests
+/// with the expected method and path are checked, and `truncated` is set when there
+/// were more. The response is 200 whether or not the expectation passed.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{webhook_id}/expectations",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = ExpectationRequest,
+    responses(
+        (status = 200, description = "Outcome of the expectation", body = ExpectationReport),
+        (status = 400, description = "Invalid matcher or timeout", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn expect_webhook_requests(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<ExpectationRequest>,
+) -> Result<Json<ExpectationReport>, ApiError> {
+    let expectation = Expectation::compile(payload).map_err(ApiError::BadRequest)?;
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    {
+        let db = state.db.lock().await;
+        webhook_dao
+            .get_webhook(db.clone(), &user.sub, &webhook_id)
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook: {} {}", webhook_id, err);
+                ApiError::NotFound("webhook not found".to_string())
+            })?;
+    }
+
+    // Woken up by every request captured while waiting
+    let session_id = Uuid::new_v4().to_string();
+    let (tx, mut rx) = mpsc::channel(64);
+    {
+        let mut notification = state.notification.lock().await;
+        notification.subscribe(webhook_id.clone(), session_id.clone(), tx);
+    }
+
+    let started = Instant::now();
+    let timeout = Duration::from_millis(expectation.timeout_ms());
+    let report = loop {
+        let (candidates, others) =
+            match fetch_requests(&state, &webhook_dao, &webhook_id, &expectation).await {
+                Ok(requests) => requests,
+                Err(err) => break Err(err),
+            };
+        let truncated = candidates.len() as u64 > SCAN_LIMIT;
+        let candidates = &candidates[..candidates.len().min(SCAN_LIMIT as usize)];
+
+        let elapsed = started.elapsed();
+        let timed_out = elapsed >= timeout;
+        if let Some(report) = expectation.evaluate(
+            candidates,
+            &others,
+            truncated,
+            timed_out,
+            elapsed.as_millis() as u64,
+        ) {
+            break Ok(report);
+        }
+
+        let wait = (timeout - elapsed).min(POLL_INTERVAL);
+        let _ = tokio::time::timeout(wait, rx.recv()).await;
+        while rx.try_recv().is_ok() {}
+    };
+
+    {
+        let mut notification = state.notification.lock().await;
+        notification.unsubscribe(&session_id);
+    }
+
+    report.map(Json)
+}
+
+/// Requests on the expected route, one more than `SCAN_LIMIT` to tell when some were
+/// left out, and the newest few on other routes as near-miss candidates.
+async fn fetch_requests(
+    state: &AppState,
+    webhook_dao: &WebhookDao,
+    webhook_id: &str,
+    expectation: &Expectation,
+) -> Result<(Vec<ScannedRequest>, Vec<ScannedRequest>), ApiError> {
+    let db = state.db.lock().await;
+
+    let candidates = webhook_dao
+        .scan_webhook_requests(
+            db.clone(),
+            webhook_id,
+            &expectation.scan_filter(false),
+            SCAN_LIMIT + 1,
+        )
+        .await;
+    let others = webhook_dao
+        .scan_webhook_requests(
+            db.clone(),
+            webhook_id,
+            &expectation.scan_filter(true),
+            MAX_NEAR_MISSES as u64,
+        )
+        .await;
+
+    candidates
+        .and_then(|candidates| Ok((candidates, others?)))
+        .map_err(|err| {
+            error!("Failed to fetch requests: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to fetch requests".to_string())
+        })
+}
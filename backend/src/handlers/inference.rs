@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use tracing::error;
+
+use crate::dao::webhook::WebhookDao;
+#[allow(unused_imports)]
+use crate::handlers::error::{ApiError, ErrorBody};
+use crate::inference::{export, Shape};
+use crate::schema::inference::{InferSchemaParams, InferredSchema, SchemaFormat};
+use crate::{app::AppState, auth::jwt::AuthUser};
+
+/// Infer a schema from the JSON bodies a webhook captured
+///
+/// The newest JSON bodies are merged into one shape: fields missing from some
+/// payloads are optional, fields that were `null` are nullable, and strings that
+/// keep repeating a few values become enums. The result is rendered as JSON Schema,
+/// Rust serde structs or TypeScript interfaces.
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/schema",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("event_type" = Option<String>, Query, description = "Only infer from requests of this detected event type"),
+        ("format" = Option<SchemaFormat>, Query, description = "Output format (default `json_schema`)"),
+        ("size" = Option<u64>, Query, description = "Number of newest JSON bodies to merge (default 200, max 1000)"),
+    ),
+    responses(
+        (status = 200, description = "Inferred schema", body = InferredSchema),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn infer_webhook_schema(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<InferSchemaParams>,
+) -> Result<Json<InferredSchema>, ApiError> {
+    let size = params.size.unwrap_or(200).min(1000);
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let (bodies, event_types) = {
+        let db = state.db.lock().await;
+
+        webhook_dao
+            .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook: {} {}", webhook_id, err);
+                ApiError::NotFound("webhook not found".to_string())
+            })?;
+
+        let bodies = webhook_dao
+            .get_json_bodies(db.clone(), &webhook_id, params.event_type.as_deref(), size)
+            .await
+            .map_err(|err| {
+                error!("Failed to fetch request bodies: {} {}", webhook_id, err);
+                ApiError::InternalServerError("failed to fetch request bodies".to_string())
+            })?;
+
+        let event_types = webhook_dao
+            .get_event_types(db.clone(), &webhook_id)
+            .await
+            .map_err(|err| {
+                error!("Failed to fetch event types: {} {}", webhook_id, err);
+                ApiError::InternalServerError("failed to fetch event types".to_string())
+            })?;
+
+        (bodies, event_types)
+    };
+
+    let root = params
+        .event_type
+        .as_deref()
+        .map(export::type_name)
+        .unwrap_or_else(|| "Payload".to_string());
+
+    let content = if bodies.is_empty() {
+        String::new()
+    } else {
+        let shape = Shape::infer(&bodies);
+        match params.format {
+            SchemaFormat::JsonSchema => {
+                let schema = export::json_schema(&shape, &root);
+                serde_json::to_string_pretty(&schema).unwrap_or_default()
+            }
+            SchemaFormat::Rust => export::rust_types(&shape, &root),
+            SchemaFormat::Typescript => export::typescript_types(&shape, &root),
+        }
+    };
+
+    Ok(Json(InferredSchema {
+        samples: bodies.len() as u64,
+        event_types,
+        format: params.format,
+        content,
+    }))
+}
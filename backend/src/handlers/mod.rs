@@ -1,5 +1,11 @@
 pub mod admin;
 pub mod auth;
+pub mod delivery;
+pub mod drift;
 pub mod error;
+pub mod expectation;
+pub mod inference;
+pub mod replay;
+pub mod sse;
 pub mod webhook;
 pub mod ws;
@@ -0,0 +1,184 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, Method},
+    response::Json,
+};
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+use crate::capture::forward::{rewrite_headers, send_request, upstream_response};
+use crate::capture::target;
+use crate::dao::replay::ReplayDao;
+use crate::dao::webhook::WebhookDao;
+#[allow(unused_imports)]
+use crate::handlers::error::{ApiError, ErrorBody};
+use crate::schema::replay::{Replay, ReplayRequest};
+use crate::schema::webhook::RequestHeader;
+use crate::{app::AppState, auth::jwt::AuthUser};
+
+/// Send a captured request again, to any URL
+///
+/// The stored method, headers and body are sent to `url` as is, minus connection
+/// headers and `remove_headers`, plus `set_headers`; `body` replaces the stored body,
+/// and then the stored `Content-Encoding` and `Content-Length` are not sent.
+/// Every replay is saved with the target's answer and timing, including failed ones.
+/// Like forwarding, loopback, private and link-local targets are refused unless the
+/// server allows them, and the answer's body is limited to 32 MiB.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{webhook_id}/requests/{request_id}/replay",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("request_id" = String, Path, description = "Unique request identifier"),
+    ),
+    request_body = ReplayRequest,
+    responses(
+        (status = 200, description = "Request replayed; a failed send is recorded in `response.error`", body = Replay),
+        (status = 400, description = "Invalid or private URL, timeout or header", body = ErrorBody),
+        (status = 404, description = "Webhook or request not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn replay_webhook_request(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, request_id)): Path<(String, String)>,
+    Json(payload): Json<ReplayRequest>,
+) -> Result<Json<Replay>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
+    if !state.config.allow_private_targets {
+        target::check_url(&payload.url)
+            .map_err(|blocked| ApiError::BadRequest(format!("target refused: {}", blocked)))?;
+    }
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let stored = {
+        let db = state.db.lock().await;
+
+        webhook_dao
+            .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook: {} {}", webhook_id, err);
+                ApiError::NotFound("webhook not found".to_string())
+            })?;
+
+        webhook_dao
+            .get_request_payload(db.clone(), &webhook_id, &request_id)
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook request: {} {}", request_id, err);
+                ApiError::NotFound("webhook request not found".to_string())
+            })?
+    };
+
+    let method = Method::from_bytes(stored.method.as_bytes())
+        .map_err(|_| ApiError::BadRequest(format!("cannot replay method {:?}", stored.method)))?;
+    let mut received = RequestHeader::to_header_map(&stored.headers);
+    if payload.body.is_some() {
+        // They describe the stored body, not the plain text sent instead
+        received.remove(header::CONTENT_ENCODING);
+        received.remove(header::CONTENT_LENGTH);
+    }
+    let headers = rewrite_headers(&received, &payload.remove_headers, &payload.set_headers);
+    let body = match payload.body.as_ref() {
+        Some(body) => body.clone().into_bytes(),
+        None => stored.body,
+    };
+
+    let replayed_at = chrono::Utc::now().to_rfc3339();
+    let started = Instant::now();
+
+    // The database lock is not held while waiting for the target
+    let result = send_request(
+        &state.http_client,
+        method,
+        &payload.url,
+        headers.clone(),
+        body,
+        Duration::from_millis(payload.timeout_ms),
+        state.config.allow_private_targets,
+    )
+    .await;
+
+    let response = upstream_response(
+        payload.url.clone(),
+        &result,
+        started.elapsed().as_micros() as u64,
+    );
+    if let Some(err) = response.error.as_ref() {
+        warn!("Failed to replay request: {} {}", request_id, err);
+    }
+
+    let replay = Replay {
+        id: uuid::Uuid::new_v4().to_string(),
+        request_id,
+        replayed_at,
+        method: stored.method,
+        headers: RequestHeader::from_header_map(&headers),
+        body_override: payload.body,
+        response,
+    };
+
+    let db = state.db.lock().await;
+
+    ReplayDao
+        .create_replay(db.clone(), &webhook_id, &replay)
+        .await
+        .map_err(|err| {
+            error!("Failed to save replay: {} {}", replay.request_id, err);
+            ApiError::InternalServerError("failed to save replay".to_string())
+        })?;
+
+    Ok(Json(replay))
+}
+
+/// List the replays of a captured request
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/requests/{request_id}/replays",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("request_id" = String, Path, description = "Unique request identifier"),
+    ),
+    responses(
+        (status = 200, description = "Replays, newest first", body = Vec<Replay>),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn get_webhook_request_replays(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, request_id)): Path<(String, String)>,
+) -> Result<Json<Vec<Replay>>, ApiError> {
+    let db = state.db.lock().await;
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    webhook_dao
+        .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+        .await
+        .map_err(|err| {
+            error!("Failed to get webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
+        })?;
+
+    ReplayDao
+        .get_replays(db.clone(), &webhook_id, &request_id)
+        .await
+        .map(Json)
+        .map_err(|err| {
+            error!("Failed to fetch replays: {} {}", request_id, err);
+            ApiError::InternalServerError("failed to fetch replays".to_string())
+        })
+}
@@ -0,0 +1,172 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::{stream, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::auth::jwt::AuthUser;
+use crate::dao::webhook::WebhookDao;
+use crate::handlers::error::ApiError;
+use crate::notification::notification::Event;
+
+/// How often a comment is sent to keep idle connections open through proxies.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Stream a webhook's notifications as Server-Sent Events
+///
+/// Carries the same messages as `/ws/webhooks/{webhook_id}/notifications`, for
+/// networks that block WebSocket upgrades, to the webhook's owner. Each event has an
+/// `id`; a client reconnecting with `Last-Event-ID` is first sent the recent requests
+/// it missed, as currently stored. A `: heartbeat` comment is sent every 15 seconds.
+pub async fn webhook_notifications_sse(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    {
+        let db = state.db.lock().await;
+        webhook_dao
+            .get_webhook(db.clone(), &user.sub, &webhook_id)
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook: {} {}", webhook_id, err);
+                ApiError::NotFound("webhook not found".to_string())
+            })?;
+    }
+
+    let session_id = Uuid::new_v4().to_string();
+    info!("SSE client connected: {} {}", webhook_id, session_id);
+
+    let (tx, rx) = mpsc::channel::<Event>(32);
+
+    // Subscribing under the same lock as reading the history neither loses nor
+    // repeats events published in between
+    let missed = {
+        let mut notification = state.notification.lock().await;
+        notification.subscribe(webhook_id.clone(), session_id.clone(), tx);
+        match last_event_id(&headers) {
+            Some(last_id) => notification.webhook_events_after(&webhook_id, last_id),
+            None => Vec::new(),
+        }
+    };
+
+    // Requests deleted since they were announced are skipped
+    let mut events = Vec::new();
+    if !missed.is_empty() {
+        let db = state.db.lock().await;
+        for missed in missed {
+            let Ok(request) = webhook_dao
+                .get_webhook_request(db.clone(), &webhook_id, &missed.request_id)
+                .await
+            else {
+                continue;
+            };
+            match request.public_json() {
+                Ok(data) => events.push(Event {
+                    id: missed.id,
+                    data,
+                }),
+                Err(err) => error!("Failed to serialize webhook request {}", err),
+            }
+        }
+    }
+
+    Ok(event_stream(state, session_id, events, rx).into_response())
+}
+
+/// Stream the authenticated user's notifications as Server-Sent Events
+///
+/// Carries the same messages as `/ws/user/notifications`, with the same `id`,
+/// `Last-Event-ID` and heartbeat handling as the per-webhook stream.
+pub async fn user_notifications_sse(
+    State(state): State<AppState>,
+    auth_user: Result<AuthUser, (StatusCode, &'static str)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match auth_user {
+        Ok(AuthUser(claims)) => claims.sub,
+        Err((status, msg)) => return (status, msg).into_response(),
+    };
+
+    let session_id = Uuid::new_v4().to_string();
+    info!("User SSE connected: {} {}", user_id, session_id);
+
+    let (tx, rx) = mpsc::channel::<Event>(32);
+
+    let missed = {
+        let mut notification = state.notification.lock().await;
+        notification.subscribe_user(user_id.clone(), session_id.clone(), tx);
+        match last_event_id(&headers) {
+            Some(last_id) => notification.user_events_after(&user_id, last_id),
+            None => Vec::new(),
+        }
+    };
+
+    event_stream(state, session_id, missed, rx).into_response()
+}
+
+fn last_event_id(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Sends `missed`, then every event received on `rx` until the client goes away.
+fn event_stream(
+    state: AppState,
+    session_id: String,
+    missed: Vec<Event>,
+    rx: mpsc::Receiver<Event>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let subscription = Subscription { state, session_id };
+    let live = stream::unfold((rx, subscription), |(mut rx, subscription)| async move {
+        rx.recv().await.map(|event| (event, (rx, subscription)))
+    });
+
+    let events = stream::iter(missed).chain(live).map(|event| {
+        Ok(SseEvent::default()
+            .id(event.id.to_string())
+            .data(event.data))
+    });
+
+    Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(HEARTBEAT_INTERVAL)
+            .text("heartbeat"),
+    )
+}
+
+/// Unsubscribes the session once its stream is dropped, i.e. the client disconnected.
+struct Subscription {
+    state: AppState,
+    session_id: String,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let state = self.state.clone();
+        let session_id = std::mem::take(&mut self.session_id);
+        tokio::spawn(async move {
+            let mut notification = state.notification.lock().await;
+            notification.unsubscribe(&session_id);
+            info!("SSE client disconnected: {}", session_id);
+        });
+    }
+}
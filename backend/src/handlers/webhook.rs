@@ -1,18 +1,45 @@
 use axum::{
-    extract::{ConnectInfo, Path, Query, State},
-    http::HeaderMap,
-    response::Json,
+    body::Bytes,
+    extract::{ConnectInfo, OriginalUri, Path, Query, State},
+    http::{header, Method, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
 use std::net::SocketAddr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use tracing::error;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+use uuid::Uuid;
 
+use crate::capture::body::parse_body;
+use crate::capture::cloudevents::parse_cloud_events;
+use crate::capture::decode::decode_content_encoding;
+use crate::capture::fault::{apply_fault, error_reply, pick_fault};
+use crate::capture::forward::{forward_request, target_url};
+use crate::capture::json_schema::{compile_json_schema, validate_json_body};
+use crate::capture::multipart::parse_multipart;
+use crate::capture::provider::detect_provider;
+use crate::capture::response::build_response;
+use crate::capture::script::{compile_script, run_script, ScriptOutcome};
+use crate::capture::signature::{request_url, verify_signature};
+use crate::capture::template::{render_response, validate_templates, TemplateRequest};
+use crate::capture::wire::CapturedHeaders;
+use crate::dao::baseline::BaselineDao;
+use crate::dao::delivery::DeliveryDao;
 use crate::dao::webhook::WebhookDao;
 #[allow(unused_imports)]
 use crate::handlers::error::{ApiError, ErrorBody};
-use crate::model::webhook::Webhook;
-use crate::schema::webhook::{CreateWebhookRequest, WebhookRequest};
+use crate::inference::drift::diff;
+use crate::model::webhook::{CaptureSettings, Webhook};
+use crate::schema::delivery::FanOutConfig;
+use crate::schema::drift::{DriftEvent, ShapeChange};
+use crate::schema::json_schema::WebhookJsonSchema;
+use crate::schema::signature::SignatureConfig;
+use crate::schema::webhook::{
+    BodyFormat, CaptureSetting, CreateWebhookRequest, FaultAction, FaultProfile, ForwardTarget,
+    NewWebhookRequest, RequestFilter, ResponseConfig, ResponseSequence, SentResponse,
+    SequencePosition, TestScriptRequest, UpstreamResponse, WebhookRequest, WebhookScript,
+};
 use crate::{app::AppState, auth::jwt::AuthUser};
 
 /// Create a new webhook endpoint
@@ -24,7 +51,7 @@ use crate::{app::AppState, auth::jwt::AuthUser};
         (status = 200, description = "Webhook created successfully", body = Webhook),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
-    security(("cookie_auth" = [])),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "webhooks"
 )]
 pub async fn create_webhook(
@@ -61,7 +88,7 @@ pub async fn create_webhook(
         (status = 404, description = "Webhook not found", body = ErrorBody),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
-    security(("cookie_auth" = [])),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "webhooks"
 )]
 pub async fn get_webhook(
@@ -94,7 +121,7 @@ pub async fn get_webhook(
         (status = 200, description = "List of webhooks", body = Vec<Webhook>),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
-    security(("cookie_auth" = [])),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "webhooks"
 )]
 pub async fn list_webhooks(
@@ -130,7 +157,7 @@ pub async fn list_webhooks(
         (status = 404, description = "Webhook not found", body = ErrorBody),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
-    security(("cookie_auth" = [])),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "webhooks"
 )]
 pub async fn delete_webhook(
@@ -160,158 +187,1527 @@ pub async fn delete_webhook(
             ApiError::InternalServerError("failed to delete webhook".to_string())
         })?;
 
+    {
+        let mut notification = state.notification.lock().await;
+        notification.forget_webhook(&webhook_id);
+    }
+
     Ok(())
 }
 
-/// Receive an incoming webhook request
+/// Set the reply sent to callers of a webhook's capture URL
 ///
-/// This is the public endpoint that external services post their webhook payloads to.
-/// No authentication is required.
+/// The body, content type and header values are templates with access to the incoming
+/// request (`request.method`, `request.path`, `request.query`, `request.headers`,
+/// `request.body`, `request.json`) and the `uuid()`, `now()` and `hmac()` helpers.
 #[utoipa::path(
-    post,
-    path = "/api/webhooks/{webhook_id}",
+    put,
+    path = "/api/webhooks/{webhook_id}/response",
     params(
         ("webhook_id" = String, Path, description = "Unique webhook identifier"),
     ),
-    request_body(
-        content = String,
-        description = "Raw webhook payload (any content type)",
-        content_type = "text/plain"
-    ),
+    request_body = ResponseConfig,
     responses(
-        (status = 200, description = "Webhook request recorded", body = WebhookRequest),
+        (status = 200, description = "Response configuration saved", body = Webhook),
+        (status = 400, description = "Invalid status code, header or template", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "webhooks"
 )]
-pub async fn receive_webhook(
+pub async fn update_webhook_response(
     State(state): State<AppState>,
+    AuthUser(user): AuthUser,
     Path(webhook_id): Path<String>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
-    body: String,
-) -> Result<Json<WebhookRequest>, ApiError> {
-    let start = Instant::now();
-
-    // Prefer X-Forwarded-For (set by proxies) over the direct socket address
-    let caller_ip = headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.split(',').next().unwrap_or(s).trim().to_string())
-        .unwrap_or_else(|| addr.ip().to_string());
-
-    // Convert HeaderMap to a serializable HashMap
-    let headers_map: std::collections::HashMap<String, String> = headers
-        .iter()
-        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-        .collect();
-    let headers_json = serde_json::to_string(&headers_map).unwrap_or_else(|_| "{}".to_string());
+    Json(payload): Json<ResponseConfig>,
+) -> Result<Json<Webhook>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
+    validate_templates(&payload).map_err(ApiError::BadRequest)?;
 
-    let db = state.db.lock().await;
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Response(Some(payload)),
+    )
+    .await
+    .map(Json)
+}
 
-    let webhook_dao = WebhookDao {
-        domain: state.domain.clone(),
-    };
+/// Restore the default reply, which echoes the stored request as JSON
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/response",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Response configuration removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn reset_webhook_response(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Response(None),
+    )
+    .await
+    .map(Json)
+}
 
-    let duration_us = start.elapsed().as_micros() as u64;
+/// Attach a Rhai script that runs for every request captured by a webhook
+///
+/// The script sees the incoming request as `request` and may evaluate to a map with
+/// `status`, `content_type`, `headers` and `body` to reply with, `store: false` to drop the
+/// request, and `tags` to label it. Scripts are limited in operations and run time.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/script",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = WebhookScript,
+    responses(
+        (status = 200, description = "Script saved", body = Webhook),
+        (status = 400, description = "Script does not compile", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_script(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<WebhookScript>,
+) -> Result<Json<Webhook>, ApiError> {
+    compile_script(&payload.script).map_err(ApiError::BadRequest)?;
 
-    let webhook_request = webhook_dao
-        .create_webhook_request(
-            db.clone(),
-            webhook_id.clone(),
-            headers_json.clone(),
-            body.clone(),
-            Some(caller_ip),
-            Some(duration_us),
-        )
-        .await
-        .map_err(|err| {
-            error!("Failed to insert webhook request {}", err);
-            ApiError::InternalServerError("failed to save a webhook request".to_string())
-        })?;
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Script(Some(payload.script)),
+    )
+    .await
+    .map(Json)
+}
 
-    let user_id = webhook_dao
-        .get_webhook_user_id(db.clone(), webhook_id.as_str())
+/// Remove the script from a webhook
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/script",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Script removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_script(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(&state, &user.sub, &webhook_id, CaptureSetting::Script(None))
         .await
-        .ok();
+        .map(Json)
+}
 
-    let mut notification = state.notification.lock().await;
+/// Make a webhook's capture URL misbehave on purpose
+///
+/// Every request gets `latency_ms` plus up to `jitter_ms` of delay. On top of that, the
+/// rates pick the share of requests answered with `error_status`, dropped without a
+/// reply, left hanging for `timeout_ms`, or answered with a body trickled out in chunks.
+/// The fault injected for each request is stored with it.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/faults",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = FaultProfile,
+    responses(
+        (status = 200, description = "Fault profile saved", body = Webhook),
+        (status = 400, description = "Rates or delays out of bounds", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_faults(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<FaultProfile>,
+) -> Result<Json<Webhook>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
 
-    let result_json = serde_json::to_string(&webhook_request).map_err(|err| {
-        error!("Failed to serialize webhook request {}", err);
-        ApiError::InternalServerError("failed to save a webhook request".to_string())
-    })?;
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Faults(Some(payload)),
+    )
+    .await
+    .map(Json)
+}
 
-    notification.notify(webhook_id.clone(), result_json).await;
+/// Stop injecting faults into a webhook's replies
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/faults",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Fault profile removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_faults(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(&state, &user.sub, &webhook_id, CaptureSetting::Faults(None))
+        .await
+        .map(Json)
+}
 
-    if let Some(uid) = user_id {
-        notification.notify_user(&uid, &webhook_id).await;
+/// Reply to captured requests with a fixed series of responses
+///
+/// Each stored request takes the next step, whatever the script replies. After the last
+/// step the sequence either keeps sending it (`stick_on_last`) or starts over (`loop`).
+/// Steps are templates like the response configuration, which they take precedence over.
+/// Saving a sequence starts it from the first step.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/sequence",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = ResponseSequence,
+    responses(
+        (status = 200, description = "Sequence saved", body = Webhook),
+        (status = 400, description = "Empty sequence or invalid step", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_sequence(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<ResponseSequence>,
+) -> Result<Json<Webhook>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
+    for (index, step) in payload.steps.iter().enumerate() {
+        validate_templates(step)
+            .map_err(|err| ApiError::BadRequest(format!("step {}: {}", index + 1, err)))?;
     }
 
-    Ok(Json(webhook_request))
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Sequence(Some(payload)),
+    )
+    .await
+    .map(Json)
 }
 
-#[derive(serde::Deserialize)]
-pub struct PaginationParams {
-    pub size: Option<u64>,
-    pub page: Option<u64>,
+/// Remove the response sequence from a webhook
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/sequence",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Sequence removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_sequence(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Sequence(None),
+    )
+    .await
+    .map(Json)
 }
 
-/// List all requests received by a webhook
+/// Start a webhook's response sequence over from the first step
 #[utoipa::path(
-    get,
-    path = "/api/webhooks/{webhook_id}/requests",
+    post,
+    path = "/api/webhooks/{webhook_id}/sequence/reset",
     params(
         ("webhook_id" = String, Path, description = "Unique webhook identifier"),
-        ("size" = Option<u64>, Query, description = "Number of requests per page (default 100, max 1000)"),
-        ("page" = Option<u64>, Query, description = "Page number for pagination (default 1)"),
     ),
     responses(
-        (status = 200, description = "List of recorded webhook requests", body = Vec<WebhookRequest>),
+        (status = 200, description = "Sequence cursor reset", body = Webhook),
         (status = 404, description = "Webhook not found", body = ErrorBody),
         (status = 500, description = "Internal server error", body = ErrorBody),
     ),
-    security(("cookie_auth" = [])),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
     tag = "webhooks"
 )]
-pub async fn get_webhook_requests(
+pub async fn reset_webhook_sequence(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Path(webhook_id): Path<String>,
-    Query(params): Query<PaginationParams>,
-) -> Result<Json<Vec<WebhookRequest>>, ApiError> {
-    let page_size = params.size.unwrap_or(100).min(1000);
-    let page_number = params.page.unwrap_or(1).max(1);
-    let offset = (page_number - 1) * page_size;
-
+) -> Result<Json<Webhook>, ApiError> {
     let db = state.db.lock().await;
 
     let webhook_dao = WebhookDao {
         domain: state.domain.clone(),
     };
 
-    let _webhook = webhook_dao
+    webhook_dao
         .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
         .await
         .map_err(|err| {
-            error!("Failed to query webhook: {} {}", webhook_id, err);
-            ApiError::InternalServerError("failed to get a webhook".to_string())
+            error!("Failed to get webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
         })?;
 
-    let requests = webhook_dao
-        .get_webhook_requests(db.clone(), webhook_id.as_str(), offset, page_size)
+    webhook_dao
+        .reset_sequence_cursor(db.clone(), user.sub.as_str(), webhook_id.as_str())
         .await
         .map_err(|err| {
-            error!("Failed to fetch webhook requests {}", err);
-            ApiError::InternalServerError("failed to fetch webhook requests".to_string())
+            error!("Failed to reset sequence: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to reset the sequence".to_string())
         })?;
 
-    if let Err(err) = webhook_dao
-        .mark_as_seen(db.clone(), user.sub.as_str(), webhook_id.as_str())
+    webhook_dao
+        .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
         .await
-    {
-        error!("Failed to mark webhook as seen: {} {}", webhook_id, err);
-    }
+        .map(Json)
+        .map_err(|err| {
+            error!("Failed to query webhook: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to get a webhook".to_string())
+        })
+}
 
-    Ok(Json(requests))
+/// Relay requests captured by a webhook to an upstream URL
+///
+/// Captured requests are still stored, and are forwarded with the same method, body and
+/// headers, minus connection headers and `remove_headers`, plus `set_headers`. The
+/// captured sub-path and query string are appended to `url`. The caller gets the
+/// upstream's answer, or a 502 (504 after `timeout_ms`) when there is none. A reply
+/// returned by the script is sent instead and the request is then not forwarded.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/forward",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = ForwardTarget,
+    responses(
+        (status = 200, description = "Forward target saved", body = Webhook),
+        (status = 400, description = "Invalid URL, timeout or header", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_forward(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<ForwardTarget>,
+) -> Result<Json<Webhook>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
+
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Forward(Some(payload)),
+    )
+    .await
+    .map(Json)
+}
+
+/// Stop relaying a webhook's captured requests
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/forward",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Forward target removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_forward(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Forward(None),
+    )
+    .await
+    .map(Json)
+}
+
+/// Deliver requests captured by a webhook to a set of targets in the background
+///
+/// Every captured request is queued once per target and sent with the same method,
+/// body and headers, the captured sub-path and query string appended to the target URL.
+/// A 2xx answer marks a delivery as delivered. Anything else is retried after
+/// `initial_backoff_ms`, doubling each time, until `max_attempts` is reached and the
+/// delivery is dead-lettered. The caller's reply is not affected.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/fanout",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = FanOutConfig,
+    responses(
+        (status = 200, description = "Fan-out targets saved", body = Webhook),
+        (status = 400, description = "Invalid target URL or retry policy", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_fanout(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<FanOutConfig>,
+) -> Result<Json<Webhook>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
+
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::FanOut(Some(payload)),
+    )
+    .await
+    .map(Json)
+}
+
+/// Stop queueing deliveries for a webhook's captured requests
+///
+/// Deliveries already queued are still attempted.
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/fanout",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Fan-out targets removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_fanout(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(&state, &user.sub, &webhook_id, CaptureSetting::FanOut(None))
+        .await
+        .map(Json)
+}
+
+/// Verify the signature of requests captured by a webhook
+///
+/// Every captured request is checked against `secret` the way `provider` signs its
+/// webhooks, and stored as verified, mismatched or missing together with the expected
+/// signature. The caller's reply is not affected.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/signature",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = SignatureConfig,
+    responses(
+        (status = 200, description = "Signing secret saved", body = Webhook),
+        (status = 400, description = "Empty secret or invalid header name", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_signature(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<SignatureConfig>,
+) -> Result<Json<Webhook>, ApiError> {
+    payload.validate().map_err(ApiError::BadRequest)?;
+
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Signature(Some(payload)),
+    )
+    .await
+    .map(Json)
+}
+
+/// Stop verifying the signature of a webhook's captured requests
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/signature",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "Signing secret removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_signature(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::Signature(None),
+    )
+    .await
+    .map(Json)
+}
+
+/// Validate the JSON bodies captured by a webhook against a JSON Schema
+///
+/// Every captured JSON body is checked against `schema`, and the outcome is stored with
+/// the request together with the path of each failing value. The draft is taken from
+/// `$schema`; remote references are not fetched. The caller's reply is not affected.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/json-schema",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = WebhookJsonSchema,
+    responses(
+        (status = 200, description = "JSON Schema saved", body = Webhook),
+        (status = 400, description = "Not a valid JSON Schema", body = ErrorBody),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_json_schema(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<WebhookJsonSchema>,
+) -> Result<Json<Webhook>, ApiError> {
+    compile_json_schema(&payload.schema).map_err(ApiError::BadRequest)?;
+
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::JsonSchema(Some(payload.schema)),
+    )
+    .await
+    .map(Json)
+}
+
+/// Stop validating a webhook's captured requests against a JSON Schema
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}/json-schema",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    responses(
+        (status = 200, description = "JSON Schema removed", body = Webhook),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_json_schema(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<Webhook>, ApiError> {
+    save_capture_setting(
+        &state,
+        &user.sub,
+        &webhook_id,
+        CaptureSetting::JsonSchema(None),
+    )
+    .await
+    .map(Json)
+}
+
+/// Run a script against a captured request without storing or replying anything
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{webhook_id}/script/test",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+    ),
+    request_body = TestScriptRequest,
+    responses(
+        (status = 200, description = "What the script decided, including errors and logs", body = ScriptOutcome),
+        (status = 400, description = "No script given and none saved", body = ErrorBody),
+        (status = 404, description = "Webhook or request not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn test_webhook_script(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<TestScriptRequest>,
+) -> Result<Json<ScriptOutcome>, ApiError> {
+    let (script, request) = {
+        let db = state.db.lock().await;
+
+        let webhook_dao = WebhookDao {
+            domain: state.domain.clone(),
+        };
+
+        let webhook = webhook_dao
+            .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook: {} {}", webhook_id, err);
+                ApiError::NotFound("webhook not found".to_string())
+            })?;
+
+        let request = webhook_dao
+            .get_webhook_request(db.clone(), &webhook_id, &payload.request_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    "Failed to get webhook request: {} {}",
+                    payload.request_id, err
+                );
+                ApiError::NotFound("webhook request not found".to_string())
+            })?;
+
+        let script = payload
+            .script
+            .or(webhook.script)
+            .ok_or_else(|| ApiError::BadRequest("webhook has no script".to_string()))?;
+
+        (script, request)
+    };
+
+    let template_request = TemplateRequest::from_stored(&request);
+    let outcome = tokio::task::spawn_blocking(move || run_script(&script, &template_request))
+        .await
+        .map_err(|err| {
+            error!("Webhook script panicked: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to run webhook script".to_string())
+        })?;
+
+    Ok(Json(outcome))
+}
+
+async fn save_capture_setting(
+    state: &AppState,
+    user_id: &str,
+    webhook_id: &str,
+    setting: CaptureSetting,
+) -> Result<Webhook, ApiError> {
+    let db = state.db.lock().await;
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    webhook_dao
+        .get_webhook(db.clone(), user_id, webhook_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to get webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
+        })?;
+
+    let column = setting.column();
+    webhook_dao
+        .update_capture_setting(db.clone(), user_id, webhook_id, setting)
+        .await
+        .map_err(|err| {
+            error!("Failed to save {}: {} {}", column, webhook_id, err);
+            ApiError::InternalServerError("failed to save webhook settings".to_string())
+        })?;
+
+    webhook_dao
+        .get_webhook(db.clone(), user_id, webhook_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to query webhook: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to get a webhook".to_string())
+        })
+}
+
+#[derive(serde::Deserialize)]
+pub struct CapturePath {
+    pub webhook_id: String,
+    pub path: Option<String>,
+}
+
+/// Receive an incoming webhook request
+///
+/// This is the public endpoint that external services send their webhook payloads to.
+/// Any HTTP method is accepted and recorded, as is any sub-path or query string the
+/// sender appends to the webhook URL. No authentication is required. The reply is the
+/// stored request as JSON unless the webhook has a response configuration; its signature
+/// check only carries the status, header and detail, never the expected signature.
+/// Documented as POST; the same operation is listed for every other method.
+#[utoipa::path(
+    post,
+    path = "/api/hooks/{webhook_id}/{path}",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("path" = Option<String>, Path, description = "Optional sub-path chosen by the sender"),
+    ),
+    request_body(
+        content = Vec<u8>,
+        description = "Raw webhook payload (any content type, binary safe)",
+        content_type = "application/octet-stream"
+    ),
+    responses(
+        (status = 200, description = "Webhook request recorded", body = WebhookRequest),
+        (status = "default", description = "Reply set by the webhook's response configuration"),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    tag = "webhooks"
+)]
+pub async fn receive_webhook(
+    State(state): State<AppState>,
+    Path(CapturePath { webhook_id, path }): Path<CapturePath>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    OriginalUri(original_uri): OriginalUri,
+    method: Method,
+    CapturedHeaders {
+        map: headers,
+        list: header_list,
+    }: CapturedHeaders,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let start = Instant::now();
+    let query = original_uri.query().map(str::to_string);
+
+    // Prefer X-Forwarded-For (set by proxies) over the direct socket address
+    let caller_ip = headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.split(',').next().unwrap_or(s).trim().to_string())
+        .unwrap_or_else(|| addr.ip().to_string());
+
+    // Every header value, including repeats, in the order and case they were sent
+    let headers_json = serde_json::to_string(&header_list).unwrap_or_else(|_| "[]".to_string());
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    // Decompress by Content-Encoding; parsing works on the decoded bytes
+    let content_encoding = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok());
+    let decoded_body = content_encoding.and_then(|ce| decode_content_encoding(ce, &body));
+    let readable_body = decoded_body
+        .clone()
+        .map(Bytes::from)
+        .unwrap_or(body.clone());
+
+    let parts = parse_multipart(content_type, &readable_body).await;
+    let cloud_events = parse_cloud_events(&headers, &readable_body);
+    let (body_format, parsed_body) = parse_body(content_type, &readable_body);
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let settings = {
+        let db = state.db.lock().await;
+        webhook_dao
+            .get_capture_settings(db.clone(), webhook_id.as_str())
+            .await
+            .unwrap_or_else(|err| {
+                error!("Failed to load capture settings: {} {}", webhook_id, err);
+                CaptureSettings::default()
+            })
+    };
+
+    let path = path.map(|p| format!("/{}", p));
+    let template_request = TemplateRequest::new(
+        method.as_str(),
+        path.as_deref(),
+        query.as_deref(),
+        &header_list,
+        &readable_body,
+    );
+
+    // Scripts run off the async workers and without the database lock held
+    let (script_outcome, template_request) = match settings.script {
+        Some(script) => tokio::task::spawn_blocking(move || {
+            let outcome = run_script(&script, &template_request);
+            (Some(outcome), template_request)
+        })
+        .await
+        .map_err(|err| {
+            error!("Webhook script panicked: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to run webhook script".to_string())
+        })?,
+        None => (None, template_request),
+    };
+
+    if let Some(err) = script_outcome.as_ref().and_then(|o| o.error.as_ref()) {
+        warn!("Webhook script failed: {} {}", webhook_id, err);
+    }
+
+    let store = script_outcome.as_ref().is_none_or(|o| o.store);
+
+    // Every stored request moves the sequence on, even when the script replies instead
+    let sequence_position = match settings.sequence.as_ref() {
+        Some(sequence) if store => {
+            let db = state.db.lock().await;
+            match webhook_dao
+                .next_sequence_hit(db.clone(), webhook_id.as_str())
+                .await
+            {
+                Ok(hit) => Some(SequencePosition {
+                    hit,
+                    step: sequence.step(hit),
+                }),
+                Err(err) => {
+                    error!(
+                        "Failed to advance response sequence: {} {}",
+                        webhook_id, err
+                    );
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let configured = match (settings.sequence, sequence_position.as_ref()) {
+        (Some(mut sequence), Some(position)) => Some(sequence.steps.swap_remove(position.step)),
+        _ => settings.response,
+    };
+
+    // A reply returned by the script wins, then the forward target's answer; otherwise
+    // render the configured one now so it can be stored with the request
+    let mut upstream = None;
+    let rendered = match script_outcome.as_ref().and_then(|o| o.response.clone()) {
+        Some(config) => {
+            let sent = SentResponse::from_config(&config);
+            Some((build_response(&config), sent))
+        }
+        None => match settings.forward.as_ref() {
+            Some(target) => {
+                let forwarded = forward_request(
+                    &state.http_client,
+                    state.config.allow_private_targets,
+                    target,
+                    target_url(&target.url, path.as_deref(), query.as_deref()),
+                    &method,
+                    &headers,
+                    body.clone(),
+                )
+                .await;
+                if let Some(err) = forwarded.upstream.error.as_ref() {
+                    warn!("Failed to forward request: {} {}", webhook_id, err);
+                }
+                upstream = Some(forwarded.upstream);
+                Some((forwarded.reply, forwarded.sent))
+            }
+            None => configured.map(|config| match render_response(&config, &template_request) {
+                Ok(rendered) => {
+                    let sent = SentResponse::from_config(&rendered);
+                    (build_response(&rendered), sent)
+                }
+                Err(err) => {
+                    warn!("Failed to render response template: {} {}", webhook_id, err);
+                    let fallback = ResponseConfig {
+                        status: 500,
+                        content_type: Some("text/plain; charset=utf-8".to_string()),
+                        headers: Vec::new(),
+                        body: format!("response template error: {}", err),
+                    };
+                    let sent = SentResponse {
+                        error: Some(err),
+                        ..SentResponse::from_config(&fallback)
+                    };
+                    (build_response(&fallback), sent)
+                }
+            }),
+        },
+    };
+
+    let fault = settings.faults.as_ref().and_then(pick_fault);
+
+    // An injected error replaces whatever would have been sent
+    let rendered = match fault.as_ref().and_then(|f| f.action.as_ref()) {
+        Some(FaultAction::Error { status }) => {
+            let config = error_reply(*status);
+            let sent = SentResponse::from_config(&config);
+            Some((build_response(&config), sent))
+        }
+        _ => rendered,
+    };
+
+    if !store {
+        let reply = match rendered {
+            Some((reply, _)) => reply,
+            None => StatusCode::NO_CONTENT.into_response(),
+        };
+        return Ok(match fault {
+            Some(fault) => apply_fault(&fault, reply).await,
+            None => reply,
+        });
+    }
+
+    let (tags, script_error) = script_outcome
+        .map(|o| (o.tags, o.error))
+        .unwrap_or_default();
+
+    let signature = settings.signature.as_ref().map(|config| {
+        let url = request_url(&webhook_dao.domain, &original_uri, &headers);
+        verify_signature(config, &headers, &body, &url)
+    });
+
+    let detected = detect_provider(&headers, &readable_body);
+
+    // Only JSON bodies are checked; anything else is left unvalidated
+    let json_body: Option<serde_json::Value> = (body_format == Some(BodyFormat::Json))
+        .then(|| serde_json::from_slice(&readable_body).ok())
+        .flatten();
+
+    let schema_validation = settings
+        .json_schema
+        .as_ref()
+        .filter(|_| json_body.is_some())
+        .and_then(|schema| match compile_json_schema(schema) {
+            Ok(validator) => Some(validator),
+            Err(err) => {
+                error!("Failed to compile JSON Schema: {} {}", webhook_id, err);
+                None
+            }
+        })
+        .and_then(|validator| {
+            json_body
+                .as_ref()
+                .map(|body| validate_json_body(&validator, body))
+        });
+
+    let event_type = detected.as_ref().and_then(|d| d.event_type.clone());
+
+    let db = state.db.lock().await;
+
+    // A failed check must not fail the capture
+    let drift = match json_body.as_ref() {
+        Some(body) => check_drift(db.clone(), &webhook_id, event_type.as_deref(), body)
+            .await
+            .unwrap_or_else(|err| {
+                error!("Failed to check payload drift: {} {}", webhook_id, err);
+                Vec::new()
+            }),
+        None => Vec::new(),
+    };
+
+    let mut webhook_request = webhook_dao
+        .create_webhook_request(
+            db.clone(),
+            NewWebhookRequest {
+                webhook_id: webhook_id.clone(),
+                method: method.to_string(),
+                path,
+                query,
+                headers: headers_json,
+                body: body.to_vec(),
+                content_encoding: decoded_body
+                    .as_ref()
+                    .and(content_encoding.map(str::to_string)),
+                decoded_body,
+                body_format,
+                parsed_body,
+                caller_ip: Some(caller_ip),
+                tags,
+                script_error,
+                fault: fault.clone(),
+                sequence: sequence_position,
+                upstream,
+                signature,
+                provider: detected.as_ref().map(|d| d.provider.to_string()),
+                event_type,
+                schema_validation,
+                drift,
+                parts,
+                cloud_events,
+            },
+        )
+        .await
+        .map_err(|err| {
+            error!("Failed to insert webhook request {}", err);
+            ApiError::InternalServerError("failed to save a webhook request".to_string())
+        })?;
+
+    if let Some(fanout) = settings.fanout.as_ref() {
+        // Sent later by the delivery worker; a failure here must not fail the capture
+        if let Err(err) = DeliveryDao
+            .enqueue_deliveries(
+                db.clone(),
+                &webhook_id,
+                &webhook_request.id,
+                fanout,
+                state.config.allow_private_targets,
+            )
+            .await
+        {
+            error!("Failed to queue deliveries: {} {}", webhook_id, err);
+        }
+    }
+
+    // Without a configured reply the caller gets the stored request back as JSON
+    let (reply, sent) = match rendered {
+        Some(rendered) => rendered,
+        None => {
+            let echo = ResponseConfig {
+                status: 200,
+                content_type: Some("application/json".to_string()),
+                headers: Vec::new(),
+                body: webhook_request.public_json().map_err(|err| {
+                    error!("Failed to serialize webhook request {}", err);
+                    ApiError::InternalServerError("failed to save a webhook request".to_string())
+                })?,
+            };
+            let sent = SentResponse::from_config(&echo);
+            (build_response(&echo), sent)
+        }
+    };
+
+    // Nothing reaches the caller when the connection is dropped
+    let sent = match fault.as_ref().and_then(|f| f.action.as_ref()) {
+        Some(FaultAction::Reset | FaultAction::Timeout { .. }) => None,
+        _ => Some(sent),
+    };
+
+    webhook_request.response = sent.clone();
+
+    if let Err(err) = webhook_dao
+        .finish_webhook_request(db.clone(), &webhook_request.id, sent.as_ref())
+        .await
+    {
+        error!(
+            "Failed to record response for {}: {}",
+            webhook_request.id, err
+        );
+    }
+
+    let user_id = webhook_dao
+        .get_webhook_user_id(db.clone(), webhook_id.as_str())
+        .await
+        .ok();
+
+    {
+        let mut notification = state.notification.lock().await;
+
+        let result_json = webhook_request.public_json().map_err(|err| {
+            error!("Failed to serialize webhook request {}", err);
+            ApiError::InternalServerError("failed to save a webhook request".to_string())
+        })?;
+
+        notification
+            .notify(webhook_id.clone(), &webhook_request.id, result_json)
+            .await;
+
+        if let Some(uid) = user_id {
+            notification.notify_user(&uid, &webhook_id).await;
+
+            if !webhook_request.drift.is_empty() {
+                let event = DriftEvent {
+                    webhook_id: webhook_id.clone(),
+                    request_id: webhook_request.id.clone(),
+                    event_type: webhook_request.event_type.clone(),
+                    changes: webhook_request.drift.clone(),
+                };
+                match serde_json::to_string(&event) {
+                    Ok(message) => notification.send_user(&uid, message).await,
+                    Err(err) => error!("Failed to serialize drift event {}", err),
+                }
+            }
+        }
+    }
+
+    // Taken after the fan-out, so it is only stored; notified copies carry no duration
+    let duration_us = start.elapsed().as_micros() as u64;
+    if let Err(err) = webhook_dao
+        .set_request_duration(db.clone(), &webhook_request.id, duration_us)
+        .await
+    {
+        error!(
+            "Failed to record duration for {}: {}",
+            webhook_request.id, err
+        );
+    }
+
+    drop(db);
+
+    // Injected delays run after the request is recorded, so it shows up while the
+    // sender is still waiting
+    Ok(match fault {
+        Some(fault) => apply_fault(&fault, reply).await,
+        None => reply,
+    })
+}
+
+/// Checks a JSON body against the baseline shape of its event type, or merges it in
+/// while the baseline is still learning.
+async fn check_drift(
+    db: turso::Connection,
+    webhook_id: &str,
+    event_type: Option<&str>,
+    body: &serde_json::Value,
+) -> anyhow::Result<Vec<ShapeChange>> {
+    let event_type = event_type.unwrap_or_default();
+    let baseline = BaselineDao
+        .get_baseline(db.clone(), webhook_id, event_type)
+        .await?;
+
+    match baseline {
+        Some(baseline) if !baseline.learning() => Ok(diff(&baseline.shape, body)),
+        baseline => {
+            let mut shape = baseline.map(|b| b.shape).unwrap_or_default();
+            shape.add(body);
+            BaselineDao
+                .save_baseline(db, webhook_id, event_type, &shape, false)
+                .await?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct PaginationParams {
+    pub size: Option<u64>,
+    pub page: Option<u64>,
+}
+
+/// List all requests received by a webhook
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/requests",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("size" = Option<u64>, Query, description = "Number of requests per page (default 100, max 1000)"),
+        ("page" = Option<u64>, Query, description = "Page number for pagination (default 1)"),
+        ("provider" = Option<String>, Query, description = "Only requests from this detected sender, e.g. `github`"),
+        ("event_type" = Option<String>, Query, description = "Only requests for this event, e.g. `push`"),
+        ("schema_valid" = Option<bool>, Query, description = "Only requests whose JSON body passed (`true`) or failed (`false`) the webhook's JSON Schema"),
+        ("drifted" = Option<bool>, Query, description = "Only requests whose JSON body drifted (`true`) or did not drift (`false`) from its baseline shape"),
+    ),
+    responses(
+        (status = 200, description = "List of recorded webhook requests", body = Vec<WebhookRequest>),
+        (status = 404, description = "Webhook not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn get_webhook_requests(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<PaginationParams>,
+    Query(filter): Query<RequestFilter>,
+) -> Result<Json<Vec<WebhookRequest>>, ApiError> {
+    let page_size = params.size.unwrap_or(100).min(1000);
+    let page_number = params.page.unwrap_or(1).max(1);
+    let offset = (page_number - 1) * page_size;
+
+    let db = state.db.lock().await;
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let _webhook = webhook_dao
+        .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+        .await
+        .map_err(|err| {
+            error!("Failed to query webhook: {} {}", webhook_id, err);
+            ApiError::InternalServerError("failed to get a webhook".to_string())
+        })?;
+
+    let requests = webhook_dao
+        .get_webhook_requests(db.clone(), webhook_id.as_str(), &filter, offset, page_size)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch webhook requests {}", err);
+            ApiError::InternalServerError("failed to fetch webhook requests".to_string())
+        })?;
+
+    if let Err(err) = webhook_dao
+        .mark_as_seen(db.clone(), user.sub.as_str(), webhook_id.as_str())
+        .await
+    {
+        error!("Failed to mark webhook as seen: {} {}", webhook_id, err);
+    }
+
+    Ok(Json(requests))
+}
+
+#[derive(serde::Deserialize)]
+pub struct NextRequestParams {
+    pub after: Option<String>,
+    pub timeout: Option<String>,
+}
+
+/// How long `requests/next` waits when no timeout is given.
+const DEFAULT_NEXT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest `requests/next` is allowed to wait.
+const MAX_NEXT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Longest wait between two checks; new requests wake the handler sooner.
+const NEXT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wait for the next request a webhook captures
+///
+/// Long-polling alternative to the notification WebSocket for shell scripts and CI
+/// jobs. Returns the oldest request captured after `after`, or after the newest
+/// request stored when the call was made if `after` is omitted, as soon as there is one. Passing the returned id as
+/// the next `after` walks through requests one by one without missing any.
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/requests/next",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("after" = Option<String>, Query, description = "Id of a captured request; only newer requests are returned"),
+        ("timeout" = Option<String>, Query, description = "How long to wait, e.g. `30s`, `500ms` or `2m` (default 30s, max 2m)"),
+    ),
+    responses(
+        (status = 200, description = "The next captured request", body = WebhookRequest),
+        (status = 204, description = "No request arrived before the timeout"),
+        (status = 400, description = "Invalid timeout", body = ErrorBody),
+        (status = 404, description = "Webhook or `after` request not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn get_next_webhook_request(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<NextRequestParams>,
+) -> Result<Response, ApiError> {
+    let timeout = match params.timeout.as_deref() {
+        Some(timeout) => parse_timeout(timeout).map_err(ApiError::BadRequest)?,
+        None => DEFAULT_NEXT_TIMEOUT,
+    };
+    if timeout > MAX_NEXT_TIMEOUT {
+        return Err(ApiError::BadRequest(format!(
+            "timeout must be at most {}s",
+            MAX_NEXT_TIMEOUT.as_secs()
+        )));
+    }
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    // Requests are walked in (received_at, id) order from this cursor
+    let (received_at, request_id) = {
+        let db = state.db.lock().await;
+
+        webhook_dao
+            .get_webhook(db.clone(), &user.sub, &webhook_id)
+            .await
+            .map_err(|err| {
+                error!("Failed to get webhook: {} {}", webhook_id, err);
+                ApiError::NotFound("webhook not found".to_string())
+            })?;
+
+        match params.after.as_deref() {
+            Some(after) => {
+                let request = webhook_dao
+                    .get_webhook_request(db.clone(), &webhook_id, after)
+                    .await
+                    .map_err(|err| {
+                        error!("Failed to get webhook request: {} {}", after, err);
+                        ApiError::NotFound("request not found".to_string())
+                    })?;
+                (request.received_at, request.id)
+            }
+            // Stored times are compared with stored times, never with this clock
+            None => webhook_dao
+                .get_latest_request_cursor(db.clone(), &webhook_id)
+                .await
+                .map_err(|err| {
+                    error!("Failed to get the newest request: {} {}", webhook_id, err);
+                    ApiError::InternalServerError("failed to fetch the next request".to_string())
+                })?
+                .unwrap_or_default(),
+        }
+    };
+
+    // Woken up by every request captured while waiting
+    let session_id = Uuid::new_v4().to_string();
+    let (tx, mut rx) = mpsc::channel(64);
+    {
+        let mut notification = state.notification.lock().await;
+        notification.subscribe(webhook_id.clone(), session_id.clone(), tx);
+    }
+
+    let started = Instant::now();
+    let next = loop {
+        let next = {
+            let db = state.db.lock().await;
+            webhook_dao
+                .get_next_webhook_request(db.clone(), &webhook_id, &received_at, &request_id)
+                .await
+        };
+
+        let elapsed = started.elapsed();
+        match next {
+            Ok(None) if elapsed < timeout => {}
+            next => break next,
+        }
+
+        let wait = (timeout - elapsed).min(NEXT_POLL_INTERVAL);
+        let _ = tokio::time::timeout(wait, rx.recv()).await;
+        while rx.try_recv().is_ok() {}
+    };
+
+    {
+        let mut notification = state.notification.lock().await;
+        notification.unsubscribe(&session_id);
+    }
+
+    match next {
+        Ok(Some(request)) => Ok(Json(request).into_response()),
+        Ok(None) => Ok(StatusCode::NO_CONTENT.into_response()),
+        Err(err) => {
+            error!("Failed to fetch the next request: {} {}", webhook_id, err);
+            Err(ApiError::InternalServerError(
+                "failed to fetch the next request".to_string(),
+            ))
+        }
+    }
+}
+
+/// Parses a timeout such as `30s`, `500ms` or `2m`; a bare number is in seconds.
+fn parse_timeout(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, unit_ms) = if let Some(number) = text.strip_suffix("ms") {
+        (number, 1)
+    } else if let Some(number) = text.strip_suffix('s') {
+        (number, 1_000)
+    } else if let Some(number) = text.strip_suffix('m') {
+        (number, 60_000)
+    } else {
+        (text, 1_000)
+    };
+
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit_ms))
+        .map(Duration::from_millis)
+        .ok_or_else(|| format!("invalid timeout {:?}, expected e.g. 30s, 500ms or 2m", text))
+}
+
+/// Download one part of a multipart request body
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/requests/{request_id}/parts/{part_id}",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("request_id" = String, Path, description = "Captured request identifier"),
+        ("part_id" = String, Path, description = "Multipart part identifier"),
+    ),
+    responses(
+        (status = 200, description = "Raw bytes of the part", content_type = "application/octet-stream"),
+        (status = 404, description = "Webhook, request or part not found", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn get_webhook_request_part(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, request_id, part_id)): Path<(String, String, String)>,
+) -> Result<Response, ApiError> {
+    let db = state.db.lock().await;
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let _webhook = webhook_dao
+        .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+        .await
+        .map_err(|err| {
+            error!("Failed to query webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
+        })?;
+
+    let (filename, content_type, data) = webhook_dao
+        .get_webhook_request_part_data(db.clone(), &webhook_id, &request_id, &part_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch multipart part: {} {}", part_id, err);
+            ApiError::NotFound("part not found".to_string())
+        })?;
+
+    let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+    let filename = filename
+        .unwrap_or_else(|| format!("part-{}", part_id))
+        .replace(['"', '\\', '\r', '\n'], "_");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Record what a local target answered for a captured request
+///
+/// Called by `hookspy-cli forward` after it relayed a captured request to a local
+/// target. The result is stored as `local_response` and subscribers of the webhook are
+/// notified with the updated request.
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{webhook_id}/requests/{request_id}/local-response",
+    params(
+        ("webhook_id" = String, Path, description = "Unique webhook identifier"),
+        ("request_id" = String, Path, description = "Captured request identifier"),
+    ),
+    request_body = UpstreamResponse,
+    responses(
+        (status = 200, description = "Local response stored", body = WebhookRequest),
+        (status = 404, description = "Webhook or request not found", body = ErrorBody),
+        (status = 500, description = "Internal server error", body = ErrorBody),
+    ),
+    security(("cookie_auth" = []), ("bearer_auth" = [])),
+    tag = "webhooks"
+)]
+pub async fn report_local_response(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, request_id)): Path<(String, String)>,
+    Json(payload): Json<UpstreamResponse>,
+) -> Result<Json<WebhookRequest>, ApiError> {
+    let db = state.db.lock().await;
+
+    let webhook_dao = WebhookDao {
+        domain: state.domain.clone(),
+    };
+
+    let _webhook = webhook_dao
+        .get_webhook(db.clone(), user.sub.as_str(), webhook_id.as_str())
+        .await
+        .map_err(|err| {
+            error!("Failed to query webhook: {} {}", webhook_id, err);
+            ApiError::NotFound("webhook not found".to_string())
+        })?;
+
+    let found = webhook_dao
+        .set_local_response(db.clone(), &webhook_id, &request_id, &payload)
+        .await
+        .map_err(|err| {
+            error!("Failed to store local response: {} {}", request_id, err);
+            ApiError::InternalServerError("failed to store local response".to_string())
+        })?;
+
+    if !found {
+        return Err(ApiError::NotFound("webhook request not found".to_string()));
+    }
+
+    let request = webhook_dao
+        .get_webhook_request(db.clone(), &webhook_id, &request_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to get webhook request: {} {}", request_id, err);
+            ApiError::InternalServerError("failed to get webhook request".to_string())
+        })?;
+
+    match request.public_json() {
+        Ok(request_json) => {
+            let mut notification = state.notification.lock().await;
+            notification
+                .notify(webhook_id.clone(), &request.id, request_json)
+                .await;
+        }
+        Err(err) => error!("Failed to serialize webhook request {}", err),
+    }
+
+    Ok(Json(request))
 }
@@ -13,6 +13,7 @@ use uuid::Uuid;
 
 use crate::app::AppState;
 use crate::auth::jwt::AuthUser;
+use crate::notification::notification::Event;
 
 pub async fn webhook_notifications_ws(
     State(state): State<AppState>,
@@ -44,7 +45,7 @@ async fn handle_socket(state: AppState, socket: WebSocket, webhook_id: String) {
     info!("Client connected: {} {}", webhook_id, session_id);
 
     let (mut ws_tx, mut ws_rx) = socket.split();
-    let (tx, mut rx) = mpsc::channel::<Message>(32);
+    let (tx, mut rx) = mpsc::channel::<Event>(32);
 
     // Register subscriber
     {
@@ -54,8 +55,8 @@ async fn handle_socket(state: AppState, socket: WebSocket, webhook_id: String) {
 
     // Task: send notifications → websocket
     let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            if ws_tx.send(msg).await.is_err() {
+        while let Some(event) = rx.recv().await {
+            if ws_tx.send(Message::Text(event.data)).await.is_err() {
                 break;
             }
         }
@@ -87,7 +88,7 @@ async fn handle_user_socket(state: AppState, socket: WebSocket, user_id: String)
     info!("User WS connected: {} {}", user_id, session_id);
 
     let (mut ws_tx, mut ws_rx) = socket.split();
-    let (tx, mut rx) = mpsc::channel::<Message>(32);
+    let (tx, mut rx) = mpsc::channel::<Event>(32);
 
     // Register per-user subscriber
     {
@@ -97,8 +98,8 @@ async fn handle_user_socket(state: AppState, socket: WebSocket, user_id: String)
 
     // Task: forward notifications to websocket
     let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            if ws_tx.send(msg).await.is_err() {
+        while let Some(event) = rx.recv().await {
+            if ws_tx.send(Message::Text(event.data)).await.is_err() {
                 break;
             }
         }
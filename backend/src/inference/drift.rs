@@ -0,0 +1,226 @@
+use serde_json::Value;
+
+use crate::inference::Shape;
+use crate::schema::drift::{ShapeChange, ShapeChangeKind};
+
+/// Payloads merged into a learned baseline before requests are checked against it.
+pub const LEARNING_SAMPLES: u64 = 10;
+
+/// Most changes kept for one request.
+const MAX_CHANGES: usize = 50;
+
+/// Ways `payload` departs from `baseline`: fields it adds, fields the baseline always
+/// had that it lacks, and values of a type the baseline never had.
+pub fn diff(baseline: &Shape, payload: &Value) -> Vec<ShapeChange> {
+    let mut changes = Vec::new();
+    compare(baseline, payload, "", &mut changes);
+    changes
+}
+
+fn compare(shape: &Shape, value: &Value, path: &str, changes: &mut Vec<ShapeChange>) {
+    // Nothing is known about items of arrays that were always empty
+    if shape.seen == 0 {
+        return;
+    }
+
+    let actual = type_name(value);
+    let expected = type_names(shape);
+    let widened = actual == "integer" && expected.contains(&"number");
+    if !expected.contains(&actual) && !widened {
+        push(
+            changes,
+            ShapeChange {
+                path: path.to_string(),
+                change: ShapeChangeKind::TypeChanged,
+                expected: Some(expected.join(" | ")),
+                actual: Some(actual.to_string()),
+            },
+        );
+        return;
+    }
+
+    match value {
+        Value::Object(map) => {
+            for (key, field_value) in map {
+                let field_path = child_path(path, key);
+                match shape.fields.get(key) {
+                    Some(field) => compare(field, field_value, &field_path, changes),
+                    None => push(
+                        changes,
+                        ShapeChange {
+                            path: field_path,
+                            change: ShapeChangeKind::Added,
+                            expected: None,
+                            actual: Some(type_name(field_value).to_string()),
+                        },
+                    ),
+                }
+            }
+            for (key, field) in shape.fields.iter() {
+                if !map.contains_key(key) && !shape.optional(field) {
+                    push(
+                        changes,
+                        ShapeChange {
+                            path: child_path(path, key),
+                            change: ShapeChangeKind::Removed,
+                            expected: Some(type_names(field).join(" | ")),
+                            actual: None,
+                        },
+                    );
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_shape) = shape.items.as_deref() {
+                let item_path = format!("{}/*", path);
+                for item in items {
+                    compare(item_shape, item, &item_path, changes);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// JSON Pointer to the `key` field of the object at `path`.
+fn child_path(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Records `change` once, up to `MAX_CHANGES`; array items repeat the same paths.
+fn push(changes: &mut Vec<ShapeChange>, change: ShapeChange) {
+    if changes.len() < MAX_CHANGES && !changes.contains(&change) {
+        changes.push(change);
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// JSON types seen at a position, as named by JSON Schema.
+fn type_names(shape: &Shape) -> Vec<&'static str> {
+    [
+        (shape.booleans, "boolean"),
+        (if shape.floats > 0 { 0 } else { shape.integers }, "integer"),
+        (shape.floats, "number"),
+        (shape.strings, "string"),
+        (shape.arrays, "array"),
+        (shape.objects, "object"),
+        (shape.nulls, "null"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn baseline() -> Shape {
+        Shape::infer(&[
+            json!({ "id": "1", "amount": 10, "note": "a", "items": [{ "sku": "a" }] }),
+            json!({ "id": "2", "amount": 2.5, "items": [] }),
+            json!({ "id": "3", "amount": 1, "note": null, "items": [{ "sku": "b" }] }),
+        ])
+    }
+
+    fn change(
+        path: &str,
+        change: ShapeChangeKind,
+        expected: Option<&str>,
+        actual: Option<&str>,
+    ) -> ShapeChange {
+        ShapeChange {
+            path: path.to_string(),
+            change,
+            expected: expected.map(str::to_string),
+            actual: actual.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn payloads_like_the_baseline_have_no_changes() {
+        let payload = json!({ "id": "4", "amount": 3, "note": null, "items": [{ "sku": "c" }] });
+        assert_eq!(diff(&baseline(), &payload), []);
+        // Optional fields may be left out
+        assert_eq!(
+            diff(&baseline(), &json!({ "id": "5", "amount": 1, "items": [] })),
+            []
+        );
+    }
+
+    #[test]
+    fn added_removed_and_retyped_fields_are_reported() {
+        let payload =
+            json!({ "id": 4, "note": "b", "items": [{ "sku": "c", "qty": 1 }], "a/b~": true });
+        assert_eq!(
+            diff(&baseline(), &payload),
+            [
+                change("/a~1b~0", ShapeChangeKind::Added, None, Some("boolean")),
+                change(
+                    "/id",
+                    ShapeChangeKind::TypeChanged,
+                    Some("string"),
+                    Some("integer")
+                ),
+                change(
+                    "/items/*/qty",
+                    ShapeChangeKind::Added,
+                    None,
+                    Some("integer")
+                ),
+                change("/amount", ShapeChangeKind::Removed, Some("number"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn array_items_report_each_change_once() {
+        let payload = json!({ "id": "4", "amount": 1, "items": [{ "sku": 1 }, { "sku": 2 }] });
+        assert_eq!(
+            diff(&baseline(), &payload),
+            [change(
+                "/items/*/sku",
+                ShapeChangeKind::TypeChanged,
+                Some("string"),
+                Some("integer")
+            )]
+        );
+    }
+
+    #[test]
+    fn changes_are_capped() {
+        let fields: serde_json::Map<String, Value> = (0..MAX_CHANGES * 2)
+            .map(|n| (format!("f{}", n), json!(n)))
+            .collect();
+        let mut payload = Value::Object(fields);
+        payload["id"] = json!("1");
+        assert_eq!(diff(&baseline(), &payload).len(), MAX_CHANGES);
+    }
+
+    #[test]
+    fn a_different_root_type_is_one_change() {
+        assert_eq!(
+            diff(&baseline(), &json!([1])),
+            [change(
+                "",
+                ShapeChangeKind::TypeChanged,
+                Some("object"),
+                Some("array")
+            )]
+        );
+    }
+}
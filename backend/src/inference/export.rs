@@ -0,0 +1,519 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use serde_json::{json, Map, Value};
+
+use crate::inference::{Kind, Shape, StringFormat};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// JSON Schema (draft 2020-12) accepting every merged payload.
+pub fn json_schema(shape: &Shape, title: &str) -> Value {
+    let mut schema = Map::new();
+    schema.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    schema.insert("title".to_string(), json!(title));
+    schema.extend(schema_for(shape));
+    Value::Object(schema)
+}
+
+fn schema_for(shape: &Shape) -> Map<String, Value> {
+    let mut schema = schema_for_kind(&shape.kind(), shape.string_format());
+    if shape.nullable() {
+        allow_null(&mut schema);
+    }
+    schema
+}
+
+fn schema_for_kind(kind: &Kind, format: Option<StringFormat>) -> Map<String, Value> {
+    let schema = match kind {
+        Kind::Unknown => json!({}),
+        Kind::Boolean => json!({ "type": "boolean" }),
+        Kind::Integer => json!({ "type": "integer" }),
+        Kind::Number => json!({ "type": "number" }),
+        Kind::String => match format {
+            Some(format) => json!({ "type": "string", "format": format.as_str() }),
+            None => json!({ "type": "string" }),
+        },
+        Kind::Enum(values) => json!({ "type": "string", "enum": values }),
+        Kind::Array(None) => json!({ "type": "array" }),
+        Kind::Array(Some(items)) => json!({ "type": "array", "items": schema_for(items) }),
+        Kind::Object(object) => {
+            let properties: Map<String, Value> = object
+                .fields
+                .iter()
+                .map(|(name, field)| (name.clone(), Value::Object(schema_for(field))))
+                .collect();
+            let required: Vec<&String> = object
+                .fields
+                .iter()
+                .filter(|(_, field)| !object.optional(field))
+                .map(|(name, _)| name)
+                .collect();
+            json!({ "type": "object", "properties": properties, "required": required })
+        }
+        Kind::Mixed(kinds) => {
+            let any_of: Vec<Map<String, Value>> = kinds
+                .iter()
+                .map(|kind| schema_for_kind(kind, format))
+                .collect();
+            json!({ "anyOf": any_of })
+        }
+    };
+
+    match schema {
+        Value::Object(schema) => schema,
+        _ => Map::new(),
+    }
+}
+
+/// Widens a schema so it also accepts `null`.
+fn allow_null(schema: &mut Map<String, Value>) {
+    if let Some(Value::Array(values)) = schema.get_mut("enum") {
+        values.push(Value::Null);
+    }
+    if let Some(Value::Array(any_of)) = schema.get_mut("anyOf") {
+        any_of.push(json!({ "type": "null" }));
+        return;
+    }
+    match schema.get("type").cloned() {
+        Some(Value::String(kind)) => {
+            schema.insert("type".to_string(), json!([kind, "null"]));
+        }
+        Some(_) => {}
+        None => {
+            schema.insert("type".to_string(), json!("null"));
+        }
+    }
+}
+
+/// serde structs and enums that deserialize every merged payload.
+pub fn rust_types(shape: &Shape, root: &str) -> String {
+    let mut generator = TypeGenerator::default();
+    let root_name = generator.claim(root, "");
+    let mut out = String::from("use serde::{Deserialize, Serialize};\n");
+
+    match shape.kind() {
+        Kind::Object(object) => generator.pending.push_back(Decl::Struct(root_name, object)),
+        _ => {
+            let ty = generator.rust_type(shape, &format!("{}Item", root_name), &root_name);
+            let ty = if shape.nullable() {
+                rust_option(ty)
+            } else {
+                ty
+            };
+            out.push_str(&format!("\npub type {} = {};\n", root_name, ty));
+        }
+    }
+
+    while let Some(decl) = generator.pending.pop_front() {
+        out.push('\n');
+        match decl {
+            Decl::Struct(name, object) => {
+                out.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+                out.push_str(&format!("pub struct {} {{\n", name));
+                let mut idents = HashSet::new();
+                for (field_name, field) in object.fields.iter() {
+                    let ident = unique(&mut idents, rust_ident(field_name));
+                    if ident.trim_start_matches("r#") != field_name {
+                        out.push_str(&format!("    #[serde(rename = {:?})]\n", field_name));
+                    }
+                    let ty = generator.rust_type(field, field_name, &name);
+                    let ty = if object.optional(field) || field.nullable() {
+                        rust_option(ty)
+                    } else {
+                        ty
+                    };
+                    out.push_str(&format!("    pub {}: {},\n", ident, ty));
+                }
+                out.push_str("}\n");
+            }
+            Decl::Enum(name, values) => {
+                out.push_str(
+                    "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n",
+                );
+                out.push_str(&format!("pub enum {} {{\n", name));
+                let mut variants = HashSet::new();
+                for value in values.iter() {
+                    let variant = unique(&mut variants, variant_name(value));
+                    if variant != *value {
+                        out.push_str(&format!("    #[serde(rename = {:?})]\n", value));
+                    }
+                    out.push_str(&format!("    {},\n", variant));
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+
+    out
+}
+
+/// TypeScript interfaces describing every merged payload.
+pub fn typescript_types(shape: &Shape, root: &str) -> String {
+    let mut generator = TypeGenerator::default();
+    let root_name = generator.claim(root, "");
+    let mut out = String::new();
+
+    match shape.kind() {
+        Kind::Object(object) => generator.pending.push_back(Decl::Struct(root_name, object)),
+        _ => {
+            let ty = generator.typescript_type(shape, &format!("{}Item", root_name), &root_name);
+            out.push_str(&format!("export type {} = {};\n", root_name, ty));
+        }
+    }
+
+    while let Some(decl) = generator.pending.pop_front() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        // Enums are written inline as unions of string literals
+        if let Decl::Struct(name, object) = decl {
+            out.push_str(&format!("export interface {} {{\n", name));
+            for (field_name, field) in object.fields.iter() {
+                let key = if is_typescript_ident(field_name) {
+                    field_name.clone()
+                } else {
+                    json!(field_name).to_string()
+                };
+                let marker = if object.optional(field) { "?" } else { "" };
+                let ty = generator.typescript_type(field, field_name, &name);
+                out.push_str(&format!("  {}{}: {};\n", key, marker, ty));
+            }
+            out.push_str("}\n");
+        }
+    }
+
+    out
+}
+
+/// A named type still to be written out.
+enum Decl<'a> {
+    Struct(String, &'a Shape),
+    Enum(String, &'a BTreeSet<String>),
+}
+
+#[derive(Default)]
+struct TypeGenerator<'a> {
+    used: HashSet<String>,
+    pending: VecDeque<Decl<'a>>,
+}
+
+impl<'a> TypeGenerator<'a> {
+    /// Picks a type name for `wanted`, qualified by `parent` when it is already taken.
+    fn claim(&mut self, wanted: &str, parent: &str) -> String {
+        let base = pascal_case(wanted);
+        let qualified = format!("{}{}", parent, base);
+        if self.used.insert(base.clone()) {
+            return base;
+        }
+        if self.used.insert(qualified.clone()) {
+            return qualified;
+        }
+        (2..)
+            .map(|n| format!("{}{}", qualified, n))
+            .find(|name| self.used.insert(name.clone()))
+            .unwrap_or(qualified)
+    }
+
+    /// Rust type for `shape`, before any `Option` for missing or null values.
+    fn rust_type(&mut self, shape: &'a Shape, hint: &str, parent: &str) -> String {
+        match shape.kind() {
+            Kind::Boolean => "bool".to_string(),
+            Kind::Integer => "i64".to_string(),
+            Kind::Number => "f64".to_string(),
+            Kind::String => "String".to_string(),
+            Kind::Enum(values) => {
+                let name = self.claim(hint, parent);
+                self.pending.push_back(Decl::Enum(name.clone(), values));
+                name
+            }
+            Kind::Array(Some(items)) => {
+                let item = self.rust_type(items, &singular(hint), parent);
+                let item = if items.nullable() {
+                    rust_option(item)
+                } else {
+                    item
+                };
+                format!("Vec<{}>", item)
+            }
+            Kind::Array(None) => "Vec<serde_json::Value>".to_string(),
+            Kind::Object(object) => {
+                let name = self.claim(hint, parent);
+                self.pending.push_back(Decl::Struct(name.clone(), object));
+                name
+            }
+            Kind::Unknown | Kind::Mixed(_) => "serde_json::Value".to_string(),
+        }
+    }
+
+    /// TypeScript type for `shape`, `| null` included.
+    fn typescript_type(&mut self, shape: &'a Shape, hint: &str, parent: &str) -> String {
+        let ty = match shape.kind() {
+            Kind::Unknown => return "unknown".to_string(),
+            Kind::Mixed(kinds) => kinds
+                .into_iter()
+                .map(|kind| self.typescript_kind(kind, hint, parent))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            kind => self.typescript_kind(kind, hint, parent),
+        };
+        if shape.nullable() {
+            format!("{} | null", ty)
+        } else {
+            ty
+        }
+    }
+
+    fn typescript_kind(&mut self, kind: Kind<'a>, hint: &str, parent: &str) -> String {
+        match kind {
+            Kind::Boolean => "boolean".to_string(),
+            Kind::Integer | Kind::Number => "number".to_string(),
+            Kind::String => "string".to_string(),
+            Kind::Enum(values) => values
+                .iter()
+                .map(|value| json!(value).to_string())
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Kind::Array(Some(items)) => {
+                let item = self.typescript_type(items, &singular(hint), parent);
+                if item.contains(' ') {
+                    format!("({})[]", item)
+                } else {
+                    format!("{}[]", item)
+                }
+            }
+            Kind::Array(None) => "unknown[]".to_string(),
+            Kind::Object(object) => {
+                let name = self.claim(hint, parent);
+                self.pending.push_back(Decl::Struct(name.clone(), object));
+                name
+            }
+            Kind::Unknown | Kind::Mixed(_) => "unknown".to_string(),
+        }
+    }
+}
+
+fn rust_option(ty: String) -> String {
+    // A missing or null `Value` is already `Value::Null`
+    if ty == "serde_json::Value" {
+        ty
+    } else {
+        format!("Option<{}>", ty)
+    }
+}
+
+/// Adds a numeric suffix until `name` is not in `taken`.
+fn unique(taken: &mut HashSet<String>, name: String) -> String {
+    if taken.insert(name.clone()) {
+        return name;
+    }
+    (2..)
+        .map(|n| format!("{}{}", name, n))
+        .find(|candidate| taken.insert(candidate.clone()))
+        .unwrap_or(name)
+}
+
+/// Splits on anything that is not a letter or digit, and on lower-to-upper case changes.
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Type name for a payload, e.g. `InvoicePaid` for `invoice.paid`.
+pub fn type_name(text: &str) -> String {
+    pascal_case(text)
+}
+
+fn pascal_case(text: &str) -> String {
+    let name: String = words(text)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    match name.chars().next() {
+        None => "Type".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("T{}", name),
+        Some(_) => name,
+    }
+}
+
+fn rust_ident(field: &str) -> String {
+    let ident = words(field)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    match ident.as_str() {
+        "" => "field".to_string(),
+        // The only keywords that cannot be raw identifiers
+        "self" | "super" | "crate" => format!("{}_", ident),
+        _ if RUST_KEYWORDS.contains(&ident.as_str()) => format!("r#{}", ident),
+        _ if ident.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", ident),
+        _ => ident,
+    }
+}
+
+fn variant_name(value: &str) -> String {
+    if words(value).is_empty() {
+        return "Empty".to_string();
+    }
+    match pascal_case(value).as_str() {
+        "Self" => "Self_".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Naive English singular of a field name, used to name array element types.
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with('s') && !name.ends_with("ss") && name.len() > 3 {
+        name[..name.len() - 1].to_string()
+    } else {
+        format!("{}Item", pascal_case(name))
+    }
+}
+
+fn is_typescript_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn payloads() -> Vec<Value> {
+        vec![
+            json!({ "id": "evt_1", "type": "invoice.paid", "amount": 10, "lineItems": [{ "sku": "a" }], "self": true }),
+            json!({ "id": "evt_2", "type": "invoice.paid", "amount": 2.5, "lineItems": [], "note": null }),
+            json!({ "id": "evt_3", "type": "invoice.void", "amount": 0, "lineItems": [{ "sku": "b" }] }),
+        ]
+    }
+
+    #[test]
+    fn json_schema_accepts_every_merged_payload() {
+        let shape = Shape::infer(&payloads());
+        let schema = json_schema(&shape, "Invoice");
+        assert_eq!(schema["title"], "Invoice");
+        assert_eq!(
+            schema["required"],
+            json!(["amount", "id", "lineItems", "type"])
+        );
+        assert_eq!(schema["properties"]["amount"], json!({ "type": "number" }));
+        assert_eq!(
+            schema["properties"]["type"]["enum"],
+            json!(["invoice.paid", "invoice.void"])
+        );
+
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert!(payloads().iter().all(|payload| validator.is_valid(payload)));
+        assert!(!validator
+            .is_valid(&json!({ "id": 1, "type": "invoice.paid", "amount": 1, "lineItems": [] })));
+    }
+
+    #[test]
+    fn null_is_added_to_every_schema_form() {
+        let schema = |values: &[Value]| Value::Object(schema_for(&Shape::infer(values)));
+        assert_eq!(
+            schema(&[json!(1), json!(null)]),
+            json!({ "type": ["integer", "null"] })
+        );
+        assert_eq!(schema(&[json!(null)]), json!({ "type": "null" }));
+        assert_eq!(
+            schema(&[json!(1), json!("a"), json!(null)]),
+            json!({ "anyOf": [{ "type": "integer" }, { "type": "string" }, { "type": "null" }] })
+        );
+        let statuses = schema(&[json!("a"), json!("a"), json!("b"), json!(null)]);
+        assert_eq!(statuses["enum"], json!(["a", "b", null]));
+    }
+
+    #[test]
+    fn rust_types_rename_fields_that_are_not_identifiers() {
+        let rust = rust_types(&Shape::infer(&payloads()), "invoice.paid");
+        for line in [
+            "pub struct InvoicePaid {",
+            "    #[serde(rename = \"lineItems\")]\n    pub line_items: Vec<LineItem>,",
+            "    pub amount: f64,",
+            "    pub note: serde_json::Value,",
+            "    #[serde(rename = \"self\")]\n    pub self_: Option<bool>,",
+            "    pub r#type: Type,",
+            "pub struct LineItem {\n    pub sku: String,\n}",
+            "    #[serde(rename = \"invoice.void\")]\n    InvoiceVoid,",
+        ] {
+            assert!(rust.contains(line), "{:?} not in\n{}", line, rust);
+        }
+    }
+
+    #[test]
+    fn typescript_marks_optional_fields_and_inlines_enums() {
+        let typescript = typescript_types(&Shape::infer(&payloads()), "invoice.paid");
+        assert_eq!(
+            typescript,
+            "export interface InvoicePaid {\n  amount: number;\n  id: string;\n  lineItems: LineItem[];\n  note?: unknown;\n  self?: boolean;\n  type: \"invoice.paid\" | \"invoice.void\";\n}\n\nexport interface LineItem {\n  sku: string;\n}\n"
+        );
+
+        let list = typescript_types(&Shape::infer(&[json!([1, null])]), "ids");
+        assert_eq!(list, "export type Ids = (number | null)[];\n");
+    }
+
+    #[test]
+    fn names_are_split_on_separators_and_case_changes() {
+        assert_eq!(type_name("invoice.paid"), "InvoicePaid");
+        assert_eq!(type_name("HTTPRequest_id"), "HTTPRequestId");
+        assert_eq!(type_name("3ds"), "T3ds");
+        assert_eq!(type_name("--"), "Type");
+        assert_eq!(rust_ident("createdAt"), "created_at");
+        assert_eq!(rust_ident("2fa"), "_2fa");
+        assert_eq!(rust_ident("match"), "r#match");
+        assert_eq!(singular("entries"), "entry");
+        assert_eq!(singular("items"), "item");
+        assert_eq!(singular("address"), "AddressItem");
+    }
+}
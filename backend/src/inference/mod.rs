@@ -0,0 +1,363 @@
+pub mod drift;
+pub mod export;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Most distinct values a string may take and still be exported as an enum.
+const MAX_ENUM_VALUES: usize = 12;
+
+/// Fewest strings a position must have seen before it is exported as an enum.
+const MIN_ENUM_SAMPLES: u64 = 3;
+
+/// Everything seen at one position of the merged payloads, e.g. `$.customer.email`.
+///
+/// Stored as JSON for drift baselines, so counters that stayed at zero are left out.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Shape {
+    /// Values seen here, nulls included
+    #[serde(skip_serializing_if = "is_zero")]
+    pub seen: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub nulls: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub booleans: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub integers: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub floats: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub strings: u64,
+    /// Distinct strings, dropped once there are more than `MAX_ENUM_VALUES`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    string_values: Option<BTreeSet<String>>,
+    #[serde(skip_serializing_if = "is_zero")]
+    date_times: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    uuids: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    emails: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    uris: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub arrays: u64,
+    /// Merged shape of every array element
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<Shape>>,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub objects: u64,
+    /// Merged shape of each key seen in any object here
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Shape>,
+}
+
+/// What a position holds once nulls are set aside.
+pub enum Kind<'a> {
+    /// Only nulls, or nothing at all
+    Unknown,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    /// Strings drawn from a small set of values
+    Enum(&'a BTreeSet<String>),
+    /// Arrays; `None` when every one of them was empty
+    Array(Option<&'a Shape>),
+    Object(&'a Shape),
+    /// More than one of the above, listed one level deep
+    Mixed(Vec<Kind<'a>>),
+}
+
+/// Well-known string formats, as named by JSON Schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringFormat {
+    DateTime,
+    Uuid,
+    Email,
+    Uri,
+}
+
+impl StringFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StringFormat::DateTime => "date-time",
+            StringFormat::Uuid => "uuid",
+            StringFormat::Email => "email",
+            StringFormat::Uri => "uri",
+        }
+    }
+}
+
+impl Shape {
+    /// Merges every payload into one shape.
+    pub fn infer<'a>(payloads: impl IntoIterator<Item = &'a Value>) -> Shape {
+        let mut shape = Shape::default();
+        for payload in payloads {
+            shape.add(payload);
+        }
+        shape
+    }
+
+    /// Merges one more payload into the shape.
+    pub fn add(&mut self, value: &Value) {
+        self.seen += 1;
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Bool(_) => self.booleans += 1,
+            Value::Number(number) if number.is_f64() => self.floats += 1,
+            Value::Number(_) => self.integers += 1,
+            Value::String(text) => self.add_string(text),
+            Value::Array(items) => {
+                self.arrays += 1;
+                let shape = self.items.get_or_insert_with(Default::default);
+                for item in items {
+                    shape.add(item);
+                }
+            }
+            Value::Object(map) => {
+                self.objects += 1;
+                for (key, value) in map {
+                    self.fields.entry(key.clone()).or_default().add(value);
+                }
+            }
+        }
+    }
+
+    fn add_string(&mut self, text: &str) {
+        // Values are only worth keeping while the set still looks like an enum
+        if self.strings == 0 {
+            self.string_values = Some(BTreeSet::new());
+        }
+        self.strings += 1;
+        if let Some(values) = self.string_values.as_mut() {
+            values.insert(text.to_string());
+            if values.len() > MAX_ENUM_VALUES {
+                self.string_values = None;
+            }
+        }
+
+        if chrono::DateTime::parse_from_rfc3339(text).is_ok() {
+            self.date_times += 1;
+        }
+        if Uuid::parse_str(text).is_ok() {
+            self.uuids += 1;
+        }
+        if is_email(text) {
+            self.emails += 1;
+        }
+        if is_uri(text) {
+            self.uris += 1;
+        }
+    }
+
+    /// Whether some values here were `null`.
+    pub fn nullable(&self) -> bool {
+        self.nulls > 0
+    }
+
+    /// Whether `field` was missing from some of the objects seen here.
+    pub fn optional(&self, field: &Shape) -> bool {
+        field.seen < self.objects
+    }
+
+    /// Format every string seen here matches, if any.
+    pub fn string_format(&self) -> Option<StringFormat> {
+        [
+            (self.date_times, StringFormat::DateTime),
+            (self.uuids, StringFormat::Uuid),
+            (self.emails, StringFormat::Email),
+            (self.uris, StringFormat::Uri),
+        ]
+        .into_iter()
+        .find(|(count, _)| self.strings > 0 && *count == self.strings)
+        .map(|(_, format)| format)
+    }
+
+    /// Strings that repeat often enough, with few enough distinct values, to be an enum.
+    fn enum_values(&self) -> Option<&BTreeSet<String>> {
+        let values = self.string_values.as_ref()?;
+        let repeats = (values.len() as u64) < self.strings;
+        (self.strings >= MIN_ENUM_SAMPLES && repeats && self.string_format().is_none())
+            .then_some(values)
+    }
+
+    pub fn kind(&self) -> Kind<'_> {
+        let mut kinds = Vec::new();
+        if self.booleans > 0 {
+            kinds.push(Kind::Boolean);
+        }
+        if self.floats > 0 {
+            kinds.push(Kind::Number);
+        } else if self.integers > 0 {
+            kinds.push(Kind::Integer);
+        }
+        if self.strings > 0 {
+            kinds.push(match self.enum_values() {
+                Some(values) => Kind::Enum(values),
+                None => Kind::String,
+            });
+        }
+        if self.arrays > 0 {
+            kinds.push(Kind::Array(
+                self.items.as_deref().filter(|items| items.seen > 0),
+            ));
+        }
+        if self.objects > 0 {
+            kinds.push(Kind::Object(self));
+        }
+
+        match kinds.len() {
+            0 => Kind::Unknown,
+            1 => kinds.remove(0),
+            _ => Kind::Mixed(kinds),
+        }
+    }
+}
+
+fn is_zero(count: &u64) -> bool {
+    *count == 0
+}
+
+fn is_email(text: &str) -> bool {
+    match text.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !text.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_uri(text: &str) -> bool {
+    match text.split_once("://") {
+        Some((scheme, rest)) => {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                && !rest.is_empty()
+                && !text.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn infer(payloads: &[Value]) -> Shape {
+        Shape::infer(payloads)
+    }
+
+    #[test]
+    fn fields_missing_from_some_payloads_are_optional() {
+        let shape = infer(&[
+            json!({ "id": 1, "note": "a" }),
+            json!({ "id": 2, "note": null }),
+            json!({ "id": 3 }),
+        ]);
+        let id = &shape.fields["id"];
+        let note = &shape.fields["note"];
+        assert!(!shape.optional(id));
+        assert!(shape.optional(note));
+        assert!(note.nullable());
+        assert!(!id.nullable());
+        assert!(matches!(id.kind(), Kind::Integer));
+    }
+
+    #[test]
+    fn integers_widen_to_numbers_and_other_types_mix() {
+        assert!(matches!(
+            infer(&[json!(1), json!(1.5)]).kind(),
+            Kind::Number
+        ));
+        match infer(&[json!(1), json!("one"), json!(true)]).kind() {
+            Kind::Mixed(kinds) => {
+                assert!(matches!(
+                    kinds[..],
+                    [Kind::Boolean, Kind::Integer, Kind::String]
+                ))
+            }
+            _ => panic!("expected a mixed kind"),
+        }
+        assert!(matches!(infer(&[json!(null)]).kind(), Kind::Unknown));
+        assert!(matches!(infer(&[json!([])]).kind(), Kind::Array(None)));
+        assert!(matches!(
+            infer(&[json!([1, 2])]).kind(),
+            Kind::Array(Some(_))
+        ));
+    }
+
+    #[test]
+    fn formats_apply_only_when_every_string_matches() {
+        let format = |values: &[Value]| infer(values).string_format();
+        assert_eq!(
+            format(&[
+                json!("2024-01-01T10:00:00Z"),
+                json!("2024-02-01T10:00:00+02:00")
+            ]),
+            Some(StringFormat::DateTime)
+        );
+        assert_eq!(
+            format(&[json!("67e55044-10b1-426f-9247-bb680e5fe0c8")]),
+            Some(StringFormat::Uuid)
+        );
+        assert_eq!(
+            format(&[json!("ada@example.com")]),
+            Some(StringFormat::Email)
+        );
+        assert_eq!(
+            format(&[json!("https://example.com/a")]),
+            Some(StringFormat::Uri)
+        );
+        assert_eq!(format(&[json!("ada@example.com"), json!("nobody")]), None);
+        assert_eq!(format(&[json!("a b@example.com"), json!("x@.com")]), None);
+        assert_eq!(format(&[json!(1)]), None);
+    }
+
+    #[test]
+    fn repeated_strings_from_a_small_set_are_enums() {
+        let statuses = ["paid", "open", "paid", "void"].map(|status| json!(status));
+        match infer(&statuses).kind() {
+            Kind::Enum(values) => {
+                assert_eq!(values.iter().collect::<Vec<_>>(), ["open", "paid", "void"])
+            }
+            _ => panic!("expected an enum"),
+        }
+
+        // Too few samples, no repeats, or too many distinct values
+        assert!(matches!(
+            infer(&[json!("a"), json!("a")]).kind(),
+            Kind::String
+        ));
+        assert!(matches!(
+            infer(&[json!("a"), json!("b"), json!("c")]).kind(),
+            Kind::String
+        ));
+        let many: Vec<Value> = (0..=MAX_ENUM_VALUES)
+            .chain([0])
+            .map(|n| json!(format!("v{}", n)))
+            .collect();
+        assert!(matches!(infer(&many).kind(), Kind::String));
+    }
+
+    #[test]
+    fn shapes_round_trip_without_zero_counters() {
+        let shape = infer(&[json!({ "tags": ["a"], "ok": true })]);
+        let stored = serde_json::to_value(&shape).unwrap();
+        assert_eq!(stored["fields"]["ok"], json!({ "seen": 1, "booleans": 1 }));
+
+        let restored: Shape = serde_json::from_value(stored.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), stored);
+    }
+}
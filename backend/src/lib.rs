@@ -1,8 +1,12 @@
 pub mod app;
 pub mod auth;
+pub mod capture;
 pub mod config;
 pub mod dao;
+pub mod delivery;
+pub mod expectation;
 pub mod handlers;
+pub mod inference;
 pub mod model;
 pub mod notification;
 pub mod schema;
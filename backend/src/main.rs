@@ -1,28 +1,46 @@
 use axum::{
     http::{header, StatusCode, Uri},
     response::IntoResponse,
-    routing::{delete, get, post},
+    routing::{any, delete, get, post, put},
     Router,
 };
 use clap::Parser;
 use rust_embed::RustEmbed;
 use std::sync::Arc;
-use std::{net::SocketAddr, time::Duration};
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::{info, Level};
 use turso::Builder;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+use hookspy::capture::{target, wire};
+use hookspy::delivery::run_delivery_worker;
+use hookspy::handlers::delivery::{
+    discard_webhook_delivery, get_webhook_deliveries, retry_webhook_delivery,
+};
+use hookspy::handlers::drift::{
+    delete_webhook_baseline, get_webhook_baselines, pin_webhook_baseline,
+};
+use hookspy::handlers::expectation::expect_webhook_requests;
+use hookspy::handlers::inference::infer_webhook_schema;
+use hookspy::handlers::replay::{get_webhook_request_replays, replay_webhook_request};
 use hookspy::handlers::webhook::{
-    create_webhook, delete_webhook, get_webhook, get_webhook_requests, list_webhooks,
-    receive_webhook,
+    create_webhook, delete_webhook, delete_webhook_fanout, delete_webhook_faults,
+    delete_webhook_forward, delete_webhook_json_schema, delete_webhook_script,
+    delete_webhook_sequence, delete_webhook_signature, get_next_webhook_request, get_webhook,
+    get_webhook_request_part, get_webhook_requests, list_webhooks, receive_webhook,
+    report_local_response, reset_webhook_response, reset_webhook_sequence, test_webhook_script,
+    update_webhook_fanout, update_webhook_faults, update_webhook_forward,
+    update_webhook_json_schema, update_webhook_response, update_webhook_script,
+    update_webhook_sequence, update_webhook_signature,
 };
 use hookspy::model::db::init_db;
 use hookspy::notification::notification::Notification;
 use hookspy::sweeper::run_sweeper;
 use hookspy::{
     app::AppState,
+    handlers::sse::{user_notifications_sse, webhook_notifications_sse},
     handlers::ws::{user_notifications_ws, webhook_notifications_ws},
 };
 use hookspy::{
@@ -63,8 +81,39 @@ async fn static_handler(uri: Uri) -> impl IntoResponse {
         hookspy::handlers::webhook::list_webhooks,
         hookspy::handlers::webhook::get_webhook,
         hookspy::handlers::webhook::delete_webhook,
+        hookspy::handlers::webhook::update_webhook_response,
+        hookspy::handlers::webhook::reset_webhook_response,
+        hookspy::handlers::webhook::update_webhook_script,
+        hookspy::handlers::webhook::delete_webhook_script,
+        hookspy::handlers::webhook::test_webhook_script,
+        hookspy::handlers::webhook::update_webhook_faults,
+        hookspy::handlers::webhook::delete_webhook_faults,
+        hookspy::handlers::webhook::update_webhook_sequence,
+        hookspy::handlers::webhook::delete_webhook_sequence,
+        hookspy::handlers::webhook::reset_webhook_sequence,
+        hookspy::handlers::webhook::update_webhook_forward,
+        hookspy::handlers::webhook::delete_webhook_forward,
+        hookspy::handlers::webhook::update_webhook_fanout,
+        hookspy::handlers::webhook::delete_webhook_fanout,
+        hookspy::handlers::webhook::update_webhook_signature,
+        hookspy::handlers::webhook::delete_webhook_signature,
+        hookspy::handlers::webhook::update_webhook_json_schema,
+        hookspy::handlers::webhook::delete_webhook_json_schema,
+        hookspy::handlers::delivery::get_webhook_deliveries,
+        hookspy::handlers::delivery::retry_webhook_delivery,
+        hookspy::handlers::delivery::discard_webhook_delivery,
         hookspy::handlers::webhook::receive_webhook,
         hookspy::handlers::webhook::get_webhook_requests,
+        hookspy::handlers::webhook::get_next_webhook_request,
+        hookspy::handlers::webhook::get_webhook_request_part,
+        hookspy::handlers::webhook::report_local_response,
+        hookspy::handlers::replay::replay_webhook_request,
+        hookspy::handlers::replay::get_webhook_request_replays,
+        hookspy::handlers::inference::infer_webhook_schema,
+        hookspy::handlers::drift::get_webhook_baselines,
+        hookspy::handlers::drift::pin_webhook_baseline,
+        hookspy::handlers::drift::delete_webhook_baseline,
+        hookspy::handlers::expectation::expect_webhook_requests,
         hookspy::handlers::auth::login,
         hookspy::handlers::auth::callback,
     ),
@@ -75,11 +124,59 @@ async fn static_handler(uri: Uri) -> impl IntoResponse {
             hookspy::model::webhook::Webhook,
             hookspy::schema::webhook::CreateWebhookRequest,
             hookspy::schema::webhook::WebhookRequest,
+            hookspy::schema::webhook::QueryParam,
+            hookspy::schema::webhook::BodyEncoding,
+            hookspy::schema::webhook::BodyFormat,
+            hookspy::schema::webhook::RequestHeader,
+            hookspy::schema::webhook::WebhookRequestPart,
+            hookspy::schema::webhook::CloudEvent,
+            hookspy::schema::webhook::CloudEventMode,
+            hookspy::schema::webhook::ResponseConfig,
+            hookspy::schema::webhook::SentResponse,
+            hookspy::schema::webhook::WebhookScript,
+            hookspy::schema::webhook::TestScriptRequest,
+            hookspy::capture::script::ScriptOutcome,
+            hookspy::schema::webhook::FaultProfile,
+            hookspy::schema::webhook::InjectedFault,
+            hookspy::schema::webhook::FaultAction,
+            hookspy::schema::webhook::ResponseSequence,
+            hookspy::schema::webhook::SequenceEnd,
+            hookspy::schema::webhook::SequencePosition,
+            hookspy::schema::webhook::ForwardTarget,
+            hookspy::schema::webhook::UpstreamResponse,
+            hookspy::schema::webhook::ResponseHeader,
+            hookspy::schema::delivery::FanOutConfig,
+            hookspy::schema::delivery::Delivery,
+            hookspy::schema::delivery::DeliveryAttempt,
+            hookspy::schema::delivery::DeliveryStatus,
+            hookspy::schema::replay::ReplayRequest,
+            hookspy::schema::replay::Replay,
+            hookspy::schema::signature::SignatureConfig,
+            hookspy::schema::signature::SignatureProvider,
+            hookspy::schema::signature::SignatureStatus,
+            hookspy::schema::signature::SignatureCheck,
+            hookspy::schema::json_schema::WebhookJsonSchema,
+            hookspy::schema::inference::SchemaFormat,
+            hookspy::schema::inference::InferredSchema,
+            hookspy::schema::drift::ShapeChange,
+            hookspy::schema::drift::ShapeChangeKind,
+            hookspy::schema::drift::Baseline,
+            hookspy::schema::drift::PinBaselineRequest,
+            hookspy::schema::drift::DriftEvent,
+            hookspy::schema::expectation::ExpectationRequest,
+            hookspy::schema::expectation::HeaderMatcher,
+            hookspy::schema::expectation::JsonPathMatcher,
+            hookspy::schema::expectation::ExpectedCount,
+            hookspy::schema::expectation::RequestSummary,
+            hookspy::schema::expectation::NearMiss,
+            hookspy::schema::expectation::ExpectationReport,
+            hookspy::schema::json_schema::SchemaViolation,
+            hookspy::schema::json_schema::SchemaValidation,
             hookspy::model::user::User,
             hookspy::handlers::error::ErrorBody,
         )
     ),
-    modifiers(&SecurityAddon),
+    modifiers(&SecurityAddon, &CaptureMethodsAddon),
     tags(
         (name = "admin", description = "Administrative endpoints for monitoring and statistics"),
         (name = "auth", description = "Authentication via Google OAuth2"),
@@ -96,6 +193,40 @@ struct ApiDoc;
 
 struct SecurityAddon;
 
+/// `receive_webhook` answers every HTTP method, but a `utoipa::path` documents only
+/// one; its POST operation is copied to the other methods.
+struct CaptureMethodsAddon;
+
+impl utoipa::Modify for CaptureMethodsAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        use utoipa::openapi::PathItemType;
+
+        let Some(item) = openapi
+            .paths
+            .paths
+            .get_mut("/api/hooks/{webhook_id}/{path}")
+        else {
+            return;
+        };
+        let Some(operation) = item.operations.get(&PathItemType::Post).cloned() else {
+            return;
+        };
+
+        for (method, name) in [
+            (PathItemType::Get, "get"),
+            (PathItemType::Put, "put"),
+            (PathItemType::Patch, "patch"),
+            (PathItemType::Delete, "delete"),
+            (PathItemType::Head, "head"),
+            (PathItemType::Options, "options"),
+        ] {
+            let mut operation = operation.clone();
+            operation.operation_id = Some(format!("receive_webhook_{}", name));
+            item.operations.entry(method).or_insert(operation);
+        }
+    }
+}
+
 impl utoipa::Modify for SecurityAddon {
     fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
         if let Some(components) = openapi.components.as_mut() {
@@ -107,6 +238,15 @@ impl utoipa::Modify for SecurityAddon {
                     ),
                 ),
             );
+            components.add_security_scheme(
+                "bearer_auth",
+                utoipa::openapi::security::SecurityScheme::Http(
+                    utoipa::openapi::security::HttpBuilder::new()
+                        .scheme(utoipa::openapi::security::HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
         }
     }
 }
@@ -168,6 +308,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         notification: Arc::new(Mutex::new(Notification::new())),
         domain: args.domain,
         config: config.clone(),
+        http_client: target::http_client(config.allow_private_targets),
     };
 
     let api_routes = Router::new()
@@ -176,9 +317,117 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/webhooks", post(create_webhook))
         .route("/webhooks", get(list_webhooks))
         .route("/webhooks/:webhook_id/requests", get(get_webhook_requests))
+        .route(
+            "/webhooks/:webhook_id/requests/next",
+            get(get_next_webhook_request),
+        )
+        .route(
+            "/webhooks/:webhook_id/requests/:request_id/parts/:part_id",
+            get(get_webhook_request_part),
+        )
+        .route(
+            "/webhooks/:webhook_id/requests/:request_id/local-response",
+            put(report_local_response),
+        )
+        .route(
+            "/webhooks/:webhook_id/requests/:request_id/replay",
+            post(replay_webhook_request),
+        )
+        .route(
+            "/webhooks/:webhook_id/requests/:request_id/replays",
+            get(get_webhook_request_replays),
+        )
+        .route("/webhooks/:webhook_id/schema", get(infer_webhook_schema))
+        .route(
+            "/webhooks/:webhook_id/baselines",
+            get(get_webhook_baselines),
+        )
+        .route("/webhooks/:webhook_id/baselines", put(pin_webhook_baseline))
+        .route(
+            "/webhooks/:webhook_id/baselines",
+            delete(delete_webhook_baseline),
+        )
+        .route(
+            "/webhooks/:webhook_id/expectations",
+            post(expect_webhook_requests),
+        )
         .route("/webhooks/:webhook_id", get(get_webhook))
         .route("/webhooks/:webhook_id", post(receive_webhook))
+        .route("/hooks/:webhook_id", any(receive_webhook))
+        .route("/hooks/:webhook_id/*path", any(receive_webhook))
         .route("/webhooks/:webhook_id", delete(delete_webhook))
+        .route(
+            "/webhooks/:webhook_id/response",
+            put(update_webhook_response),
+        )
+        .route(
+            "/webhooks/:webhook_id/response",
+            delete(reset_webhook_response),
+        )
+        .route("/webhooks/:webhook_id/script", put(update_webhook_script))
+        .route(
+            "/webhooks/:webhook_id/script",
+            delete(delete_webhook_script),
+        )
+        .route(
+            "/webhooks/:webhook_id/script/test",
+            post(test_webhook_script),
+        )
+        .route("/webhooks/:webhook_id/faults", put(update_webhook_faults))
+        .route(
+            "/webhooks/:webhook_id/faults",
+            delete(delete_webhook_faults),
+        )
+        .route(
+            "/webhooks/:webhook_id/sequence",
+            put(update_webhook_sequence),
+        )
+        .route(
+            "/webhooks/:webhook_id/sequence",
+            delete(delete_webhook_sequence),
+        )
+        .route(
+            "/webhooks/:webhook_id/sequence/reset",
+            post(reset_webhook_sequence),
+        )
+        .route("/webhooks/:webhook_id/forward", put(update_webhook_forward))
+        .route(
+            "/webhooks/:webhook_id/forward",
+            delete(delete_webhook_forward),
+        )
+        .route("/webhooks/:webhook_id/fanout", put(update_webhook_fanout))
+        .route(
+            "/webhooks/:webhook_id/fanout",
+            delete(delete_webhook_fanout),
+        )
+        .route(
+            "/webhooks/:webhook_id/signature",
+            put(update_webhook_signature),
+        )
+        .route(
+            "/webhooks/:webhook_id/signature",
+            delete(delete_webhook_signature),
+        )
+        .route(
+            "/webhooks/:webhook_id/json-schema",
+            put(update_webhook_json_schema),
+        )
+        .route(
+            "/webhooks/:webhook_id/json-schema",
+            delete(delete_webhook_json_schema),
+        )
+        .route(
+            "/webhooks/:webhook_id/deliveries",
+            get(get_webhook_deliveries),
+        )
+        .route(
+            "/webhooks/:webhook_id/deliveries/:delivery_id/retry",
+            post(retry_webhook_delivery),
+        )
+        .route(
+            "/webhooks/:webhook_id/deliveries/:delivery_id",
+            delete(discard_webhook_delivery),
+        )
         .route("/auth/callback", get(callback))
         .route("/auth/login", get(login))
         .route("/auth/logout", post(logout));
@@ -190,10 +439,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .route("/user/notifications", get(user_notifications_ws));
 
+    let sse_routes = Router::new()
+        .route(
+            "/webhooks/:webhook_id/notifications",
+            get(webhook_notifications_sse),
+        )
+        .route("/user/notifications", get(user_notifications_sse));
+
     let app = Router::new()
         .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .nest("/api", api_routes)
         .nest("/ws", ws_routes)
+        .nest("/sse", sse_routes)
         .with_state(state.clone())
         .fallback(static_handler);
 
@@ -201,16 +458,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Server running on http://{}", args.address);
     info!("Swagger UI available at http://{}/docs", args.address);
 
+    tokio::spawn(run_delivery_worker(
+        state.clone(),
+        Duration::from_millis(config.delivery_poll_interval_ms),
+    ));
+
     tokio::spawn(run_sweeper(
         state,
         Duration::from_secs(config.sweep_interval_seconds),
     ));
 
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .await?;
+    // Keeps the raw request heads so captured headers are stored as sent
+    wire::serve(listener, app).await;
 
     Ok(())
 }
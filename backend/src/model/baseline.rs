@@ -0,0 +1,35 @@
+use crate::inference::drift::LEARNING_SAMPLES;
+use crate::inference::{export, Shape};
+use crate::schema::drift::Baseline;
+
+/// Baseline shape of one webhook and event type, as stored.
+pub struct StoredBaseline {
+    /// Empty for requests without a detected event type
+    pub event_type: String,
+    pub shape: Shape,
+    pub pinned: bool,
+    pub updated_at: String,
+}
+
+impl StoredBaseline {
+    /// Whether captured payloads are still being merged in rather than checked.
+    pub fn learning(&self) -> bool {
+        !self.pinned && self.shape.seen < LEARNING_SAMPLES
+    }
+
+    pub fn to_baseline(&self) -> Baseline {
+        let title = match self.event_type.as_str() {
+            "" => "Payload".to_string(),
+            event_type => export::type_name(event_type),
+        };
+
+        Baseline {
+            event_type: Some(self.event_type.clone()).filter(|e| !e.is_empty()),
+            samples: self.shape.seen,
+            pinned: self.pinned,
+            learning: self.learning(),
+            updated_at: self.updated_at.clone(),
+            schema: export::json_schema(&self.shape, &title),
+        }
+    }
+}
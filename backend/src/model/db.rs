@@ -1,5 +1,7 @@
 use turso::Connection;
 
+use crate::schema::webhook::{BodyEncoding, RequestHeader};
+
 pub async fn init_db(conn: &Connection) -> Result<(), turso::Error> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS users (
@@ -39,6 +41,56 @@ pub async fn init_db(conn: &Connection) -> Result<(), turso::Error> {
     )
     .await?;
 
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_request_parts (
+            id TEXT PRIMARY KEY,
+            request_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            name TEXT,
+            filename TEXT,
+            content_type TEXT,
+            headers TEXT NOT NULL,
+            data BLOB NOT NULL,
+            FOREIGN KEY (request_id) REFERENCES webhook_requests(id) ON DELETE CASCADE
+        )",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_request_parts_request_id ON webhook_request_parts (request_id)",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_request_events (
+            id TEXT PRIMARY KEY,
+            request_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            mode TEXT NOT NULL,
+            specversion TEXT,
+            event_id TEXT,
+            source TEXT,
+            type TEXT,
+            subject TEXT,
+            time TEXT,
+            datacontenttype TEXT,
+            dataschema TEXT,
+            extensions TEXT NOT NULL,
+            data TEXT,
+            FOREIGN KEY (request_id) REFERENCES webhook_requests(id) ON DELETE CASCADE
+        )",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_request_events_request_id ON webhook_request_events (request_id)",
+        (),
+    )
+    .await?;
+
     conn.execute("ALTER TABLE webhook_requests ADD COLUMN caller_ip TEXT", ())
         .await
         .ok(); // Ignore error if column already exists
@@ -50,9 +102,370 @@ pub async fn init_db(conn: &Connection) -> Result<(), turso::Error> {
     .await
     .ok(); // Ignore error if column already exists
 
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN path TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN query TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN body_raw BLOB", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN content_encoding TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN body_decoded BLOB",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN body_format TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN parsed_body TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN response_status INTEGER",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN response_headers TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN response_body TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN response_error TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN tags TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN script_error TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN fault_profile TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN fault TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN sequence TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhooks ADD COLUMN sequence_cursor INTEGER NOT NULL DEFAULT 0",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN sequence_hit INTEGER",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN sequence_step INTEGER",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN forward_target TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN upstream TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN local_response TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
     conn.execute("ALTER TABLE webhooks ADD COLUMN last_seen_at TEXT", ())
         .await
         .ok(); // Ignore error if column already exists
 
+    conn.execute("ALTER TABLE webhooks ADD COLUMN response_config TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN script TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN fanout TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN signature TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN signature TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN provider TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN event_type TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_requests_event_type ON webhook_requests (webhook_id, provider, event_type)",
+        (),
+    )
+    .await?;
+
+    conn.execute("ALTER TABLE webhooks ADD COLUMN json_schema TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN schema_valid INTEGER",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "ALTER TABLE webhook_requests ADD COLUMN schema_errors TEXT",
+        (),
+    )
+    .await
+    .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_requests_schema_valid ON webhook_requests (webhook_id, schema_valid)",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS deliveries (
+            id TEXT PRIMARY KEY,
+            webhook_id TEXT NOT NULL,
+            request_id TEXT NOT NULL,
+            url TEXT NOT NULL,
+            timeout_ms INTEGER NOT NULL,
+            initial_backoff_ms INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL,
+            attempt_limit INTEGER NOT NULL,
+            next_attempt_at TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            last_error TEXT,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE,
+            FOREIGN KEY (request_id) REFERENCES webhook_requests(id) ON DELETE CASCADE
+        )",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_deliveries_due ON deliveries (status, next_attempt_at)",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS delivery_attempts (
+            id TEXT PRIMARY KEY,
+            delivery_id TEXT NOT NULL,
+            attempt INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            status INTEGER,
+            latency_us INTEGER NOT NULL,
+            error TEXT,
+            response_body TEXT,
+            FOREIGN KEY (delivery_id) REFERENCES deliveries(id) ON DELETE CASCADE
+        )",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_delivery_attempts_delivery_id ON delivery_attempts (delivery_id)",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_request_replays (
+            id TEXT PRIMARY KEY,
+            webhook_id TEXT NOT NULL,
+            request_id TEXT NOT NULL,
+            replayed_at TEXT NOT NULL,
+            method TEXT NOT NULL,
+            headers TEXT NOT NULL,
+            body_override TEXT,
+            response TEXT NOT NULL,
+            FOREIGN KEY (request_id) REFERENCES webhook_requests(id) ON DELETE CASCADE
+        )",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_request_replays_request_id ON webhook_request_replays (request_id)",
+        (),
+    )
+    .await?;
+
+    conn.execute("ALTER TABLE webhook_requests ADD COLUMN drift TEXT", ())
+        .await
+        .ok(); // Ignore error if column already exists
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_baselines (
+            webhook_id TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            shape TEXT NOT NULL,
+            pinned INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (webhook_id, event_type),
+            FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+        )",
+        (),
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            name TEXT PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        )",
+        (),
+    )
+    .await?;
+
+    migrate_headers_to_list(conn).await?;
+
+    // Bodies are kept in body_raw; drop the text copies older versions also wrote
+    migrate_once(
+        conn,
+        "clear_text_body_copies",
+        "UPDATE webhook_requests SET body = '' WHERE body_raw IS NOT NULL AND body != ''",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Runs `sql` unless the migration `name` is already recorded in `schema_migrations`.
+async fn migrate_once(conn: &Connection, name: &str, sql: &str) -> Result<(), turso::Error> {
+    let mut rows = conn
+        .query(
+            "SELECT 1 FROM schema_migrations WHERE name = ?",
+            turso::params![name],
+        )
+        .await?;
+    if rows.next().await?.is_some() {
+        return Ok(());
+    }
+    drop(rows);
+
+    conn.execute(sql, ()).await?;
+    conn.execute(
+        "INSERT INTO schema_migrations (name, applied_at) VALUES (?, ?)",
+        turso::params![name, chrono::Utc::now().to_rfc3339()],
+    )
+    .await?;
+    Ok(())
+}
+
+/// Rewrites headers stored as a JSON object (one value per name) into the
+/// ordered `RequestHeader` list used since repeated headers are preserved.
+async fn migrate_headers_to_list(conn: &Connection) -> Result<(), turso::Error> {
+    let mut rows = conn
+        .query(
+            "SELECT id, headers FROM webhook_requests WHERE headers LIKE '{%'",
+            (),
+        )
+        .await?;
+
+    let mut legacy = Vec::new();
+    while let Some(row) = rows.next().await? {
+        let id: String = row.get(0)?;
+        let headers: String = row.get(1)?;
+        legacy.push((id, headers));
+    }
+
+    for (id, headers) in legacy {
+        let map: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&headers).unwrap_or_default();
+
+        let list: Vec<RequestHeader> = map
+            .into_iter()
+            .map(|(name, value)| RequestHeader {
+                name,
+                value: value.as_str().unwrap_or_default().to_string(),
+                encoding: BodyEncoding::Utf8,
+            })
+            .collect();
+
+        let list_json = serde_json::to_string(&list).unwrap_or_else(|_| "[]".to_string());
+
+        conn.execute(
+            "UPDATE webhook_requests SET headers = ? WHERE id = ?",
+            turso::params![list_json, id],
+        )
+        .await?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,23 @@
+/// A delivery the worker should attempt now.
+pub struct DueDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub request_id: String,
+    pub url: String,
+    pub timeout_ms: u64,
+    pub initial_backoff_ms: u64,
+    pub attempts: u32,
+    pub attempt_limit: u32,
+}
+
+/// What the worker learned from one attempt, as handed to
+/// `DeliveryDao::record_delivery_attempt`.
+pub struct AttemptOutcome {
+    pub started_at: String,
+    pub status: Option<u16>,
+    pub latency_us: u64,
+    pub error: Option<String>,
+    pub response_body: Option<String>,
+    /// The target resolved to an address deliveries may not reach, so it is not retried
+    pub refused: bool,
+}
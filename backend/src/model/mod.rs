@@ -1,4 +1,6 @@
+pub mod baseline;
 pub mod db;
+pub mod delivery;
 pub mod stats;
 pub mod user;
 pub mod webhook;
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::schema::{
+    delivery::FanOutConfig,
+    signature::SignatureConfig,
+    webhook::{FaultProfile, ForwardTarget, RequestHeader, ResponseConfig, ResponseSequence},
+};
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct Webhook {
     pub id: String,
@@ -9,4 +15,45 @@ pub struct Webhook {
     pub created_at: String,
     pub last_seen_at: Option<String>,
     pub has_unread: bool,
+    /// Custom reply for the capture endpoint; `None` answers with the stored request
+    pub response: Option<ResponseConfig>,
+    /// Rhai script run for every captured request
+    pub script: Option<String>,
+    /// Failures injected into the capture endpoint's replies
+    pub fault_profile: Option<FaultProfile>,
+    /// Replies sent in order, taking precedence over `response`
+    pub sequence: Option<ResponseSequence>,
+    /// Requests the sequence has answered since it was saved or reset
+    pub sequence_cursor: u64,
+    /// Upstream captured requests are relayed to, taking precedence over `sequence`
+    pub forward: Option<ForwardTarget>,
+    /// Targets every captured request is delivered to in the background, with retries
+    pub fanout: Option<FanOutConfig>,
+    /// Provider and secret captured requests are verified with
+    pub signature: Option<SignatureConfig>,
+    /// JSON Schema captured JSON bodies are validated against
+    #[schema(value_type = Option<Object>)]
+    pub json_schema: Option<serde_json::Value>,
+}
+
+/// Per-webhook settings the capture endpoint applies to incoming requests.
+#[derive(Default)]
+pub struct CaptureSettings {
+    pub response: Option<ResponseConfig>,
+    pub script: Option<String>,
+    pub faults: Option<FaultProfile>,
+    pub sequence: Option<ResponseSequence>,
+    pub forward: Option<ForwardTarget>,
+    pub fanout: Option<FanOutConfig>,
+    pub signature: Option<SignatureConfig>,
+    pub json_schema: Option<serde_json::Value>,
+}
+
+/// A captured request as received, for sending it on again.
+pub struct RequestPayload {
+    pub method: String,
+    pub path: Option<String>,
+    pub query: Option<String>,
+    pub headers: Vec<RequestHeader>,
+    pub body: Vec<u8>,
 }
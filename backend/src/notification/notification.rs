@@ -1,12 +1,40 @@
-use axum::extract::ws::Message;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tokio::sync::mpsc;
 
+/// Events kept per stream for SSE clients resuming with `Last-Event-ID`.
+const HISTORY_LIMIT: usize = 50;
+
+/// A message sent to subscribers; `id` increases with every event across all streams.
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub id: u64,
+    pub data: String,
+}
+
+/// A past webhook event. Only the id of the request it announced is kept, so resuming
+/// clients are sent the request as currently stored and deleted ones are not replayed.
+#[derive(Clone, Debug)]
+pub struct RequestEvent {
+    pub id: u64,
+    pub request_id: String,
+}
+
 pub struct Notification {
     /// Per-webhook subscribers: webhook_id -> [(session_id, tx)]
-    pub subscribers: HashMap<String, Vec<(String, mpsc::Sender<Message>)>>,
+    pub subscribers: HashMap<String, Vec<(String, mpsc::Sender<Event>)>>,
     /// Per-user subscribers: user_id -> [(session_id, tx)]
-    pub user_subscribers: HashMap<String, Vec<(String, mpsc::Sender<Message>)>>,
+    pub user_subscribers: HashMap<String, Vec<(String, mpsc::Sender<Event>)>>,
+    /// Latest events per webhook, oldest first
+    pub history: HashMap<String, VecDeque<RequestEvent>>,
+    /// Latest events per user (webhook ids and drift summaries), oldest first
+    pub user_history: HashMap<String, VecDeque<Event>>,
+    next_id: u64,
+}
+
+impl Default for Notification {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Notification {
@@ -14,22 +42,22 @@ impl Notification {
         Self {
             subscribers: HashMap::new(),
             user_subscribers: HashMap::new(),
+            history: HashMap::new(),
+            user_history: HashMap::new(),
+            // Starting from the clock keeps ids growing across restarts, so a client
+            // resuming after one is not handed events it never saw as old ones
+            next_id: chrono::Utc::now().timestamp_millis().max(0) as u64,
         }
     }
 
-    pub fn subscribe(&mut self, webhook_id: String, session_id: String, tx: mpsc::Sender<Message>) {
+    pub fn subscribe(&mut self, webhook_id: String, session_id: String, tx: mpsc::Sender<Event>) {
         self.subscribers
             .entry(webhook_id)
             .or_default()
             .push((session_id, tx));
     }
 
-    pub fn subscribe_user(
-        &mut self,
-        user_id: String,
-        session_id: String,
-        tx: mpsc::Sender<Message>,
-    ) {
+    pub fn subscribe_user(&mut self, user_id: String, session_id: String, tx: mpsc::Sender<Event>) {
         self.user_subscribers
             .entry(user_id)
             .or_default()
@@ -47,18 +75,82 @@ impl Notification {
         });
     }
 
-    pub async fn notify(&mut self, webhook_id: String, message: String) {
+    /// Events of `webhook_id` newer than `last_id` that are still in its history.
+    pub fn webhook_events_after(&self, webhook_id: &str, last_id: u64) -> Vec<RequestEvent> {
+        events_after(self.history.get(webhook_id), last_id, |event| event.id)
+    }
+
+    /// Events of `user_id` newer than `last_id` that are still in its history.
+    pub fn user_events_after(&self, user_id: &str, last_id: u64) -> Vec<Event> {
+        events_after(self.user_history.get(user_id), last_id, |event| event.id)
+    }
+
+    /// Drops the history of a deleted webhook.
+    pub fn forget_webhook(&mut self, webhook_id: &str) {
+        self.history.remove(webhook_id);
+    }
+
+    /// Sends `message`, the serialized request `request_id`, to the webhook's subscribers.
+    pub async fn notify(&mut self, webhook_id: String, request_id: &str, message: String) {
+        let event = self.next_event(message);
+        record(
+            self.history.entry(webhook_id.clone()).or_default(),
+            RequestEvent {
+                id: event.id,
+                request_id: request_id.to_string(),
+            },
+        );
         if let Some(subs) = self.subscribers.get_mut(&webhook_id) {
-            subs.retain(|(_, tx)| tx.try_send(Message::Text(message.clone())).is_ok());
+            subs.retain(|(_, tx)| tx.try_send(event.clone()).is_ok());
         }
     }
 
     /// Notify all per-user subscribers that a new request arrived for `webhook_id`.
     /// The message payload is just the webhook_id so the frontend knows which item to mark unread.
     pub async fn notify_user(&mut self, user_id: &str, webhook_id: &str) {
+        self.send_user(user_id, webhook_id.to_string()).await;
+    }
+
+    /// Send a JSON event, tagged by its `type` field, to all per-user subscribers.
+    pub async fn send_user(&mut self, user_id: &str, message: String) {
+        let event = self.next_event(message);
+        record(
+            self.user_history.entry(user_id.to_string()).or_default(),
+            event.clone(),
+        );
         if let Some(subs) = self.user_subscribers.get_mut(user_id) {
-            let message = webhook_id.to_string();
-            subs.retain(|(_, tx)| tx.try_send(Message::Text(message.clone())).is_ok());
+            subs.retain(|(_, tx)| tx.try_send(event.clone()).is_ok());
+        }
+    }
+
+    fn next_event(&mut self, data: String) -> Event {
+        self.next_id += 1;
+        Event {
+            id: self.next_id,
+            data,
         }
     }
 }
+
+fn record<T>(history: &mut VecDeque<T>, event: T) {
+    if history.len() == HISTORY_LIMIT {
+        history.pop_front();
+    }
+    history.push_back(event);
+}
+
+fn events_after<T: Clone>(
+    history: Option<&VecDeque<T>>,
+    last_id: u64,
+    id: impl Fn(&T) -> u64,
+) -> Vec<T> {
+    history
+        .map(|events| {
+            events
+                .iter()
+                .filter(|event| id(event) > last_id)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
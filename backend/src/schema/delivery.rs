@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Targets that get an asynchronous copy of every captured request, with retries.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct FanOutConfig {
+    /// URLs to deliver to; the captured sub-path and query string are appended to each
+    pub targets: Vec<String>,
+    /// How long to wait for a target on each attempt
+    #[serde(default = "FanOutConfig::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Attempts per delivery before it is dead-lettered
+    #[serde(default = "FanOutConfig::default_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the first retry; every further retry waits twice as long
+    #[serde(default = "FanOutConfig::default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+}
+
+impl FanOutConfig {
+    pub const MAX_TARGETS: usize = 10;
+    pub const MAX_ATTEMPTS: u32 = 20;
+    pub const MAX_TIMEOUT_MS: u64 = 60_000;
+    /// Upper bound for `initial_backoff_ms` and for any single wait between attempts.
+    pub const MAX_BACKOFF_MS: u64 = 3_600_000;
+
+    fn default_timeout_ms() -> u64 {
+        10_000
+    }
+
+    fn default_max_attempts() -> u32 {
+        5
+    }
+
+    fn default_initial_backoff_ms() -> u64 {
+        1_000
+    }
+
+    /// Checks the targets and the retry policy.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.targets.is_empty() || self.targets.len() > Self::MAX_TARGETS {
+            return Err(format!("fan-out needs 1-{} targets", Self::MAX_TARGETS));
+        }
+
+        for target in self.targets.iter() {
+            let url = reqwest::Url::parse(target)
+                .map_err(|err| format!("invalid target URL {:?}: {}", target, err))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!(
+                    "target URL must be http or https, got {}",
+                    url.scheme()
+                ));
+            }
+        }
+
+        if self.timeout_ms == 0 || self.timeout_ms > Self::MAX_TIMEOUT_MS {
+            return Err(format!(
+                "delivery timeout must be 1-{} ms",
+                Self::MAX_TIMEOUT_MS
+            ));
+        }
+
+        if self.max_attempts == 0 || self.max_attempts > Self::MAX_ATTEMPTS {
+            return Err(format!("max attempts must be 1-{}", Self::MAX_ATTEMPTS));
+        }
+
+        if self.initial_backoff_ms == 0 || self.initial_backoff_ms > Self::MAX_BACKOFF_MS {
+            return Err(format!(
+                "initial backoff must be 1-{} ms",
+                Self::MAX_BACKOFF_MS
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Wait after the `attempt`-th failed attempt, doubling each time.
+pub fn retry_backoff_ms(initial_backoff_ms: u64, attempt: u32) -> u64 {
+    initial_backoff_ms
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(32))
+        .min(FanOutConfig::MAX_BACKOFF_MS)
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its next attempt
+    Pending,
+    /// A target answered with a 2xx status
+    Delivered,
+    /// Every attempt failed; waits for a manual retry or discard
+    Dead,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Dead => "dead",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<DeliveryStatus> {
+        match value {
+            "pending" => Some(DeliveryStatus::Pending),
+            "delivered" => Some(DeliveryStatus::Delivered),
+            "dead" => Some(DeliveryStatus::Dead),
+            _ => None,
+        }
+    }
+}
+
+/// One captured request queued for one fan-out target.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct Delivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub request_id: String,
+    /// Target URL, as configured when the request was captured
+    pub url: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// Attempts allowed before the delivery is dead-lettered
+    pub attempt_limit: u32,
+    /// When the next attempt is due, for pending deliveries
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_error: Option<String>,
+    /// Every attempt so far, oldest first
+    pub attempt_log: Vec<DeliveryAttempt>,
+}
+
+/// Outcome of one attempt at a delivery.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct DeliveryAttempt {
+    /// 1 for the first attempt
+    pub attempt: u32,
+    pub started_at: String,
+    /// Status the target answered with; missing when it could not be reached
+    pub status: Option<u16>,
+    pub latency_us: u64,
+    pub error: Option<String>,
+    /// Start of the target's response body
+    pub response_body: Option<String>,
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeChangeKind {
+    /// The field is not in the baseline
+    Added,
+    /// The baseline always had the field
+    Removed,
+    /// The value has a type the baseline never had here
+    TypeChanged,
+}
+
+/// One way a captured JSON body departs from the baseline shape of its event type.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ShapeChange {
+    /// JSON Pointer to the value; `*` stands for any array index
+    pub path: String,
+    pub change: ShapeChangeKind,
+    /// Types the baseline allows here, e.g. `string | null`; absent for added fields
+    pub expected: Option<String>,
+    /// Type found in the body; absent for removed fields
+    pub actual: Option<String>,
+}
+
+/// Shape captured JSON bodies of one event type are expected to have.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct Baseline {
+    /// Detected event type; absent for requests without one
+    pub event_type: Option<String>,
+    /// Payloads merged into the baseline
+    pub samples: u64,
+    /// Set by hand rather than learned from the first captured payloads
+    pub pinned: bool,
+    /// Still learning; requests are only checked once the baseline has enough samples
+    pub learning: bool,
+    pub updated_at: String,
+    /// The baseline as JSON Schema
+    #[schema(value_type = Object)]
+    pub schema: serde_json::Value,
+}
+
+/// Body of `PUT /api/webhooks/{webhook_id}/baselines`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PinBaselineRequest {
+    /// Event type to pin the baseline for; absent for requests without one
+    pub event_type: Option<String>,
+    /// Payloads to build the baseline from; the newest captured JSON bodies of the
+    /// event type when absent
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub payloads: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+pub struct BaselineParams {
+    pub event_type: Option<String>,
+}
+
+/// Sent on the user notification WebSocket when a captured body drifts from its
+/// baseline, as `{"type": "drift", ...}`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename = "drift")]
+pub struct DriftEvent {
+    pub webhook_id: String,
+    pub request_id: String,
+    pub event_type: Option<String>,
+    pub changes: Vec<ShapeChange>,
+}
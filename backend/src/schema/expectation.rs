@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Body of `POST /api/webhooks/{webhook_id}/expectations`: which requests to look for,
+/// how many of them, and how long to wait.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct ExpectationRequest {
+    /// HTTP method, compared case-insensitively
+    pub method: Option<String>,
+    /// Sub-path after the webhook URL, e.g. `/orders`; `/` for none
+    pub path: Option<String>,
+    /// Headers every matching request must carry
+    #[serde(default)]
+    pub headers: Vec<HeaderMatcher>,
+    /// JSONPath conditions on the JSON body
+    #[serde(default)]
+    pub json: Vec<JsonPathMatcher>,
+    /// Text the body must contain
+    pub body_contains: Option<String>,
+    #[serde(default)]
+    pub count: ExpectedCount,
+    /// How long to wait for the expectation to be met
+    #[serde(default = "ExpectationRequest::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Only requests received at or after this RFC 3339 time count; when absent, only
+    /// those received after the call starts, unless `history` is set
+    pub since: Option<String>,
+    /// Count every captured request when `since` is absent, not only new ones
+    #[serde(default)]
+    pub history: bool,
+}
+
+impl ExpectationRequest {
+    pub const MAX_TIMEOUT_MS: u64 = 120_000;
+
+    fn default_timeout_ms() -> u64 {
+        10_000
+    }
+
+    /// Checks the timeout and the time window; JSONPaths are checked when compiled.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_ms > Self::MAX_TIMEOUT_MS {
+            return Err(format!(
+                "timeout_ms must be at most {}",
+                Self::MAX_TIMEOUT_MS
+            ));
+        }
+        if let Some(since) = self.since.as_deref() {
+            chrono::DateTime::parse_from_rfc3339(since)
+                .map_err(|_| format!("since is not an RFC 3339 time: {:?}", since))?;
+            if self.history {
+                return Err("since and history cannot be combined".to_string());
+            }
+        }
+        if self.headers.iter().any(|header| header.name.is_empty()) {
+            return Err("header matchers need a name".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A header a matching request must carry.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct HeaderMatcher {
+    /// Compared case-insensitively
+    pub name: String,
+    /// Exact value; any value when absent
+    pub value: Option<String>,
+}
+
+/// A JSONPath condition on the JSON body, e.g. `$.event` equal to `"order.paid"`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct JsonPathMatcher {
+    /// `$`, `.key`, `['key']`, `[0]` and `[*]` are supported
+    pub path: String,
+    /// Value one of the selected nodes must equal; the path only has to exist when absent
+    #[schema(value_type = Option<Object>)]
+    pub equals: Option<serde_json::Value>,
+}
+
+/// How many matching requests are expected, as `{"exactly": 1}`, `{"at_least": 1}`
+/// or `"none"`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectedCount {
+    /// Holds until the timeout, failing as soon as there are more
+    Exactly(u64),
+    /// Passes as soon as there are enough
+    AtLeast(u64),
+    /// Holds until the timeout, failing as soon as one arrives
+    None,
+}
+
+impl Default for ExpectedCount {
+    fn default() -> Self {
+        ExpectedCount::AtLeast(1)
+    }
+}
+
+impl ExpectedCount {
+    pub fn describe(&self) -> String {
+        let requests = |n: u64| if n == 1 { "request" } else { "requests" };
+        match self {
+            ExpectedCount::Exactly(n) => format!("exactly {} matching {}", n, requests(*n)),
+            ExpectedCount::AtLeast(n) => format!("at least {} matching {}", n, requests(*n)),
+            ExpectedCount::None => "no matching requests".to_string(),
+        }
+    }
+}
+
+/// A captured request, as listed in an expectation report.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct RequestSummary {
+    pub id: String,
+    pub method: String,
+    pub path: Option<String>,
+    pub received_at: String,
+}
+
+/// A request that failed some of the matchers.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct NearMiss {
+    pub request: RequestSummary,
+    /// Why each failing matcher did not match, e.g. `method: expected POST, got GET`
+    pub failures: Vec<String>,
+}
+
+/// Outcome of an expectation.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ExpectationReport {
+    pub passed: bool,
+    /// What was expected and what was found
+    pub message: String,
+    pub count: ExpectedCount,
+    /// Matching requests found
+    pub matched: u64,
+    /// Time spent waiting
+    pub waited_ms: u64,
+    /// Matching requests, oldest first
+    pub matches: Vec<RequestSummary>,
+    /// Requests that failed the fewest matchers, at most 5
+    pub near_misses: Vec<NearMiss>,
+    /// More requests with the expected method and path arrived than are checked; only
+    /// the newest 1000 were looked at
+    pub truncated: bool,
+}
+
+/// Which captured requests `WebhookDao::scan_webhook_requests` lists.
+#[derive(Clone, Debug, Default)]
+pub struct ScanFilter {
+    /// Oldest `received_at` included, as a UTC RFC 3339 time
+    pub since: Option<String>,
+    /// Compared case-insensitively
+    pub method: Option<String>,
+    /// Sub-path without leading slashes; empty for none
+    pub path: Option<String>,
+    /// List the requests with another method or path instead of the matching ones
+    pub other_routes: bool,
+}
+
+/// The parts of a captured request an expectation is checked against.
+#[derive(Clone, Debug)]
+pub struct ScannedRequest {
+    pub id: String,
+    pub method: String,
+    pub path: Option<String>,
+    pub received_at: String,
+    /// JSON encoded list of `RequestHeader`
+    pub headers: String,
+    /// Body after undoing any `Content-Encoding`
+    pub body: Vec<u8>,
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Output language of `GET /api/webhooks/{webhook_id}/schema`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaFormat {
+    /// JSON Schema, draft 2020-12
+    #[default]
+    JsonSchema,
+    /// serde structs and enums
+    Rust,
+    /// TypeScript interfaces
+    Typescript,
+}
+
+#[derive(Deserialize)]
+pub struct InferSchemaParams {
+    pub event_type: Option<String>,
+    #[serde(default)]
+    pub format: SchemaFormat,
+    pub size: Option<u64>,
+}
+
+/// Schema merged from the JSON bodies a webhook captured.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct InferredSchema {
+    /// Number of JSON bodies the schema was inferred from
+    pub samples: u64,
+    /// Event types detected among the captured requests, for narrowing the inference
+    pub event_types: Vec<String>,
+    pub format: SchemaFormat,
+    /// The schema rendered in `format`; empty when there were no samples
+    pub content: String,
+}
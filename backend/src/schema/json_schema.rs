@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Body of `PUT /api/webhooks/{webhook_id}/json-schema`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct WebhookJsonSchema {
+    /// JSON Schema every captured JSON body is validated against
+    #[schema(value_type = Object)]
+    pub schema: serde_json::Value,
+}
+
+/// One way a captured body breaks the webhook's JSON Schema.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the offending value in the body; empty for the body itself
+    pub path: String,
+    /// JSON Pointer to the schema keyword that failed, e.g. `/properties/id/type`
+    pub schema_path: String,
+    pub message: String,
+}
+
+/// Outcome of validating a captured JSON body against the webhook's JSON Schema.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct SchemaValidation {
+    pub valid: bool,
+    /// Empty when `valid`; only the first 50 are kept
+    pub errors: Vec<SchemaViolation>,
+}
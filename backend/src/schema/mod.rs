@@ -1 +1,8 @@
+pub mod delivery;
+pub mod drift;
+pub mod expectation;
+pub mod inference;
+pub mod json_schema;
+pub mod replay;
+pub mod signature;
 pub mod webhook;
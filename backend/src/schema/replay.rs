@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::schema::webhook::{
+    validate_header_rewrites, RequestHeader, ResponseHeader, UpstreamResponse,
+};
+
+/// Body of `POST /api/webhooks/{webhook_id}/requests/{request_id}/replay`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct ReplayRequest {
+    /// URL to send the request to, used as is
+    pub url: String,
+    /// How long to wait for an answer
+    #[serde(default = "ReplayRequest::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Headers added to the replayed request, replacing any captured under the same name
+    #[serde(default)]
+    pub set_headers: Vec<ResponseHeader>,
+    /// Names of captured headers left out of the replayed request
+    #[serde(default)]
+    pub remove_headers: Vec<String>,
+    /// Body sent instead of the captured one, without the captured `Content-Encoding`
+    pub body: Option<String>,
+}
+
+impl ReplayRequest {
+    /// Upper bound for `timeout_ms`.
+    pub const MAX_TIMEOUT_MS: u64 = 60_000;
+
+    fn default_timeout_ms() -> u64 {
+        10_000
+    }
+
+    /// Checks that the URL is absolute HTTP(S) and the headers can be put on the wire.
+    pub fn validate(&self) -> Result<(), String> {
+        let url = reqwest::Url::parse(&self.url)
+            .map_err(|err| format!("invalid replay URL {:?}: {}", self.url, err))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!(
+                "replay URL must be http or https, got {}",
+                url.scheme()
+            ));
+        }
+
+        if self.timeout_ms == 0 || self.timeout_ms > Self::MAX_TIMEOUT_MS {
+            return Err(format!(
+                "replay timeout must be 1-{} ms",
+                Self::MAX_TIMEOUT_MS
+            ));
+        }
+
+        validate_header_rewrites(&self.set_headers, &self.remove_headers)
+    }
+}
+
+/// One replay of a captured request and what the target answered.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct Replay {
+    pub id: String,
+    pub request_id: String,
+    pub replayed_at: String,
+    pub method: String,
+    /// Headers sent, after the overrides were applied
+    pub headers: Vec<RequestHeader>,
+    /// Body sent instead of the captured one, if it was overridden
+    pub body_override: Option<String>,
+    pub response: UpstreamResponse,
+}
@@ -0,0 +1,117 @@
+use axum::http::HeaderName;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Sender whose signing scheme a webhook checks captured requests against.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureProvider {
+    /// `X-Hub-Signature-256: sha256=<hex>` over the body
+    Github,
+    /// `Stripe-Signature: t=<timestamp>,v1=<hex>` over `<timestamp>.<body>`
+    Stripe,
+    /// `X-Slack-Signature: v0=<hex>` over `v0:<X-Slack-Request-Timestamp>:<body>`
+    Slack,
+    /// `X-Shopify-Hmac-Sha256: <base64>` over the body
+    Shopify,
+    /// `X-Twilio-Signature: <base64>`, HMAC-SHA1 over the URL and sorted form fields
+    Twilio,
+    /// HMAC-SHA256 of the body in `header`, hex or base64, with an optional `sha256=` prefix
+    HmacSha256,
+}
+
+impl SignatureProvider {
+    /// Header the provider sends its signature in.
+    pub fn header(&self) -> &'static str {
+        match self {
+            SignatureProvider::Github => "x-hub-signature-256",
+            SignatureProvider::Stripe => "stripe-signature",
+            SignatureProvider::Slack => "x-slack-signature",
+            SignatureProvider::Shopify => "x-shopify-hmac-sha256",
+            SignatureProvider::Twilio => "x-twilio-signature",
+            SignatureProvider::HmacSha256 => SignatureConfig::DEFAULT_HEADER,
+        }
+    }
+}
+
+/// Provider and signing secret a webhook verifies captured requests with.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct SignatureConfig {
+    pub provider: SignatureProvider,
+    /// Signing secret, or the auth token for Twilio
+    pub secret: String,
+    /// Header carrying the signature for `hmac_sha256`; ignored for the other providers
+    #[serde(default)]
+    pub header: Option<String>,
+}
+
+impl SignatureConfig {
+    /// Header `hmac_sha256` reads when none is given.
+    pub const DEFAULT_HEADER: &'static str = "x-signature";
+
+    /// Header the signature is read from.
+    pub fn header(&self) -> String {
+        match (self.provider, self.header.as_ref()) {
+            (SignatureProvider::HmacSha256, Some(header)) => header.to_ascii_lowercase(),
+            (provider, _) => provider.header().to_string(),
+        }
+    }
+
+    /// Checks that there is a secret and the custom header is a valid name.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.secret.is_empty() {
+            return Err("signing secret must not be empty".to_string());
+        }
+
+        if let Some(header) = &self.header {
+            HeaderName::from_bytes(header.as_bytes())
+                .map_err(|_| format!("invalid header name {:?}", header))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// The received signature matches the expected one
+    Verified,
+    /// A signature was received but does not match
+    Mismatched,
+    /// The signature header, or something it depends on, was not sent
+    Missing,
+}
+
+/// Outcome of checking one captured request against the webhook's signing secret.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct SignatureCheck {
+    pub provider: SignatureProvider,
+    pub status: SignatureStatus,
+    /// Header the signature was read from
+    pub header: String,
+    /// Header value as received
+    pub received: Option<String>,
+    /// Signature computed from the secret, in the form the provider sends it
+    pub expected: Option<String>,
+    /// What was signed, e.g. `<timestamp>.<body>` for Stripe, cut short for long bodies
+    pub signed_payload: Option<String>,
+    /// Why the check could not be completed, e.g. a missing timestamp header
+    pub detail: Option<String>,
+}
+
+impl SignatureCheck {
+    /// Outcome without the received, expected and signed values, which are only
+    /// served to the webhook's owner.
+    pub fn public(&self) -> SignatureCheck {
+        SignatureCheck {
+            provider: self.provider,
+            status: self.status,
+            header: self.header.clone(),
+            received: None,
+            expected: None,
+            signed_payload: None,
+            detail: self.detail.clone(),
+        }
+    }
+}
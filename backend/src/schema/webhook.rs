@@ -1,19 +1,805 @@
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::schema::delivery::FanOutConfig;
+use crate::schema::drift::ShapeChange;
+use crate::schema::json_schema::SchemaValidation;
+use crate::schema::signature::{SignatureCheck, SignatureConfig};
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateWebhookRequest {
     pub name: String,
 }
 
+/// A header sent back by the capture endpoint.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ResponseHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// Reply the capture endpoint sends for a webhook instead of the stored request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ResponseConfig {
+    /// HTTP status code, 200-599
+    pub status: u16,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<ResponseHeader>,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl ResponseConfig {
+    /// Checks that the status code and headers can be put on the wire.
+    pub fn validate(&self) -> Result<(), String> {
+        match StatusCode::from_u16(self.status) {
+            Ok(status) if !status.is_informational() => {}
+            _ => return Err(format!("invalid status code {}", self.status)),
+        }
+
+        if let Some(content_type) = &self.content_type {
+            HeaderValue::from_str(content_type)
+                .map_err(|_| format!("invalid content type {:?}", content_type))?;
+        }
+
+        for header in self.headers.iter() {
+            HeaderName::from_bytes(header.name.as_bytes())
+                .map_err(|_| format!("invalid header name {:?}", header.name))?;
+            HeaderValue::from_str(&header.value)
+                .map_err(|_| format!("invalid value for header {:?}", header.name))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Upstream a webhook relays captured requests to, replying with whatever it answers.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ForwardTarget {
+    /// Base URL; the captured sub-path and query string are appended to it
+    pub url: String,
+    /// How long to wait for the upstream before answering 504
+    #[serde(default = "ForwardTarget::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Headers added to the forwarded request, replacing any received under the same name
+    #[serde(default)]
+    pub set_headers: Vec<ResponseHeader>,
+    /// Names of received headers left out of the forwarded request
+    #[serde(default)]
+    pub remove_headers: Vec<String>,
+}
+
+impl ForwardTarget {
+    /// Upper bound for `timeout_ms`.
+    pub const MAX_TIMEOUT_MS: u64 = 60_000;
+
+    fn default_timeout_ms() -> u64 {
+        10_000
+    }
+
+    /// Checks that the URL is absolute HTTP(S) and the headers can be put on the wire.
+    pub fn validate(&self) -> Result<(), String> {
+        let url = reqwest::Url::parse(&self.url)
+            .map_err(|err| format!("invalid forward URL {:?}: {}", self.url, err))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!(
+                "forward URL must be http or https, got {}",
+                url.scheme()
+            ));
+        }
+
+        if self.timeout_ms == 0 || self.timeout_ms > Self::MAX_TIMEOUT_MS {
+            return Err(format!(
+                "forward timeout must be 1-{} ms",
+                Self::MAX_TIMEOUT_MS
+            ));
+        }
+
+        validate_header_rewrites(&self.set_headers, &self.remove_headers)
+    }
+}
+
+/// Checks that headers to set can be put on the wire and headers to remove are valid names.
+pub fn validate_header_rewrites(set: &[ResponseHeader], remove: &[String]) -> Result<(), String> {
+    for header in set.iter() {
+        HeaderName::from_bytes(header.name.as_bytes())
+            .map_err(|_| format!("invalid header name {:?}", header.name))?;
+        HeaderValue::from_str(&header.value)
+            .map_err(|_| format!("invalid value for header {:?}", header.name))?;
+    }
+
+    for name in remove.iter() {
+        HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("invalid header name {:?}", name))?;
+    }
+
+    Ok(())
+}
+
+/// What the forward target answered for a captured request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct UpstreamResponse {
+    /// URL the request was forwarded to
+    pub url: String,
+    /// Missing when no response arrived
+    pub status: Option<u16>,
+    pub headers: Vec<RequestHeader>,
+    /// Response body, as text or base64 depending on `body_encoding`
+    pub body: String,
+    pub body_encoding: BodyEncoding,
+    /// Time from sending the request until the whole response was read
+    pub latency_us: u64,
+    /// Why the upstream could not be reached or read, e.g. a refused connection or a timeout
+    pub error: Option<String>,
+}
+
+/// Ordered replies a webhook walks through, one step per captured request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ResponseSequence {
+    /// Replies in order; each is a template, like a `ResponseConfig`
+    pub steps: Vec<ResponseConfig>,
+    /// What happens once the last step has been sent
+    #[serde(default)]
+    pub after_last: SequenceEnd,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceEnd {
+    /// Keep sending the last step
+    #[default]
+    StickOnLast,
+    /// Start over from the first step
+    Loop,
+}
+
+impl ResponseSequence {
+    /// Upper bound for the number of steps.
+    pub const MAX_STEPS: usize = 100;
+
+    /// Checks that there is at least one step and that every step can be sent.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("a sequence needs at least one step".to_string());
+        }
+
+        if self.steps.len() > Self::MAX_STEPS {
+            return Err(format!("a sequence has at most {} steps", Self::MAX_STEPS));
+        }
+
+        for (index, step) in self.steps.iter().enumerate() {
+            step.validate()
+                .map_err(|err| format!("step {}: {}", index + 1, err))?;
+        }
+
+        Ok(())
+    }
+
+    /// Index of the step answering the `hit`-th request since the sequence was reset.
+    pub fn step(&self, hit: u64) -> usize {
+        let last = self.steps.len().saturating_sub(1);
+        match self.after_last {
+            SequenceEnd::StickOnLast => (hit as usize).min(last),
+            SequenceEnd::Loop => (hit % self.steps.len().max(1) as u64) as usize,
+        }
+    }
+}
+
+/// Where a captured request fell in its webhook's response sequence.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct SequencePosition {
+    /// Zero-based number of the hit since the sequence was saved or reset
+    pub hit: u64,
+    /// Zero-based index of the step that was due
+    pub step: usize,
+}
+
+/// Failures a webhook injects into its replies, to exercise a sender's retry logic.
+///
+/// The rates are percentages of requests and are rolled together, so they may add up
+/// to at most 100.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct FaultProfile {
+    /// Delay added before every reply, in milliseconds
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Extra random delay of up to this many milliseconds
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Percentage of requests answered with `error_status` instead of the usual reply
+    #[serde(default)]
+    pub error_rate: u8,
+    /// Status code for injected errors, 500-599
+    #[serde(default = "FaultProfile::default_error_status")]
+    pub error_status: u16,
+    /// Percentage of requests whose connection is dropped without a reply
+    #[serde(default)]
+    pub reset_rate: u8,
+    /// Percentage of requests left unanswered for `timeout_ms` before the connection is dropped
+    #[serde(default)]
+    pub timeout_rate: u8,
+    #[serde(default = "FaultProfile::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Percentage of replies whose body is sent in small chunks with a pause between them
+    #[serde(default)]
+    pub slow_rate: u8,
+    #[serde(default = "FaultProfile::default_slow_chunk_bytes")]
+    pub slow_chunk_bytes: usize,
+    #[serde(default = "FaultProfile::default_slow_chunk_delay_ms")]
+    pub slow_chunk_delay_ms: u64,
+}
+
+impl FaultProfile {
+    /// Upper bound for any single injected delay.
+    pub const MAX_DELAY_MS: u64 = 300_000;
+
+    fn default_error_status() -> u16 {
+        503
+    }
+
+    fn default_timeout_ms() -> u64 {
+        30_000
+    }
+
+    fn default_slow_chunk_bytes() -> usize {
+        16
+    }
+
+    fn default_slow_chunk_delay_ms() -> u64 {
+        500
+    }
+
+    /// Checks that the rates and delays are within bounds.
+    pub fn validate(&self) -> Result<(), String> {
+        let total_rate = self.error_rate as u32
+            + self.reset_rate as u32
+            + self.timeout_rate as u32
+            + self.slow_rate as u32;
+        if total_rate > 100 {
+            return Err(format!("fault rates add up to {}%, over 100%", total_rate));
+        }
+
+        if !(500..=599).contains(&self.error_status) {
+            return Err(format!(
+                "injected error status must be 500-599, got {}",
+                self.error_status
+            ));
+        }
+
+        let delay_ms = self.latency_ms.checked_add(self.jitter_ms);
+        if delay_ms.is_none_or(|delay_ms| delay_ms > Self::MAX_DELAY_MS)
+            || self.timeout_ms > Self::MAX_DELAY_MS
+            || self.slow_chunk_delay_ms > Self::MAX_DELAY_MS
+        {
+            return Err(format!("delays must stay under {} ms", Self::MAX_DELAY_MS));
+        }
+
+        if self.slow_chunk_bytes == 0 {
+            return Err("slow chunk size must be at least 1 byte".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Fault injected into the reply for one captured request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct InjectedFault {
+    /// Delay added before the reply, in milliseconds
+    pub latency_ms: u64,
+    /// What happened to the reply, if anything beyond the delay
+    pub action: Option<FaultAction>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FaultAction {
+    /// Answered with a server error instead of the usual reply
+    Error { status: u16 },
+    /// Connection dropped without a reply
+    Reset,
+    /// No reply for `after_ms`, then the connection was dropped
+    Timeout { after_ms: u64 },
+    /// Usual reply, with the body trickled out in chunks
+    SlowBody {
+        chunk_bytes: usize,
+        chunk_delay_ms: u64,
+    },
+}
+
+/// Reply HookSpy sent back for a captured request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct SentResponse {
+    pub status: u16,
+    pub headers: Vec<ResponseHeader>,
+    pub body: String,
+    /// Why the configured reply could not be sent, e.g. a template that failed to render
+    pub error: Option<String>,
+}
+
+impl SentResponse {
+    /// Records a rendered `ResponseConfig`, listing the content type as a header.
+    pub fn from_config(config: &ResponseConfig) -> Self {
+        let content_type = config.content_type.iter().map(|value| ResponseHeader {
+            name: "content-type".to_string(),
+            value: value.clone(),
+        });
+
+        SentResponse {
+            status: config.status,
+            headers: content_type.chain(config.headers.iter().cloned()).collect(),
+            body: config.body.clone(),
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct QueryParam {
+    pub name: String,
+    pub value: String,
+}
+
+impl QueryParam {
+    /// Decodes a raw `application/x-www-form-urlencoded` string into ordered pairs.
+    pub fn parse_all(raw: &str) -> Vec<QueryParam> {
+        form_urlencoded::parse(raw.as_bytes())
+            .map(|(name, value)| QueryParam {
+                name: name.into_owned(),
+                value: value.into_owned(),
+            })
+            .collect()
+    }
+}
+
+/// How a byte payload is represented in a JSON string field.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyEncoding {
+    /// The bytes were valid UTF-8 and are included as-is
+    Utf8,
+    /// The bytes were not valid UTF-8 and are standard base64 encoded
+    Base64,
+}
+
+impl BodyEncoding {
+    /// Encodes `bytes` as text, falling back to base64 when they are not valid UTF-8.
+    pub fn encode(bytes: &[u8]) -> (String, BodyEncoding) {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => (text.to_string(), BodyEncoding::Utf8),
+            Err(_) => (BASE64.encode(bytes), BodyEncoding::Base64),
+        }
+    }
+
+    /// Turns text produced by `encode` back into bytes.
+    pub fn decode(&self, text: &str) -> Vec<u8> {
+        match self {
+            BodyEncoding::Utf8 => text.as_bytes().to_vec(),
+            BodyEncoding::Base64 => BASE64.decode(text).unwrap_or_default(),
+        }
+    }
+}
+
+/// Structured format detected from a request's content type.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyFormat {
+    Json,
+    Form,
+    Xml,
+    Multipart,
+}
+
+impl BodyFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BodyFormat::Json => "json",
+            BodyFormat::Form => "form",
+            BodyFormat::Xml => "xml",
+            BodyFormat::Multipart => "multipart",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<BodyFormat> {
+        match value {
+            "json" => Some(BodyFormat::Json),
+            "form" => Some(BodyFormat::Form),
+            "xml" => Some(BodyFormat::Xml),
+            "multipart" => Some(BodyFormat::Multipart),
+            _ => None,
+        }
+    }
+}
+
+/// A single header line as received. Repeated headers appear once per value.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct RequestHeader {
+    pub name: String,
+    /// Raw header value, as text or base64 depending on `encoding`
+    pub value: String,
+    pub encoding: BodyEncoding,
+}
+
+impl RequestHeader {
+    /// A header line with its name as sent and its raw value.
+    pub fn from_raw(name: &str, value: &[u8]) -> RequestHeader {
+        let (value, encoding) = BodyEncoding::encode(value);
+        RequestHeader {
+            name: name.to_string(),
+            value,
+            encoding,
+        }
+    }
+
+    /// Captures every header value in `headers`, keeping repeated names.
+    ///
+    /// `HeaderMap` does not keep the wire order or the case of names: names come out
+    /// lowercased, in the order each was first seen, with all values of a name together.
+    /// Captured requests use `capture::wire::CapturedHeaders`, which keeps both.
+    pub fn from_header_map(headers: &HeaderMap) -> Vec<RequestHeader> {
+        headers
+            .iter()
+            .map(|(name, value)| RequestHeader::from_raw(name.as_str(), value.as_bytes()))
+            .collect()
+    }
+
+    /// Rebuilds a header map from a captured list, skipping values that cannot be sent.
+    pub fn to_header_map(headers: &[RequestHeader]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for header in headers.iter() {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(header.name.as_bytes()),
+                HeaderValue::from_bytes(&header.value_bytes()),
+            ) {
+                map.append(name, value);
+            }
+        }
+        map
+    }
+
+    /// Parses the JSON list stored in `webhook_requests.headers`.
+    pub fn parse_list(json: &str) -> Vec<RequestHeader> {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// Returns the raw bytes of the header value.
+    pub fn value_bytes(&self) -> Vec<u8> {
+        self.encoding.decode(&self.value)
+    }
+}
+
+/// One part of a `multipart/form-data` body.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct WebhookRequestPart {
+    pub id: String,
+    /// Zero-based position of the part within the body
+    pub position: u64,
+    pub name: Option<String>,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    /// JSON encoded list of `RequestHeader` sent with this part
+    pub headers: String,
+    pub size: u64,
+    /// Value of a plain form field; `None` for file uploads and binary parts
+    pub text: Option<String>,
+    /// Path that serves the raw bytes of this part
+    pub download_url: String,
+}
+
+/// How a CloudEvent was carried by the request it arrived in.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudEventMode {
+    /// Attributes in `ce-*` headers, data in the body
+    Binary,
+    /// One `application/cloudevents+json` event in the body
+    Structured,
+    /// One of several events in an `application/cloudevents-batch+json` body
+    Batch,
+}
+
+impl CloudEventMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloudEventMode::Binary => "binary",
+            CloudEventMode::Structured => "structured",
+            CloudEventMode::Batch => "batch",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<CloudEventMode> {
+        match value {
+            "binary" => Some(CloudEventMode::Binary),
+            "structured" => Some(CloudEventMode::Structured),
+            "batch" => Some(CloudEventMode::Batch),
+            _ => None,
+        }
+    }
+}
+
+/// Context attributes of a CloudEvent found in a captured request.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct CloudEvent {
+    /// Zero-based position of the event within a batch; always 0 otherwise
+    pub position: u64,
+    pub mode: CloudEventMode,
+    pub specversion: Option<String>,
+    pub id: Option<String>,
+    pub source: Option<String>,
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub subject: Option<String>,
+    pub time: Option<String>,
+    pub datacontenttype: Option<String>,
+    pub dataschema: Option<String>,
+    /// Attributes outside the spec, by name
+    #[schema(value_type = Object)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+    /// `data` as JSON text, or `data_base64` as sent; `None` in binary mode, where
+    /// the request body is the data
+    pub data: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct WebhookRequest {
     pub id: String,
     pub webhook_id: String,
     pub method: String,
+    /// Sub-path appended by the sender after the webhook URL, e.g. `/github/push`
+    pub path: Option<String>,
+    /// Raw query string without the leading `?`
+    pub query: Option<String>,
+    /// `query` decoded into key/value pairs, in the order they were sent
+    pub query_params: Vec<QueryParam>,
+    /// JSON encoded list of `RequestHeader`, in the order they were received
     pub headers: String,
+    /// Request body after undoing `content_encoding`, as text or base64 depending on `body_encoding`
     pub body: String,
+    pub body_encoding: BodyEncoding,
+    /// `Content-Encoding` that was removed from the body at ingestion, e.g. `gzip`
+    pub content_encoding: Option<String>,
+    /// Base64 of the body exactly as received, present only when it was decompressed
+    pub raw_body: Option<String>,
+    pub body_format: Option<BodyFormat>,
+    /// Parsed form fields (a list of name/value pairs) or XML element tree, by `body_format`
+    #[schema(value_type = Option<Object>)]
+    pub parsed_body: Option<serde_json::Value>,
     pub received_at: String,
     pub caller_ip: Option<String>,
+    /// Total time spent handling the request, from arrival until it was stored and its
+    /// notifications were sent, not counting delays injected by a fault profile. Missing
+    /// from the echo reply and notifications, which go out before it is known
     pub duration_us: Option<u64>,
+    /// Reply HookSpy sent to the caller; missing when a fault dropped the connection
+    pub response: Option<SentResponse>,
+    /// Labels attached by the webhook script
+    pub tags: Vec<String>,
+    /// Error raised by the webhook script while handling this request
+    pub script_error: Option<String>,
+    /// Failure injected by the webhook's fault profile
+    pub fault: Option<InjectedFault>,
+    /// Position in the webhook's response sequence, if one was set
+    pub sequence: Option<SequencePosition>,
+    /// Answer from the webhook's forward target, if one was set
+    pub upstream: Option<UpstreamResponse>,
+    /// Answer from the local target `hookspy-cli forward` relayed the request to
+    pub local_response: Option<UpstreamResponse>,
+    /// Signature check against the webhook's signing secret, if one was set
+    pub signature: Option<SignatureCheck>,
+    /// Sender recognised from the headers or body, e.g. `github` or `stripe`
+    pub provider: Option<String>,
+    /// Event as the provider names it, e.g. `push` or `invoice.paid`
+    pub event_type: Option<String>,
+    /// Check of a JSON body against the webhook's JSON Schema, if one was set
+    pub schema_validation: Option<SchemaValidation>,
+    /// Ways a JSON body departs from the baseline shape of its event type; empty
+    /// when it matches or was not checked
+    pub drift: Vec<ShapeChange>,
+    /// Parts of a `multipart/form-data` body, empty for other content types
+    pub parts: Vec<WebhookRequestPart>,
+    /// CloudEvents carried by the request, one per event in a batch
+    pub cloud_events: Vec<CloudEvent>,
+}
+
+impl WebhookRequest {
+    /// Body bytes exactly as received, before any `content_encoding` was undone.
+    pub fn received_body(&self) -> Vec<u8> {
+        match self.raw_body.as_ref() {
+            Some(raw) => BodyEncoding::Base64.decode(raw),
+            None => self.body_encoding.decode(&self.body),
+        }
+    }
+
+    /// JSON for readers without a login: the capture endpoint's default reply and the
+    /// notification streams. The signature check is cut down to `SignatureCheck::public`
+    /// so the endpoint cannot be used to sign payloads with the webhook's secret.
+    pub fn public_json(&self) -> serde_json::Result<String> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(check) = self.signature.as_ref() {
+            value["signature"] = serde_json::to_value(check.public())?;
+        }
+        serde_json::to_string(&value)
+    }
+}
+
+/// Narrows `GET /api/webhooks/{webhook_id}/requests` to one sender or event, to
+/// requests that passed or failed JSON Schema validation, or to drifted ones.
+#[derive(Deserialize, Default)]
+pub struct RequestFilter {
+    pub provider: Option<String>,
+    pub event_type: Option<String>,
+    /// `false` for requests whose body broke the webhook's JSON Schema
+    pub schema_valid: Option<bool>,
+    /// `true` for requests whose body drifted from its baseline shape
+    pub drifted: Option<bool>,
+}
+
+/// Inbound side of a captured request, as handed to `WebhookDao::create_webhook_request`.
+pub struct NewWebhookRequest {
+    pub webhook_id: String,
+    pub method: String,
+    pub path: Option<String>,
+    pub query: Option<String>,
+    pub headers: String,
+    /// Body exactly as received
+    pub body: Vec<u8>,
+    pub content_encoding: Option<String>,
+    /// Body with `content_encoding` removed, when one was applied
+    pub decoded_body: Option<Vec<u8>>,
+    pub body_format: Option<BodyFormat>,
+    pub parsed_body: Option<serde_json::Value>,
+    pub caller_ip: Option<String>,
+    pub tags: Vec<String>,
+    pub script_error: Option<String>,
+    pub fault: Option<InjectedFault>,
+    pub sequence: Option<SequencePosition>,
+    pub upstream: Option<UpstreamResponse>,
+    pub signature: Option<SignatureCheck>,
+    pub provider: Option<String>,
+    pub event_type: Option<String>,
+    pub schema_validation: Option<SchemaValidation>,
+    pub drift: Vec<ShapeChange>,
+    pub parts: Vec<NewWebhookRequestPart>,
+    pub cloud_events: Vec<CloudEvent>,
+}
+
+/// One per-webhook setting, as handed to `WebhookDao::update_capture_setting`.
+pub enum CaptureSetting {
+    Response(Option<ResponseConfig>),
+    Script(Option<String>),
+    Faults(Option<FaultProfile>),
+    Sequence(Option<ResponseSequence>),
+    Forward(Option<ForwardTarget>),
+    FanOut(Option<FanOutConfig>),
+    Signature(Option<SignatureConfig>),
+    JsonSchema(Option<serde_json::Value>),
+}
+
+impl CaptureSetting {
+    /// Column on `webhooks` holding the setting.
+    pub fn column(&self) -> &'static str {
+        match self {
+            CaptureSetting::Response(_) => "response_config",
+            CaptureSetting::Script(_) => "script",
+            CaptureSetting::Faults(_) => "fault_profile",
+            CaptureSetting::Sequence(_) => "sequence",
+            CaptureSetting::Forward(_) => "forward_target",
+            CaptureSetting::FanOut(_) => "fanout",
+            CaptureSetting::Signature(_) => "signature",
+            CaptureSetting::JsonSchema(_) => "json_schema",
+        }
+    }
+
+    /// Value as stored in `column`.
+    pub fn value(&self) -> serde_json::Result<Option<String>> {
+        match self {
+            CaptureSetting::Response(config) => {
+                config.as_ref().map(serde_json::to_string).transpose()
+            }
+            CaptureSetting::Script(script) => Ok(script.clone()),
+            CaptureSetting::Faults(profile) => {
+                profile.as_ref().map(serde_json::to_string).transpose()
+            }
+            CaptureSetting::Sequence(sequence) => {
+                sequence.as_ref().map(serde_json::to_string).transpose()
+            }
+            CaptureSetting::Forward(target) => {
+                target.as_ref().map(serde_json::to_string).transpose()
+            }
+            CaptureSetting::FanOut(config) => {
+                config.as_ref().map(serde_json::to_string).transpose()
+            }
+            CaptureSetting::Signature(config) => {
+                config.as_ref().map(serde_json::to_string).transpose()
+            }
+            CaptureSetting::JsonSchema(schema) => {
+                schema.as_ref().map(serde_json::to_string).transpose()
+            }
+        }
+    }
+}
+
+/// Body of `PUT /api/webhooks/{webhook_id}/script`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct WebhookScript {
+    pub script: String,
+}
+
+/// Body of `POST /api/webhooks/{webhook_id}/script/test`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TestScriptRequest {
+    /// Captured request to run the script against
+    pub request_id: String,
+    /// Script to try; defaults to the webhook's saved script
+    pub script: Option<String>,
+}
+
+/// A parsed multipart part, as handed to `WebhookDao::create_webhook_request`.
+pub struct NewWebhookRequestPart {
+    pub name: Option<String>,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub headers: String,
+    pub data: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(status: u16) -> ResponseConfig {
+        ResponseConfig {
+            status,
+            content_type: None,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    fn sequence(steps: usize, after_last: SequenceEnd) -> ResponseSequence {
+        ResponseSequence {
+            steps: (0..steps).map(|i| reply(200 + i as u16)).collect(),
+            after_last,
+        }
+    }
+
+    #[test]
+    fn sequences_stick_on_the_last_step_by_default() {
+        let sequence: ResponseSequence =
+            serde_json::from_str(r#"{"steps":[{"status":500},{"status":200}]}"#).unwrap();
+        assert_eq!(sequence.after_last, SequenceEnd::StickOnLast);
+        let steps: Vec<usize> = (0..5).map(|hit| sequence.step(hit)).collect();
+        assert_eq!(steps, [0, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn looping_sequences_start_over() {
+        let sequence = sequence(3, SequenceEnd::Loop);
+        let steps: Vec<usize> = (0..7).map(|hit| sequence.step(hit)).collect();
+        assert_eq!(steps, [0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(sequence.step(u64::MAX), (u64::MAX % 3) as usize);
+    }
+
+    #[test]
+    fn sequences_need_between_one_and_max_valid_steps() {
+        assert!(sequence(1, SequenceEnd::Loop).validate().is_ok());
+        assert!(sequence(ResponseSequence::MAX_STEPS, SequenceEnd::Loop)
+            .validate()
+            .is_ok());
+        assert!(sequence(0, SequenceEnd::Loop).validate().is_err());
+        assert!(sequence(ResponseSequence::MAX_STEPS + 1, SequenceEnd::Loop)
+            .validate()
+            .is_err());
+
+        let mut invalid = sequence(3, SequenceEnd::StickOnLast);
+        invalid.steps[1].status = 99;
+        assert_eq!(
+            invalid.validate(),
+            Err("step 2: invalid status code 99".to_string())
+        );
+    }
 }
@@ -0,0 +1,71 @@
+mod common;
+
+use common::TestApp;
+use hookspy::model::db::init_db;
+
+#[tokio::test]
+async fn body_is_stored_once_as_bytes() {
+    let app = TestApp::spawn().await;
+
+    reqwest::Client::new()
+        .post(app.capture_url(""))
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+
+    let request = app.requests().await.remove(0);
+    assert_eq!(request.body, "hello");
+
+    let db = app.state.db.lock().await;
+    let mut rows = db
+        .query(
+            "SELECT body, body_raw FROM webhook_requests WHERE id = ?",
+            turso::params![request.id],
+        )
+        .await
+        .unwrap();
+    let row = rows.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<String>(0).unwrap(), "");
+    assert_eq!(row.get::<Vec<u8>>(1).unwrap(), b"hello");
+}
+
+#[tokio::test]
+async fn legacy_rows_are_read_from_the_text_column() {
+    let app = TestApp::spawn().await;
+    {
+        let db = app.state.db.lock().await;
+        db.execute(
+            "INSERT INTO webhook_requests (id, webhook_id, method, headers, body, received_at) VALUES ('legacy', ?, 'POST', '[]', 'old body', '2024-01-01T00:00:00+00:00')",
+            turso::params![app.webhook_id.clone()],
+        )
+        .await
+        .unwrap();
+    }
+
+    let request = app.requests().await.remove(0);
+    assert_eq!(request.id, "legacy");
+    assert_eq!(request.body, "old body");
+}
+
+#[tokio::test]
+async fn text_copies_are_cleared_once() {
+    let app = TestApp::spawn().await;
+    let db = app.state.db.lock().await;
+    db.execute(
+        "INSERT INTO webhook_requests (id, webhook_id, method, headers, body, body_raw, received_at) VALUES ('copy', ?, 'POST', '[]', 'text copy', X'00', '2024-01-01T00:00:00+00:00')",
+        turso::params![app.webhook_id.clone()],
+    )
+    .await
+    .unwrap();
+
+    // Already recorded by the first start, so a restart leaves the row alone
+    init_db(&db).await.unwrap();
+
+    let mut rows = db
+        .query("SELECT body FROM webhook_requests WHERE id = 'copy'", ())
+        .await
+        .unwrap();
+    let row = rows.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<String>(0).unwrap(), "text copy");
+}
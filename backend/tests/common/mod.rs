@@ -0,0 +1,203 @@
+//! A HookSpy capture endpoint on a local port with its own database, and helpers to
+//! serve stand-in upstreams next to it.
+
+// Each test binary uses only some of the helpers
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, Method, StatusCode, Uri},
+    routing::any,
+    Router,
+};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+use hookspy::app::AppState;
+use hookspy::auth::jwt::{AppClaims, AuthUser};
+use hookspy::capture::{target, wire};
+use hookspy::config::Config;
+use hookspy::dao::webhook::WebhookDao;
+use hookspy::handlers::webhook::receive_webhook;
+use hookspy::model::db::init_db;
+use hookspy::notification::notification::Notification;
+use hookspy::schema::webhook::{CaptureSetting, RequestFilter, WebhookRequest};
+
+pub const USER_ID: &str = "test-user";
+
+pub struct TestApp {
+    pub state: AppState,
+    pub base_url: String,
+    pub webhook_id: String,
+}
+
+impl TestApp {
+    /// Starts the capture routes with one webhook owned by a test user.
+    pub async fn spawn() -> TestApp {
+        TestApp::spawn_with(test_config()).await
+    }
+
+    /// Like `spawn`, with `config` in place of the test defaults.
+    pub async fn spawn_with(config: Config) -> TestApp {
+        let db = turso::Builder::new_local(":memory:")
+            .build()
+            .await
+            .unwrap()
+            .connect()
+            .unwrap();
+        init_db(&db).await.unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = AppState {
+            db: Arc::new(Mutex::new(db)),
+            notification: Arc::new(Mutex::new(Notification::new())),
+            domain: base_url.clone(),
+            http_client: target::http_client(config.allow_private_targets),
+            config,
+        };
+
+        let webhook = {
+            let db = state.db.lock().await;
+            WebhookDao {
+                domain: state.domain.clone(),
+            }
+            .create_webhook(db.clone(), USER_ID, "test")
+            .await
+            .unwrap()
+        };
+
+        let app = Router::new()
+            .route("/api/hooks/:webhook_id", any(receive_webhook))
+            .route("/api/hooks/:webhook_id/*path", any(receive_webhook))
+            .with_state(state.clone());
+        tokio::spawn(wire::serve(listener, app));
+
+        TestApp {
+            state,
+            base_url,
+            webhook_id: webhook.id,
+        }
+    }
+
+    /// Capture URL of the test webhook, followed by `path_and_query`.
+    pub fn capture_url(&self, path_and_query: &str) -> String {
+        format!(
+            "{}/api/hooks/{}{}",
+            self.base_url, self.webhook_id, path_and_query
+        )
+    }
+
+    pub async fn set(&self, setting: CaptureSetting) {
+        let db = self.state.db.lock().await;
+        self.dao()
+            .update_capture_setting(db.clone(), USER_ID, &self.webhook_id, setting)
+            .await
+            .unwrap();
+    }
+
+    /// Captured requests, newest first.
+    pub async fn requests(&self) -> Vec<WebhookRequest> {
+        let db = self.state.db.lock().await;
+        self.dao()
+            .get_webhook_requests(
+                db.clone(),
+                &self.webhook_id,
+                &RequestFilter::default(),
+                0,
+                100,
+            )
+            .await
+            .unwrap()
+    }
+
+    fn dao(&self) -> WebhookDao {
+        WebhookDao {
+            domain: self.state.domain.clone(),
+        }
+    }
+}
+
+/// What the upstream last received.
+#[derive(Default)]
+pub struct Received {
+    pub method: Option<Method>,
+    pub uri: Option<Uri>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+pub type Recorder = Arc<Mutex<Received>>;
+
+async fn record(
+    State(received): State<Recorder>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, [(&'static str, &'static str); 1], &'static str) {
+    *received.lock().await = Received {
+        method: Some(method),
+        uri: Some(uri),
+        headers,
+        body,
+    };
+    (
+        StatusCode::CREATED,
+        [("x-upstream", "yes")],
+        "from upstream",
+    )
+}
+
+/// Serves an upstream that answers 201 and keeps what it last received.
+pub async fn recording_upstream() -> (String, Recorder) {
+    let received = Recorder::default();
+    let url = serve(Router::new().fallback(record).with_state(received.clone())).await;
+    (url, received)
+}
+
+/// The owner of the test webhook, for calling handlers directly.
+pub fn owner() -> AuthUser {
+    AuthUser(AppClaims {
+        sub: USER_ID.to_string(),
+        email: "test@example.com".to_string(),
+        exp: i64::MAX,
+    })
+}
+
+/// Serves `router` on a free local port and returns its base URL.
+pub async fn serve(router: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    base_url
+}
+
+/// Base URL of a local port nothing listens on.
+pub async fn closed_port_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+pub fn test_config() -> Config {
+    Config {
+        admin_email: "admin@example.com".to_string(),
+        oauth_client_id: String::new(),
+        oauth_client_secret: String::new(),
+        oauth_auth_url: String::new(),
+        oauth_token_url: String::new(),
+        oauth_redirect_url: String::new(),
+        jwt_secret: "test-secret".to_string(),
+        sweep_interval_seconds: 60,
+        webhook_retention_days: 90,
+        delivery_poll_interval_ms: 1000,
+        // Upstreams in the tests listen on 127.0.0.1
+        allow_private_targets: true,
+    }
+}
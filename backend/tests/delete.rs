@@ -0,0 +1,59 @@
+mod common;
+
+use common::{TestApp, USER_ID};
+use hookspy::dao::webhook::WebhookDao;
+
+async fn count(app: &TestApp, table: &str) -> i64 {
+    let db = app.state.db.lock().await;
+    let mut rows = db
+        .query(&format!("SELECT COUNT(*) FROM {}", table), ())
+        .await
+        .unwrap();
+    rows.next().await.unwrap().unwrap().get(0).unwrap()
+}
+
+#[tokio::test]
+async fn deleting_a_webhook_deletes_its_requests_and_their_rows() {
+    let app = TestApp::spawn().await;
+    let client = reqwest::Client::new();
+    client
+        .post(app.capture_url(""))
+        .header("content-type", "multipart/form-data; boundary=b")
+        .body("--b\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--b--\r\n")
+        .send()
+        .await
+        .unwrap();
+    client
+        .post(app.capture_url(""))
+        .header("content-type", "application/cloudevents-batch+json")
+        .body(r#"[{"specversion":"1.0","id":"1","source":"/s","type":"t"}]"#)
+        .send()
+        .await
+        .unwrap();
+    for table in [
+        "webhook_requests",
+        "webhook_request_parts",
+        "webhook_request_events",
+    ] {
+        assert!(count(&app, table).await > 0, "{}", table);
+    }
+
+    {
+        let db = app.state.db.lock().await;
+        WebhookDao {
+            domain: app.state.domain.clone(),
+        }
+        .delete_webhook(db.clone(), USER_ID, &app.webhook_id)
+        .await
+        .unwrap();
+    }
+
+    for table in [
+        "webhooks",
+        "webhook_requests",
+        "webhook_request_parts",
+        "webhook_request_events",
+    ] {
+        assert_eq!(count(&app, table).await, 0, "{}", table);
+    }
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{recording_upstream, test_config, TestApp};
+use hookspy::config::Config;
+use hookspy::dao::delivery::DeliveryDao;
+use hookspy::schema::delivery::{DeliveryStatus, FanOutConfig};
+use hookspy::schema::webhook::CaptureSetting;
+
+#[tokio::test]
+async fn private_targets_are_dead_lettered_unless_allowed() {
+    let app = TestApp::spawn_with(Config {
+        allow_private_targets: false,
+        ..test_config()
+    })
+    .await;
+    let (upstream, _) = recording_upstream().await;
+    app.set(CaptureSetting::FanOut(Some(FanOutConfig {
+        targets: vec![
+            upstream,
+            "http://169.254.169.254/latest/meta-data".to_string(),
+            "https://example.com/hook".to_string(),
+        ],
+        timeout_ms: 1_000,
+        max_attempts: 3,
+        initial_backoff_ms: 1_000,
+    })))
+    .await;
+
+    reqwest::Client::new()
+        .post(app.capture_url(""))
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+
+    let db = app.state.db.lock().await;
+    let deliveries = DeliveryDao
+        .get_deliveries(db.clone(), &app.webhook_id, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 3);
+    for delivery in deliveries {
+        if delivery.url.contains("example.com") {
+            assert_eq!(delivery.status, DeliveryStatus::Pending);
+            continue;
+        }
+        assert_eq!(delivery.status, DeliveryStatus::Dead, "{}", delivery.url);
+        assert_eq!(delivery.next_attempt_at, None);
+        let error = delivery.last_error.unwrap();
+        assert!(error.starts_with("target refused"), "{}", error);
+    }
+}
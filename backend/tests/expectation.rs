@@ -0,0 +1,162 @@
+mod common;
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use common::{owner, TestApp};
+use hookspy::handlers::expectation::expect_webhook_requests;
+use hookspy::schema::expectation::ExpectationReport;
+
+async fn expect(app: &TestApp, expectation: serde_json::Value) -> ExpectationReport {
+    let Ok(Json(report)) = expect_webhook_requests(
+        State(app.state.clone()),
+        owner(),
+        Path(app.webhook_id.clone()),
+        Json(serde_json::from_value(expectation).unwrap()),
+    )
+    .await
+    else {
+        panic!("expectation failed to run");
+    };
+    report
+}
+
+async fn capture(app: &TestApp, method: reqwest::Method, path: &str, body: &str) {
+    reqwest::Client::new()
+        .request(method, app.capture_url(path))
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn other_routes_are_near_misses_but_not_matches() {
+    let app = TestApp::spawn().await;
+    capture(
+        &app,
+        reqwest::Method::POST,
+        "/orders",
+        r#"{"event":"order.paid"}"#,
+    )
+    .await;
+    capture(
+        &app,
+        reqwest::Method::GET,
+        "/orders",
+        r#"{"event":"order.paid"}"#,
+    )
+    .await;
+    capture(
+        &app,
+        reqwest::Method::POST,
+        "/refunds",
+        r#"{"event":"order.paid"}"#,
+    )
+    .await;
+
+    let report = expect(
+        &app,
+        serde_json::json!({
+            "method": "post",
+            "path": "orders",
+            "json": [{ "path": "$.event", "equals": "order.paid" }],
+            "count": { "exactly": 1 },
+            "timeout_ms": 0,
+            "history": true,
+        }),
+    )
+    .await;
+
+    assert!(report.passed, "{:?}", report);
+    assert_eq!(report.matches[0].path.as_deref(), Some("/orders"));
+    assert!(!report.truncated);
+    let failures: Vec<&str> = report
+        .near_misses
+        .iter()
+        .flat_map(|near_miss| near_miss.failures.iter().map(String::as_str))
+        .collect();
+    assert_eq!(
+        failures,
+        [
+            "method: expected POST, got GET",
+            "path: expected /orders, got /refunds"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn requests_before_since_are_left_out() {
+    let app = TestApp::spawn().await;
+    capture(&app, reqwest::Method::POST, "", "{}").await;
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    // Any offset works; it is compared as the same instant in UTC
+    let since = chrono::Utc::now()
+        .with_timezone(&chrono::FixedOffset::east_opt(2 * 3600).unwrap())
+        .to_rfc3339();
+    capture(&app, reqwest::Method::POST, "", "{}").await;
+
+    let report = expect(
+        &app,
+        serde_json::json!({ "since": since, "count": { "exactly": 1 }, "timeout_ms": 0 }),
+    )
+    .await;
+
+    assert!(report.passed, "{:?}", report);
+}
+
+#[tokio::test]
+async fn scan_cut_off_is_reported() {
+    let app = TestApp::spawn().await;
+    {
+        let db = app.state.db.lock().await;
+        for i in 0..1001 {
+            db.execute(
+                "INSERT INTO webhook_requests (id, webhook_id, method, headers, body, body_raw, received_at) VALUES (?, ?, 'POST', '[]', '', X'7B7D', ?)",
+                turso::params![
+                    format!("r{:04}", i),
+                    app.webhook_id.clone(),
+                    format!("2024-01-01T00:00:00.{:06}+00:00", i)
+                ],
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    let report = expect(
+        &app,
+        serde_json::json!({ "count": "none", "timeout_ms": 0, "history": true }),
+    )
+    .await;
+
+    assert!(report.truncated);
+    assert_eq!(report.matched, 1000);
+    assert!(
+        report.message.contains("only the newest 1000"),
+        "{}",
+        report.message
+    );
+    assert_eq!(report.matches[0].id, "r0001");
+}
+
+#[tokio::test]
+async fn only_requests_after_the_call_count_by_default() {
+    let app = TestApp::spawn().await;
+    capture(&app, reqwest::Method::POST, "", "{}").await;
+
+    let report = expect(
+        &app,
+        serde_json::json!({ "count": { "exactly": 0 }, "timeout_ms": 0 }),
+    )
+    .await;
+    assert!(report.passed, "{:?}", report);
+
+    let report = expect(
+        &app,
+        serde_json::json!({ "count": { "exactly": 1 }, "timeout_ms": 0, "history": true }),
+    )
+    .await;
+    assert!(report.passed, "{:?}", report);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::TestApp;
+use hookspy::schema::webhook::{CaptureSetting, FaultProfile};
+
+fn profile(fields: serde_json::Value) -> FaultProfile {
+    serde_json::from_value(fields).unwrap()
+}
+
+#[tokio::test]
+async fn reset_fault_drops_the_connection() {
+    let app = TestApp::spawn().await;
+    app.set(CaptureSetting::Faults(Some(profile(
+        serde_json::json!({ "reset_rate": 100 }),
+    ))))
+    .await;
+
+    let result = reqwest::Client::new()
+        .post(app.capture_url(""))
+        .body("payload")
+        .send()
+        .await;
+
+    let err = result.expect_err("no response should arrive");
+    assert!(err.is_request(), "{:?}", err);
+    assert_eq!(app.requests().await.len(), 1);
+}
+
+#[tokio::test]
+async fn timeout_fault_drops_the_connection_after_the_wait() {
+    let app = TestApp::spawn().await;
+    app.set(CaptureSetting::Faults(Some(profile(
+        serde_json::json!({ "timeout_rate": 100, "timeout_ms": 300 }),
+    ))))
+    .await;
+
+    let started = Instant::now();
+    let result = reqwest::Client::new()
+        .post(app.capture_url(""))
+        .send()
+        .await;
+
+    let err = result.expect_err("no response should arrive");
+    assert!(err.is_request(), "{:?}", err);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn latency_and_jitter_that_overflow_are_rejected() {
+    let overflowing = profile(serde_json::json!({
+        "latency_ms": u64::MAX,
+        "jitter_ms": 1,
+    }));
+    assert!(overflowing.validate().is_err());
+
+    let within = profile(serde_json::json!({ "latency_ms": 100, "jitter_ms": 50 }));
+    assert_eq!(within.validate(), Ok(()));
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{body::Body, http::Method, Router};
+use futures_util::stream;
+
+use common::{closed_port_url, recording_upstream, serve, test_config, TestApp};
+use hookspy::capture::forward::{send_request, SendError, MAX_RESPONSE_SIZE};
+use hookspy::capture::target;
+use hookspy::config::Config;
+use hookspy::schema::webhook::{CaptureSetting, ForwardTarget, ResponseHeader};
+
+fn target(url: String) -> ForwardTarget {
+    ForwardTarget {
+        url,
+        timeout_ms: 5_000,
+        set_headers: Vec::new(),
+        remove_headers: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn relays_method_headers_and_body() {
+    let app = TestApp::spawn().await;
+    let (upstream, received) = recording_upstream().await;
+    app.set(CaptureSetting::Forward(Some(target(upstream))))
+        .await;
+
+    let response = reqwest::Client::new()
+        .put(app.capture_url("/orders?x=1"))
+        .header("x-custom", "abc")
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.headers()["x-upstream"], "yes");
+    assert_eq!(response.text().await.unwrap(), "from upstream");
+
+    let received = received.lock().await;
+    assert_eq!(received.method, Some(Method::PUT));
+    assert_eq!(received.uri.as_ref().unwrap().to_string(), "/orders?x=1");
+    assert_eq!(received.headers["x-custom"], "abc");
+    assert_eq!(received.body, "payload");
+
+    let requests = app.requests().await;
+    let upstream = requests[0].upstream.as_ref().unwrap();
+    assert_eq!(upstream.status, Some(201));
+    assert_eq!(upstream.body, "from upstream");
+}
+
+#[tokio::test]
+async fn applies_header_rewrites() {
+    let app = TestApp::spawn().await;
+    let (upstream, received) = recording_upstream().await;
+    app.set(CaptureSetting::Forward(Some(ForwardTarget {
+        set_headers: vec![ResponseHeader {
+            name: "x-set".to_string(),
+            value: "forced".to_string(),
+        }],
+        remove_headers: vec!["x-secret".to_string()],
+        ..target(upstream)
+    })))
+    .await;
+
+    reqwest::Client::new()
+        .post(app.capture_url(""))
+        .header("x-secret", "hidden")
+        .header("x-set", "original")
+        .header("x-kept", "kept")
+        .send()
+        .await
+        .unwrap();
+
+    let received = received.lock().await;
+    assert!(received.headers.get("x-secret").is_none());
+    assert_eq!(received.headers.get_all("x-set").iter().count(), 1);
+    assert_eq!(received.headers["x-set"], "forced");
+    assert_eq!(received.headers["x-kept"], "kept");
+}
+
+#[tokio::test]
+async fn unreachable_upstream_gives_502_and_keeps_the_request() {
+    let app = TestApp::spawn().await;
+    app.set(CaptureSetting::Forward(Some(target(
+        closed_port_url().await,
+    ))))
+    .await;
+
+    let response = reqwest::Client::new()
+        .post(app.capture_url("/down"))
+        .body("kept")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 502);
+    let requests = app.requests().await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].body, "kept");
+    let upstream = requests[0].upstream.as_ref().unwrap();
+    assert_eq!(upstream.status, None);
+    assert!(upstream.error.is_some());
+}
+
+#[tokio::test]
+async fn slow_upstream_gives_504_and_keeps_the_request() {
+    let app = TestApp::spawn().await;
+    let upstream = serve(Router::new().fallback(|| async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        "too late"
+    }))
+    .await;
+    app.set(CaptureSetting::Forward(Some(ForwardTarget {
+        timeout_ms: 200,
+        ..target(upstream)
+    })))
+    .await;
+
+    let response = reqwest::Client::new()
+        .post(app.capture_url("/slow"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 504);
+    let requests = app.requests().await;
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].upstream.as_ref().unwrap().error.is_some());
+}
+
+#[tokio::test]
+async fn private_upstream_is_refused_unless_allowed() {
+    let app = TestApp::spawn_with(Config {
+        allow_private_targets: false,
+        ..test_config()
+    })
+    .await;
+    let (upstream, received) = recording_upstream().await;
+    app.set(CaptureSetting::Forward(Some(target(upstream))))
+        .await;
+
+    let response = reqwest::Client::new()
+        .post(app.capture_url(""))
+        .body("kept")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 502);
+    assert!(received.lock().await.method.is_none());
+    let requests = app.requests().await;
+    let error = requests[0]
+        .upstream
+        .as_ref()
+        .unwrap()
+        .error
+        .as_ref()
+        .unwrap();
+    assert!(error.contains("127.0.0.1"), "{}", error);
+}
+
+#[tokio::test]
+async fn host_names_resolving_to_private_addresses_are_refused() {
+    let (upstream, received) = recording_upstream().await;
+    let url = upstream.replace("127.0.0.1", "localhost");
+
+    // The URL check is skipped, so the refusal comes from the client's resolver
+    let result = send_request(
+        &target::http_client(false),
+        Method::GET,
+        &url,
+        Default::default(),
+        "",
+        Duration::from_secs(5),
+        true,
+    )
+    .await;
+
+    assert!(matches!(result, Err(SendError::Blocked(_))), "{:?}", result);
+    assert!(received.lock().await.method.is_none());
+}
+
+#[tokio::test]
+async fn oversized_upstream_body_gives_502() {
+    let app = TestApp::spawn().await;
+    // Streamed without a Content-Length, so the limit applies while reading
+    let upstream = serve(Router::new().fallback(|| async {
+        let chunk = vec![b'x'; 1024 * 1024];
+        let chunks = (MAX_RESPONSE_SIZE / chunk.len()) + 1;
+        Body::from_stream(stream::iter(
+            (0..chunks).map(move |_| Ok::<_, std::io::Error>(chunk.clone())),
+        ))
+    }))
+    .await;
+    app.set(CaptureSetting::Forward(Some(target(upstream))))
+        .await;
+
+    let response = reqwest::Client::new()
+        .post(app.capture_url(""))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 502);
+    let requests = app.requests().await;
+    let upstream = requests[0].upstream.as_ref().unwrap();
+    assert!(upstream.error.as_ref().unwrap().contains("larger than"));
+}
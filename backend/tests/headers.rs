@@ -0,0 +1,96 @@
+mod common;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use common::TestApp;
+use hookspy::schema::webhook::RequestHeader;
+
+/// Sends `requests` over one keep-alive connection and waits for every answer.
+async fn send_raw(app: &TestApp, requests: &[String]) {
+    let mut stream = TcpStream::connect(app.base_url.trim_start_matches("http://"))
+        .await
+        .unwrap();
+    for request in requests {
+        stream.write_all(request.as_bytes()).await.unwrap();
+        // Each echo reply is read before the next request goes out
+        let mut reply = vec![0; 64 * 1024];
+        let read = stream.read(&mut reply).await.unwrap();
+        assert!(reply[..read].starts_with(b"HTTP/1.1 200"));
+    }
+}
+
+fn names_and_values(request: &hookspy::schema::webhook::WebhookRequest) -> Vec<(String, String)> {
+    serde_json::from_str::<Vec<RequestHeader>>(&request.headers)
+        .unwrap()
+        .into_iter()
+        .map(|header| (header.name, header.value))
+        .collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn headers_keep_wire_order_and_case() {
+    let app = TestApp::spawn().await;
+    let path = format!("/api/hooks/{}", app.webhook_id);
+    send_raw(
+        &app,
+        &[format!(
+            "POST {} HTTP/1.1\r\nHost: example.com\r\nX-Hop: a\r\nx-other: 1\r\nX-HOP: b\r\nContent-Length: 2\r\n\r\n{{}}",
+            path
+        )],
+    )
+    .await;
+
+    let request = app.requests().await.remove(0);
+    assert_eq!(
+        names_and_values(&request),
+        pairs(&[
+            ("Host", "example.com"),
+            ("X-Hop", "a"),
+            ("x-other", "1"),
+            ("X-HOP", "b"),
+            ("Content-Length", "2"),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn a_body_that_looks_like_a_head_is_skipped() {
+    let app = TestApp::spawn().await;
+    let path = format!("/api/hooks/{}", app.webhook_id);
+    let fake = "POST / HTTP/1.1\r\nX-Fake: 1\r\n\r\n";
+    send_raw(
+        &app,
+        &[
+            format!(
+                "POST {} HTTP/1.1\r\nHost: h\r\nContent-Length: {}\r\n\r\n{}",
+                path,
+                fake.len(),
+                fake
+            ),
+            format!(
+                "POST {} HTTP/1.1\r\nX-Second: yes\r\nHost: h\r\nContent-Length: 0\r\n\r\n",
+                path
+            ),
+        ],
+    )
+    .await;
+
+    let requests = app.requests().await;
+    assert_eq!(requests.len(), 2);
+    let second = requests
+        .iter()
+        .find(|request| request.body.is_empty())
+        .unwrap();
+    assert_eq!(
+        names_and_values(second),
+        pairs(&[("X-Second", "yes"), ("Host", "h"), ("Content-Length", "0")])
+    );
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::body::to_bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+
+use common::{owner, TestApp};
+use hookspy::handlers::webhook::{get_next_webhook_request, NextRequestParams};
+use hookspy::schema::webhook::WebhookRequest;
+
+async fn next(app: &TestApp, timeout: &str) -> Option<WebhookRequest> {
+    let Ok(response) = get_next_webhook_request(
+        State(app.state.clone()),
+        owner(),
+        Path(app.webhook_id.clone()),
+        Query(NextRequestParams {
+            after: None,
+            timeout: Some(timeout.to_string()),
+        }),
+    )
+    .await
+    else {
+        panic!("requests/next failed");
+    };
+
+    match response.status() {
+        StatusCode::NO_CONTENT => None,
+        _ => {
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            Some(serde_json::from_slice(&body).unwrap())
+        }
+    }
+}
+
+#[tokio::test]
+async fn without_after_starts_from_the_newest_stored_request() {
+    let app = TestApp::spawn().await;
+    // Stored with a clock ahead of this one; it is still not newer than itself
+    {
+        let db = app.state.db.lock().await;
+        db.execute(
+            "INSERT INTO webhook_requests (id, webhook_id, method, headers, body, body_raw, received_at) VALUES ('ahead', ?, 'POST', '[]', '', X'', '2999-01-01T00:00:00+00:00')",
+            turso::params![app.webhook_id.clone()],
+        )
+        .await
+        .unwrap();
+    }
+
+    assert!(next(&app, "200ms").await.is_none());
+}
+
+#[tokio::test]
+async fn without_after_returns_a_request_captured_while_waiting() {
+    let app = TestApp::spawn().await;
+    reqwest::Client::new()
+        .post(app.capture_url("/old"))
+        .send()
+        .await
+        .unwrap();
+
+    let capture_url = app.capture_url("/new");
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        reqwest::Client::new()
+            .post(capture_url)
+            .send()
+            .await
+            .unwrap();
+    });
+
+    let request = next(&app, "5s").await.unwrap();
+    assert_eq!(request.path.as_deref(), Some("/new"));
+}
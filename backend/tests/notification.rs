@@ -0,0 +1,47 @@
+mod common;
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use common::TestApp;
+use hookspy::schema::webhook::WebhookRequest;
+
+#[tokio::test]
+async fn stored_duration_covers_the_notification_fan_out() {
+    let app = TestApp::spawn().await;
+    let (tx, mut rx) = mpsc::channel(1);
+    app.state
+        .notification
+        .lock()
+        .await
+        .subscribe(app.webhook_id.clone(), "test".to_string(), tx);
+
+    // Holding the lock keeps the handler waiting to notify subscribers
+    let held = app.state.notification.lock().await;
+    let send = tokio::spawn(
+        reqwest::Client::new()
+            .post(app.capture_url(""))
+            .body("{}")
+            .send(),
+    );
+    // Once the handler holds the db lock it has started, and it keeps the lock until
+    // it has notified
+    while app.state.db.try_lock().is_ok() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    drop(held);
+    send.await.unwrap().unwrap();
+
+    let event = rx.recv().await.unwrap();
+    let notified: WebhookRequest = serde_json::from_str(&event.data).unwrap();
+    let stored = app.requests().await.remove(0);
+    assert_eq!(notified.id, stored.id);
+    assert_eq!(notified.duration_us, None);
+    assert!(
+        stored.duration_us.unwrap() >= 300_000,
+        "{:?}",
+        stored.duration_us
+    );
+}
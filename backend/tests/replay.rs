@@ -0,0 +1,129 @@
+mod common;
+
+use std::io::Write;
+
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::{Json, Router};
+use flate2::{write::GzEncoder, Compression};
+
+use common::{owner, recording_upstream, serve, test_config, Recorder, TestApp};
+use hookspy::capture::forward::MAX_RESPONSE_SIZE;
+use hookspy::config::Config;
+use hookspy::handlers::error::ApiError;
+use hookspy::handlers::replay::replay_webhook_request;
+use hookspy::schema::replay::Replay;
+
+/// Captures a gzip-encoded JSON body and returns the stored request's id.
+async fn capture_gzip(app: &TestApp) -> String {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(br#"{"event":"order.paid"}"#).unwrap();
+    reqwest::Client::new()
+        .post(app.capture_url(""))
+        .header("content-type", "application/json")
+        .header("content-encoding", "gzip")
+        .body(encoder.finish().unwrap())
+        .send()
+        .await
+        .unwrap();
+    app.requests().await.remove(0).id
+}
+
+async fn replay(app: &TestApp, request_id: String, replay: serde_json::Value) -> Replay {
+    let Json(replay) = replay_webhook_request(
+        State(app.state.clone()),
+        owner(),
+        Path((app.webhook_id.clone(), request_id)),
+        Json(serde_json::from_value(replay).unwrap()),
+    )
+    .await
+    .unwrap_or_else(|_| panic!("replay failed"));
+    replay
+}
+
+async fn received_header(received: &Recorder, name: &str) -> Option<String> {
+    received
+        .lock()
+        .await
+        .headers
+        .get(name)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn overridden_body_is_sent_without_the_captured_encoding() {
+    let app = TestApp::spawn().await;
+    let (upstream, received) = recording_upstream().await;
+    let request_id = capture_gzip(&app).await;
+
+    let replay = replay(
+        &app,
+        request_id,
+        serde_json::json!({ "url": upstream, "body": "{\"event\":\"order.refunded\"}" }),
+    )
+    .await;
+    assert_eq!(replay.response.status, Some(201));
+
+    assert_eq!(received_header(&received, "content-encoding").await, None);
+    assert_eq!(
+        received_header(&received, "content-length")
+            .await
+            .as_deref(),
+        Some("26")
+    );
+    assert_eq!(received.lock().await.body, r#"{"event":"order.refunded"}"#);
+}
+
+#[tokio::test]
+async fn stored_body_keeps_its_encoding() {
+    let app = TestApp::spawn().await;
+    let (upstream, received) = recording_upstream().await;
+    let request_id = capture_gzip(&app).await;
+
+    let replay = replay(&app, request_id, serde_json::json!({ "url": upstream })).await;
+    assert_eq!(replay.response.status, Some(201));
+
+    assert_eq!(
+        received_header(&received, "content-encoding")
+            .await
+            .as_deref(),
+        Some("gzip")
+    );
+}
+
+#[tokio::test]
+async fn private_target_is_refused_unless_allowed() {
+    let app = TestApp::spawn_with(Config {
+        allow_private_targets: false,
+        ..test_config()
+    })
+    .await;
+    let (upstream, received) = recording_upstream().await;
+    let request_id = capture_gzip(&app).await;
+
+    let result = replay_webhook_request(
+        State(app.state.clone()),
+        owner(),
+        Path((app.webhook_id.clone(), request_id)),
+        Json(serde_json::from_value(serde_json::json!({ "url": upstream })).unwrap()),
+    )
+    .await;
+
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    assert!(received.lock().await.method.is_none());
+}
+
+#[tokio::test]
+async fn oversized_answer_is_recorded_as_an_error() {
+    let app = TestApp::spawn().await;
+    let upstream =
+        serve(Router::new().fallback(|| async { Body::from(vec![b'x'; MAX_RESPONSE_SIZE + 1]) }))
+            .await;
+    let request_id = capture_gzip(&app).await;
+
+    let replay = replay(&app, request_id, serde_json::json!({ "url": upstream })).await;
+
+    assert_eq!(replay.response.status, None);
+    let error = replay.response.error.unwrap();
+    assert!(error.contains("larger than"), "{}", error);
+}
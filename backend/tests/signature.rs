@@ -0,0 +1,119 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use common::TestApp;
+use hookspy::schema::signature::{SignatureConfig, SignatureStatus};
+use hookspy::schema::webhook::CaptureSetting;
+
+const AUTH_TOKEN: &str = "twilio-auth-token";
+
+fn twilio_signature(payload: &str) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(AUTH_TOKEN.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+#[tokio::test]
+async fn twilio_signs_the_url_the_sender_called_through_a_proxy() {
+    let app = TestApp::spawn().await;
+    app.set(CaptureSetting::Signature(Some(
+        serde_json::from_value::<SignatureConfig>(serde_json::json!({
+            "provider": "twilio",
+            "secret": AUTH_TOKEN,
+        }))
+        .unwrap(),
+    )))
+    .await;
+
+    // Percent-encoding in the path is signed as sent, not decoded
+    let path_and_query = format!("/api/hooks/{}/sms%20in?From=%2B1", app.webhook_id);
+    let public_url = format!("https://hooks.example.com{}", path_and_query);
+    let signature = twilio_signature(&format!("{}Bodyhi", public_url));
+
+    reqwest::Client::new()
+        .post(format!("{}{}", app.base_url, path_and_query))
+        .header("x-forwarded-proto", "https")
+        .header("x-forwarded-host", "hooks.example.com")
+        .header("content-type", "application/x-www-form-urlencoded")
+        .header("x-twilio-signature", signature)
+        .body("Body=hi")
+        .send()
+        .await
+        .unwrap();
+
+    let request = app.requests().await.remove(0);
+    let check = request.signature.unwrap();
+    assert_eq!(check.status, SignatureStatus::Verified, "{:?}", check);
+}
+
+#[tokio::test]
+async fn twilio_falls_back_to_the_host_header() {
+    let app = TestApp::spawn().await;
+    app.set(CaptureSetting::Signature(Some(
+        serde_json::from_value::<SignatureConfig>(serde_json::json!({
+            "provider": "twilio",
+            "secret": AUTH_TOKEN,
+        }))
+        .unwrap(),
+    )))
+    .await;
+
+    let url = app.capture_url("/voice?CallSid=CA1");
+    let signature = twilio_signature(&url);
+
+    reqwest::Client::new()
+        .post(&url)
+        .header("x-twilio-signature", signature)
+        .send()
+        .await
+        .unwrap();
+
+    let request = app.requests().await.remove(0);
+    let check = request.signature.unwrap();
+    assert_eq!(check.status, SignatureStatus::Verified, "{:?}", check);
+}
+
+#[tokio::test]
+async fn public_copies_leave_out_the_expected_signature() {
+    let app = TestApp::spawn().await;
+    app.set(CaptureSetting::Signature(Some(
+        serde_json::from_value::<SignatureConfig>(serde_json::json!({
+            "provider": "github",
+            "secret": "github-secret",
+        }))
+        .unwrap(),
+    )))
+    .await;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    app.state
+        .notification
+        .lock()
+        .await
+        .subscribe(app.webhook_id.clone(), "test".to_string(), tx);
+
+    let reply: serde_json::Value = reqwest::Client::new()
+        .post(app.capture_url(""))
+        .body("{}")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let notified: serde_json::Value = serde_json::from_str(&rx.recv().await.unwrap().data).unwrap();
+
+    for public in [&reply["signature"], &notified["signature"]] {
+        assert_eq!(public["status"], "missing");
+        assert_eq!(public["header"], "x-hub-signature-256");
+        assert!(public["expected"].is_null(), "{}", public);
+        assert!(public["signed_payload"].is_null(), "{}", public);
+    }
+
+    // The owner still sees what the secret signs to
+    let stored = app.requests().await.remove(0).signature.unwrap();
+    assert!(stored.expected.is_some());
+    assert!(stored.signed_payload.is_some());
+}
@@ -12,16 +12,14 @@ pub struct CreateWebhookModalProps {
 pub fn CreateWebhookModal(
     CreateWebhookModalProps { is_open, on_close }: &CreateWebhookModalProps,
 ) -> Html {
-    let name = use_state(|| String::new());
+    let name = use_state(String::new);
     let input_ref = use_node_ref();
 
     {
         let input_ref = input_ref.clone();
         use_effect_with(*is_open, move |&is_open| {
-            if is_open {
-                if let Some(input) = input_ref.cast::<HtmlInputElement>() {
-                    let _ = input.focus();
-                }
+            if is_open && let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                let _ = input.focus();
             }
         });
     }
@@ -0,0 +1,301 @@
+use gloo_net::http::Request;
+use serde::Deserialize;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::components::webhook_list::Webhook;
+use crate::components::webhook_request_details::{format_duration, status_class};
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl DeliveryStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Dead => "dead",
+        }
+    }
+
+    fn badge_class(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "status-warning",
+            DeliveryStatus::Delivered => "status-success",
+            DeliveryStatus::Dead => "status-error",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct DeliveryAttempt {
+    pub attempt: u32,
+    pub started_at: String,
+    pub status: Option<u16>,
+    pub latency_us: u64,
+    pub error: Option<String>,
+    pub response_body: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Delivery {
+    pub id: String,
+    pub request_id: String,
+    pub url: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub attempt_limit: u32,
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub last_error: Option<String>,
+    pub attempt_log: Vec<DeliveryAttempt>,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct DeliveriesPanelProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible list of fan-out deliveries, with the dead-letter queue's retry and discard
+/// actions.
+#[component]
+pub fn DeliveriesPanel(DeliveriesPanelProps { webhook }: &DeliveriesPanelProps) -> Html {
+    let expanded = use_state(|| false);
+    let filter = use_state(|| None::<DeliveryStatus>);
+    let deliveries = use_state(Vec::<Delivery>::new);
+    let error = use_state(|| None::<String>);
+    // Bumped to fetch the list again
+    let reload = use_state(|| 0u32);
+
+    {
+        let deliveries = deliveries.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        use_effect_with(
+            (webhook.id.clone(), *expanded, *filter, *reload),
+            move |(_, expanded, filter, _)| {
+                if *expanded {
+                    let deliveries = deliveries.clone();
+                    let error = error.clone();
+                    let url = match filter {
+                        Some(status) => format!(
+                            "/api/webhooks/{}/deliveries?status={}",
+                            webhook_id,
+                            status.as_str()
+                        ),
+                        None => format!("/api/webhooks/{}/deliveries", webhook_id),
+                    };
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match Request::get(&url).send().await {
+                            Ok(resp) if resp.ok() => match resp.json::<Vec<Delivery>>().await {
+                                Ok(fetched) => {
+                                    deliveries.set(fetched);
+                                    error.set(None);
+                                }
+                                Err(err) => {
+                                    web_sys::console::error_1(&err.to_string().into());
+                                }
+                            },
+                            Ok(resp) => {
+                                error.set(Some(format!("Request failed with {}", resp.status())));
+                            }
+                            Err(err) => {
+                                web_sys::console::error_1(&err.to_string().into());
+                            }
+                        }
+                    });
+                }
+                || ()
+            },
+        );
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_filter = {
+        let filter = filter.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            filter.set(match select.value().as_str() {
+                "pending" => Some(DeliveryStatus::Pending),
+                "delivered" => Some(DeliveryStatus::Delivered),
+                "dead" => Some(DeliveryStatus::Dead),
+                _ => None,
+            });
+        })
+    };
+
+    let on_refresh = {
+        let reload = reload.clone();
+        Callback::from(move |_: MouseEvent| reload.set(*reload + 1))
+    };
+
+    // Runs a retry or discard, then fetches the list again.
+    let act = {
+        let reload = reload.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        move |delivery_id: String, retry: bool| {
+            let reload = reload.clone();
+            let error = error.clone();
+            let webhook_id = webhook_id.clone();
+            Callback::from(move |_: MouseEvent| {
+                let reload = reload.clone();
+                let error = error.clone();
+                let url = format!("/api/webhooks/{}/deliveries/{}", webhook_id, delivery_id);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let req = if retry {
+                        Request::post(&format!("{}/retry", url))
+                    } else {
+                        Request::delete(&url)
+                    };
+
+                    match req.send().await {
+                        Ok(resp) if resp.ok() => {
+                            reload.set(*reload + 1);
+                        }
+                        Ok(resp) => {
+                            let message = resp
+                                .json::<ErrorBody>()
+                                .await
+                                .map(|body| body.error)
+                                .unwrap_or_else(|_| {
+                                    format!("Request failed with {}", resp.status())
+                                });
+                            error.set(Some(message));
+                        }
+                        Err(err) => {
+                            web_sys::console::error_1(&err.to_string().into());
+                        }
+                    }
+                });
+            })
+        }
+    };
+
+    let dead = deliveries
+        .iter()
+        .filter(|delivery| delivery.status == DeliveryStatus::Dead)
+        .count();
+    let summary = if !*expanded {
+        "Queued copies of captured requests".to_string()
+    } else if dead > 0 {
+        format!("{} shown, {} dead-lettered", deliveries.len(), dead)
+    } else {
+        format!("{} shown", deliveries.len())
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Deliveries" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <div class="settings-panel-body">
+                    <div class="delivery-toolbar">
+                        <select class="form-input" onchange={on_filter}>
+                            <option value="" selected={filter.is_none()}>{ "All deliveries" }</option>
+                            <option value="pending" selected={*filter == Some(DeliveryStatus::Pending)}>
+                                { "Pending" }
+                            </option>
+                            <option value="delivered" selected={*filter == Some(DeliveryStatus::Delivered)}>
+                                { "Delivered" }
+                            </option>
+                            <option value="dead" selected={*filter == Some(DeliveryStatus::Dead)}>
+                                { "Dead-lettered" }
+                            </option>
+                        </select>
+                        <button type="button" class="btn btn-secondary btn-sm" onclick={on_refresh}>
+                            { "Refresh" }
+                        </button>
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    if deliveries.is_empty() {
+                        <p class="settings-hint">{ "No deliveries yet." }</p>
+                    }
+                    { for deliveries.iter().map(|delivery| html! {
+                        <div class="delivery" key={delivery.id.clone()}>
+                            <div class="delivery-header">
+                                <span class={classes!("status-badge", delivery.status.badge_class())}>
+                                    { delivery.status.as_str() }
+                                </span>
+                                <span class="delivery-url">{ &delivery.url }</span>
+                                <span class="settings-panel-summary">
+                                    { format!("{}/{} attempts", delivery.attempts, delivery.attempt_limit) }
+                                </span>
+                                if delivery.status == DeliveryStatus::Dead {
+                                    <button
+                                        type="button"
+                                        class="btn btn-primary btn-sm"
+                                        onclick={act(delivery.id.clone(), true)}
+                                    >
+                                        { "Retry" }
+                                    </button>
+                                }
+                                <button
+                                    type="button"
+                                    class="btn btn-danger btn-sm"
+                                    onclick={act(delivery.id.clone(), false)}
+                                >
+                                    { "Discard" }
+                                </button>
+                            </div>
+                            <div class="delivery-meta">
+                                { format!("Request {} · queued {}", delivery.request_id, delivery.created_at) }
+                                if let Some(next) = delivery.next_attempt_at.as_ref() {
+                                    { format!(" · next attempt {}", next) }
+                                }
+                            </div>
+                            if let Some(last_error) = delivery.last_error.as_ref() {
+                                <div class="settings-error">{ last_error }</div>
+                            }
+                            { for delivery.attempt_log.iter().map(|attempt| html! {
+                                <div class="delivery-attempt">
+                                    <span class="delivery-attempt-number">{ format!("#{}", attempt.attempt) }</span>
+                                    {
+                                        match attempt.status {
+                                            Some(status) => html! {
+                                                <span class={classes!("status-badge", status_class(status))}>
+                                                    { status }
+                                                </span>
+                                            },
+                                            None => html! {
+                                                <span class={classes!("status-badge", "status-error")}>
+                                                    { "failed" }
+                                                </span>
+                                            },
+                                        }
+                                    }
+                                    <span>{ format!("{} · {}", attempt.started_at, format_duration(attempt.latency_us)) }</span>
+                                    if let Some(error) = attempt.error.as_ref() {
+                                        <span class="delivery-attempt-error">{ error }</span>
+                                    }
+                                    if let Some(body) = attempt.response_body.as_ref().filter(|body| !body.is_empty()) {
+                                        <pre class="code-block">{ body }</pre>
+                                    }
+                                </div>
+                            }) }
+                        </div>
+                    }) }
+                </div>
+            }
+        </div>
+    }
+}
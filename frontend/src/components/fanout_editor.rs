@@ -0,0 +1,310 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::webhook_list::Webhook;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanOutConfig {
+    pub targets: Vec<String>,
+    pub timeout_ms: u64,
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+}
+
+/// Form fields, kept as the raw text the user typed.
+#[derive(Clone, PartialEq)]
+struct FanOutForm {
+    targets: String,
+    timeout_ms: String,
+    max_attempts: String,
+    initial_backoff_ms: String,
+}
+
+impl FanOutForm {
+    fn from_config(config: Option<&FanOutConfig>) -> Self {
+        match config {
+            Some(config) => FanOutForm {
+                targets: config.targets.join("\n"),
+                timeout_ms: config.timeout_ms.to_string(),
+                max_attempts: config.max_attempts.to_string(),
+                initial_backoff_ms: config.initial_backoff_ms.to_string(),
+            },
+            None => FanOutForm {
+                targets: String::new(),
+                timeout_ms: "10000".to_string(),
+                max_attempts: "5".to_string(),
+                initial_backoff_ms: "1000".to_string(),
+            },
+        }
+    }
+
+    /// Parses the form, one target URL per line.
+    fn to_config(&self) -> Result<FanOutConfig, String> {
+        fn number<T: std::str::FromStr>(value: &str, label: &str) -> Result<T, String> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid {} \"{}\"", label, value.trim()))
+        }
+
+        Ok(FanOutConfig {
+            targets: self
+                .targets
+                .lines()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(str::to_string)
+                .collect(),
+            timeout_ms: number(&self.timeout_ms, "timeout")?,
+            max_attempts: number(&self.max_attempts, "attempt count")?,
+            initial_backoff_ms: number(&self.initial_backoff_ms, "backoff")?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FanOutEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the targets a webhook delivers captured requests to.
+#[component]
+pub fn FanOutEditor(FanOutEditorProps { webhook }: &FanOutEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.fanout.clone());
+    let form = use_state(|| FanOutForm::from_config(webhook.fanout.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let fanout = webhook.fanout.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            form.set(FanOutForm::from_config(fanout.as_ref()));
+            saved.set(fanout);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_targets = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(FanOutForm {
+                targets: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_timeout = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(FanOutForm {
+                timeout_ms: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_max_attempts = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(FanOutForm {
+                max_attempts: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_backoff = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(FanOutForm {
+                initial_backoff_ms: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let config = match form.to_config() {
+                Ok(config) => config,
+                Err(err) => {
+                    error.set(Some(err));
+                    return;
+                }
+            };
+
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/fanout", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&config) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.fanout);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_stop = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let form = form.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/fanout", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        form.set(FanOutForm::from_config(None));
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = match saved.as_ref() {
+        Some(config) if config.targets.len() == 1 => "Delivering to 1 target".to_string(),
+        Some(config) => format!("Delivering to {} targets", config.targets.len()),
+        None => "Off".to_string(),
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Fan-out" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "Every captured request is also delivered to each target in the background, \
+                           with the sub-path and query string appended. A 2xx answer completes the \
+                           delivery; anything else is retried, waiting twice as long each time, until \
+                           the attempts run out and the delivery lands in the dead-letter queue. \
+                           The caller's reply is not affected." }
+                    </p>
+                    <div class="form-group">
+                        <label class="form-label">{ "Targets" }</label>
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="3"
+                            placeholder="https://service-a.example.com/webhooks"
+                            value={form.targets.clone()}
+                            oninput={on_targets}
+                        />
+                    </div>
+                    <div class="settings-grid">
+                        <div class="form-group">
+                            <label class="form-label">{ "Timeout (ms)" }</label>
+                            <input
+                                type="number"
+                                class="form-input"
+                                min="1"
+                                max="60000"
+                                value={form.timeout_ms.clone()}
+                                oninput={on_timeout}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label class="form-label">{ "Max attempts" }</label>
+                            <input
+                                type="number"
+                                class="form-input"
+                                min="1"
+                                max="20"
+                                value={form.max_attempts.clone()}
+                                oninput={on_max_attempts}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label class="form-label">{ "First retry after (ms)" }</label>
+                            <input
+                                type="number"
+                                class="form-input"
+                                min="1"
+                                value={form.initial_backoff_ms.clone()}
+                                oninput={on_backoff}
+                            />
+                        </div>
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_stop}>
+                                { "Stop fan-out" }
+                            </button>
+                        }
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save targets" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
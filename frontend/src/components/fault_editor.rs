@@ -0,0 +1,348 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::webhook_list::Webhook;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultProfile {
+    pub latency_ms: u64,
+    pub jitter_ms: u64,
+    pub error_rate: u8,
+    pub error_status: u16,
+    pub reset_rate: u8,
+    pub timeout_rate: u8,
+    pub timeout_ms: u64,
+    pub slow_rate: u8,
+    pub slow_chunk_bytes: usize,
+    pub slow_chunk_delay_ms: u64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FaultAction {
+    Error {
+        status: u16,
+    },
+    Reset,
+    Timeout {
+        after_ms: u64,
+    },
+    SlowBody {
+        chunk_bytes: usize,
+        chunk_delay_ms: u64,
+    },
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct InjectedFault {
+    pub latency_ms: u64,
+    pub action: Option<FaultAction>,
+}
+
+impl InjectedFault {
+    /// Short description for the request card, e.g. "Injected 503 · +250 ms".
+    pub fn label(&self) -> String {
+        let action = self.action.as_ref().map(|action| match action {
+            FaultAction::Error { status } => format!("Injected {}", status),
+            FaultAction::Reset => "Connection reset".to_string(),
+            FaultAction::Timeout { after_ms } => format!("Timed out after {} ms", after_ms),
+            FaultAction::SlowBody {
+                chunk_bytes,
+                chunk_delay_ms,
+            } => format!("Slow body · {} B every {} ms", chunk_bytes, chunk_delay_ms),
+        });
+
+        match (action, self.latency_ms) {
+            (Some(action), 0) => action,
+            (Some(action), latency) => format!("{} · +{} ms", action, latency),
+            (None, latency) => format!("+{} ms", latency),
+        }
+    }
+}
+
+/// Form fields, kept as the raw text the user typed.
+#[derive(Clone, PartialEq)]
+struct FaultForm {
+    latency_ms: String,
+    jitter_ms: String,
+    error_rate: String,
+    error_status: String,
+    reset_rate: String,
+    timeout_rate: String,
+    timeout_ms: String,
+    slow_rate: String,
+    slow_chunk_bytes: String,
+    slow_chunk_delay_ms: String,
+}
+
+impl FaultForm {
+    fn from_profile(profile: Option<&FaultProfile>) -> Self {
+        let profile = profile.cloned().unwrap_or(FaultProfile {
+            latency_ms: 0,
+            jitter_ms: 0,
+            error_rate: 0,
+            error_status: 503,
+            reset_rate: 0,
+            timeout_rate: 0,
+            timeout_ms: 30_000,
+            slow_rate: 0,
+            slow_chunk_bytes: 16,
+            slow_chunk_delay_ms: 500,
+        });
+
+        FaultForm {
+            latency_ms: profile.latency_ms.to_string(),
+            jitter_ms: profile.jitter_ms.to_string(),
+            error_rate: profile.error_rate.to_string(),
+            error_status: profile.error_status.to_string(),
+            reset_rate: profile.reset_rate.to_string(),
+            timeout_rate: profile.timeout_rate.to_string(),
+            timeout_ms: profile.timeout_ms.to_string(),
+            slow_rate: profile.slow_rate.to_string(),
+            slow_chunk_bytes: profile.slow_chunk_bytes.to_string(),
+            slow_chunk_delay_ms: profile.slow_chunk_delay_ms.to_string(),
+        }
+    }
+
+    fn to_profile(&self) -> Result<FaultProfile, String> {
+        fn number<T: std::str::FromStr>(label: &str, value: &str) -> Result<T, String> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid {} \"{}\"", label, value.trim()))
+        }
+
+        Ok(FaultProfile {
+            latency_ms: number("latency", &self.latency_ms)?,
+            jitter_ms: number("jitter", &self.jitter_ms)?,
+            error_rate: number("error rate", &self.error_rate)?,
+            error_status: number("error status", &self.error_status)?,
+            reset_rate: number("reset rate", &self.reset_rate)?,
+            timeout_rate: number("timeout rate", &self.timeout_rate)?,
+            timeout_ms: number("timeout", &self.timeout_ms)?,
+            slow_rate: number("slow rate", &self.slow_rate)?,
+            slow_chunk_bytes: number("chunk size", &self.slow_chunk_bytes)?,
+            slow_chunk_delay_ms: number("chunk delay", &self.slow_chunk_delay_ms)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FaultEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the failures injected into a webhook's replies.
+#[component]
+pub fn FaultEditor(FaultEditorProps { webhook }: &FaultEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.fault_profile.clone());
+    let form = use_state(|| FaultForm::from_profile(webhook.fault_profile.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let profile = webhook.fault_profile.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            form.set(FaultForm::from_profile(profile.as_ref()));
+            saved.set(profile);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_field = |set: fn(&mut FaultForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*form).clone();
+            set(&mut next, input.value());
+            form.set(next);
+        })
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let profile = match form.to_profile() {
+                Ok(profile) => profile,
+                Err(err) => {
+                    error.set(Some(err));
+                    return;
+                }
+            };
+
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/faults", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&profile) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.fault_profile);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_disable = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let form = form.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/faults", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        form.set(FaultForm::from_profile(None));
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = match saved.as_ref() {
+        Some(profile) => {
+            let mut parts = Vec::new();
+            if profile.latency_ms > 0 || profile.jitter_ms > 0 {
+                parts.push(format!(
+                    "{}–{} ms delay",
+                    profile.latency_ms,
+                    profile.latency_ms + profile.jitter_ms
+                ));
+            }
+            for (rate, label) in [
+                (profile.error_rate, "errors"),
+                (profile.reset_rate, "resets"),
+                (profile.timeout_rate, "timeouts"),
+                (profile.slow_rate, "slow bodies"),
+            ] {
+                if rate > 0 {
+                    parts.push(format!("{}% {}", rate, label));
+                }
+            }
+            if parts.is_empty() {
+                "On · no faults".to_string()
+            } else {
+                parts.join(" · ")
+            }
+        }
+        None => "Off".to_string(),
+    };
+
+    let field = |label: &str, value: &String, oninput: Callback<InputEvent>| {
+        html! {
+            <div class="form-group">
+                <label class="form-label">{ label }</label>
+                <input type="number" class="form-input" min="0" value={value.clone()} {oninput} />
+            </div>
+        }
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Faults" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "Every reply is delayed by the latency plus a random jitter. The rates are \
+                           percentages of requests, rolled together, that get an error status, a \
+                           dropped connection, no reply until the timeout, or a body trickled out \
+                           in chunks. The injected fault is stored with each request." }
+                    </p>
+                    <div class="settings-grid">
+                        { field("Latency (ms)", &form.latency_ms, on_field(|f, v| f.latency_ms = v)) }
+                        { field("Jitter (ms)", &form.jitter_ms, on_field(|f, v| f.jitter_ms = v)) }
+                    </div>
+                    <div class="settings-grid">
+                        { field("Error rate (%)", &form.error_rate, on_field(|f, v| f.error_rate = v)) }
+                        { field("Error status", &form.error_status, on_field(|f, v| f.error_status = v)) }
+                    </div>
+                    <div class="settings-grid">
+                        { field("Reset rate (%)", &form.reset_rate, on_field(|f, v| f.reset_rate = v)) }
+                    </div>
+                    <div class="settings-grid">
+                        { field("Timeout rate (%)", &form.timeout_rate, on_field(|f, v| f.timeout_rate = v)) }
+                        { field("Timeout (ms)", &form.timeout_ms, on_field(|f, v| f.timeout_ms = v)) }
+                    </div>
+                    <div class="settings-grid">
+                        { field("Slow rate (%)", &form.slow_rate, on_field(|f, v| f.slow_rate = v)) }
+                        { field("Chunk size (bytes)", &form.slow_chunk_bytes, on_field(|f, v| f.slow_chunk_bytes = v)) }
+                        { field("Chunk delay (ms)", &form.slow_chunk_delay_ms, on_field(|f, v| f.slow_chunk_delay_ms = v)) }
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_disable}>
+                                { "Disable faults" }
+                            </button>
+                        }
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save faults" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
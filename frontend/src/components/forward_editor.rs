@@ -0,0 +1,331 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::response_editor::ResponseHeader;
+use crate::components::webhook_list::Webhook;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardTarget {
+    pub url: String,
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub set_headers: Vec<ResponseHeader>,
+    #[serde(default)]
+    pub remove_headers: Vec<String>,
+}
+
+/// Form fields, kept as the raw text the user typed.
+#[derive(Clone, PartialEq)]
+struct ForwardForm {
+    url: String,
+    timeout_ms: String,
+    set_headers: String,
+    remove_headers: String,
+}
+
+impl ForwardForm {
+    fn from_target(target: Option<&ForwardTarget>) -> Self {
+        match target {
+            Some(target) => ForwardForm {
+                url: target.url.clone(),
+                timeout_ms: target.timeout_ms.to_string(),
+                set_headers: target
+                    .set_headers
+                    .iter()
+                    .map(|header| format!("{}: {}", header.name, header.value))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                remove_headers: target.remove_headers.join("\n"),
+            },
+            None => ForwardForm {
+                url: String::new(),
+                timeout_ms: "10000".to_string(),
+                set_headers: String::new(),
+                remove_headers: String::new(),
+            },
+        }
+    }
+
+    /// Parses the form, one `Name: value` header to set and one header name to remove
+    /// per line.
+    fn to_target(&self) -> Result<ForwardTarget, String> {
+        let timeout_ms = self
+            .timeout_ms
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid timeout \"{}\"", self.timeout_ms.trim()))?;
+
+        let set_headers = self
+            .set_headers
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split_once(':')
+                    .map(|(name, value)| ResponseHeader {
+                        name: name.trim().to_string(),
+                        value: value.trim().to_string(),
+                    })
+                    .ok_or_else(|| format!("Expected \"Name: value\", got \"{}\"", line.trim()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let remove_headers = self
+            .remove_headers
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(ForwardTarget {
+            url: self.url.trim().to_string(),
+            timeout_ms,
+            set_headers,
+            remove_headers,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ForwardEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the upstream a webhook relays captured requests to.
+#[component]
+pub fn ForwardEditor(ForwardEditorProps { webhook }: &ForwardEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.forward.clone());
+    let form = use_state(|| ForwardForm::from_target(webhook.forward.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let forward = webhook.forward.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            form.set(ForwardForm::from_target(forward.as_ref()));
+            saved.set(forward);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_url = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ForwardForm {
+                url: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_timeout = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ForwardForm {
+                timeout_ms: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_set_headers = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ForwardForm {
+                set_headers: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_remove_headers = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ForwardForm {
+                remove_headers: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let target = match form.to_target() {
+                Ok(target) => target,
+                Err(err) => {
+                    error.set(Some(err));
+                    return;
+                }
+            };
+
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/forward", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&target) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.forward);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_stop = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let form = form.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/forward", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        form.set(ForwardForm::from_target(None));
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = match saved.as_ref() {
+        Some(target) => format!("Relaying to {}", target.url),
+        None => "Off".to_string(),
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Forward" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "Captured requests are still recorded, then relayed with the same method, \
+                           body and headers. The sub-path and query string are appended to the URL \
+                           and the caller gets the upstream's answer, or a 502 when there is none. \
+                           Forwarding takes precedence over the sequence and response above." }
+                    </p>
+                    <div class="settings-row">
+                        <div class="form-group">
+                            <label class="form-label">{ "Timeout (ms)" }</label>
+                            <input
+                                type="number"
+                                class="form-input"
+                                min="1"
+                                max="60000"
+                                value={form.timeout_ms.clone()}
+                                oninput={on_timeout}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label class="form-label">{ "Target URL" }</label>
+                            <input
+                                type="url"
+                                class="form-input"
+                                placeholder="e.g., https://staging.example.com/webhooks"
+                                value={form.url.clone()}
+                                oninput={on_url}
+                            />
+                        </div>
+                    </div>
+                    <div class="form-group">
+                        <label class="form-label">{ "Set headers" }</label>
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="3"
+                            placeholder="Authorization: Bearer staging-token"
+                            value={form.set_headers.clone()}
+                            oninput={on_set_headers}
+                        />
+                    </div>
+                    <div class="form-group">
+                        <label class="form-label">{ "Remove headers" }</label>
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="2"
+                            placeholder="X-Forwarded-For"
+                            value={form.remove_headers.clone()}
+                            oninput={on_remove_headers}
+                        />
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_stop}>
+                                { "Stop forwarding" }
+                            </button>
+                        }
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save forward target" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,215 @@
+use gloo_net::http::Request;
+use serde::Deserialize;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::components::webhook_list::Webhook;
+
+/// Example shown in an empty editor.
+const SCHEMA_PLACEHOLDER: &str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "type": "object",
+  "required": ["id", "type"],
+  "properties": {
+    "id": { "type": "string" },
+    "type": { "enum": ["order.created", "order.paid"] }
+  }
+}"#;
+
+/// One way a captured body breaks the webhook's JSON Schema.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the offending value; empty for the body itself
+    pub path: String,
+    pub schema_path: String,
+    pub message: String,
+}
+
+/// Outcome of validating a captured JSON body against the webhook's JSON Schema.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SchemaValidation {
+    pub valid: bool,
+    #[serde(default)]
+    pub errors: Vec<SchemaViolation>,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+fn schema_text(schema: Option<&serde_json::Value>) -> String {
+    schema
+        .and_then(|schema| serde_json::to_string_pretty(schema).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct JsonSchemaEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the JSON Schema captured JSON bodies are validated against.
+#[component]
+pub fn JsonSchemaEditor(JsonSchemaEditorProps { webhook }: &JsonSchemaEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.json_schema.clone());
+    let source = use_state(|| schema_text(webhook.json_schema.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let source = source.clone();
+        let error = error.clone();
+        let schema = webhook.json_schema.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            source.set(schema_text(schema.as_ref()));
+            saved.set(schema);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_input = {
+        let source = source.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            source.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let source = source.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let schema = match serde_json::from_str::<serde_json::Value>(&source) {
+                Ok(schema) => schema,
+                Err(err) => {
+                    error.set(Some(format!("Not valid JSON: {}", err)));
+                    return;
+                }
+            };
+
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/json-schema", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&serde_json::json!({ "schema": schema })) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.json_schema);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_remove = {
+        let source = source.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let source = source.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/json-schema", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        source.set(String::new());
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = if saved.is_some() {
+        "Validating JSON bodies"
+    } else {
+        "Off"
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "JSON Schema" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "Every captured JSON body is validated against this " }
+                        <a href="https://json-schema.org/" target="_blank" rel="noopener">{ "JSON Schema" }</a>
+                        { ". Failing requests are marked invalid and the offending values are \
+                           highlighted in the body. The draft follows " }
+                        <code>{ "$schema" }</code>
+                        { "; remote references are not fetched." }
+                    </p>
+                    <div class="form-group">
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="12"
+                            placeholder={SCHEMA_PLACEHOLDER}
+                            value={(*source).clone()}
+                            oninput={on_input}
+                        />
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_remove}>
+                                { "Stop validating" }
+                            </button>
+                        }
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save schema" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
@@ -1,7 +1,21 @@
 pub mod create_webhook_modal;
+pub mod deliveries_panel;
+pub mod fanout_editor;
+pub mod fault_editor;
+pub mod forward_editor;
+pub mod json_schema_editor;
+pub mod replay_panel;
+pub mod request_body;
+pub mod response_details;
+pub mod response_editor;
+pub mod schema_panel;
+pub mod script_editor;
+pub mod sequence_editor;
+pub mod signature_editor;
 pub mod theme_switcher;
 pub mod toast;
 pub mod tooltip;
+pub mod upstream_details;
 pub mod webhook_details;
 pub mod webhook_list;
 pub mod webhook_request_details;
@@ -0,0 +1,407 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::response_editor::ResponseHeader;
+use crate::components::upstream_details::{UpstreamDetails, UpstreamResponse};
+use crate::components::webhook_request_details::{RequestHeader, status_class};
+
+#[derive(Clone, PartialEq, Serialize)]
+struct ReplayRequest {
+    url: String,
+    timeout_ms: u64,
+    set_headers: Vec<ResponseHeader>,
+    remove_headers: Vec<String>,
+    body: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Replay {
+    pub id: String,
+    pub replayed_at: String,
+    pub method: String,
+    pub headers: Vec<RequestHeader>,
+    pub body_override: Option<String>,
+    pub response: UpstreamResponse,
+}
+
+/// Form fields, kept as the raw text the user typed.
+#[derive(Clone, PartialEq, Default)]
+struct ReplayForm {
+    url: String,
+    timeout_ms: String,
+    set_headers: String,
+    remove_headers: String,
+    override_body: bool,
+    body: String,
+}
+
+impl ReplayForm {
+    fn new(body: &str) -> Self {
+        ReplayForm {
+            timeout_ms: "10000".to_string(),
+            body: body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Parses the form, one `Name: value` header to set and one header name to remove
+    /// per line.
+    fn to_request(&self) -> Result<ReplayRequest, String> {
+        let timeout_ms = self
+            .timeout_ms
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid timeout \"{}\"", self.timeout_ms.trim()))?;
+
+        let set_headers = self
+            .set_headers
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split_once(':')
+                    .map(|(name, value)| ResponseHeader {
+                        name: name.trim().to_string(),
+                        value: value.trim().to_string(),
+                    })
+                    .ok_or_else(|| format!("Expected \"Name: value\", got \"{}\"", line.trim()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let remove_headers = self
+            .remove_headers
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(ReplayRequest {
+            url: self.url.trim().to_string(),
+            timeout_ms,
+            set_headers,
+            remove_headers,
+            body: self.override_body.then(|| self.body.clone()),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ReplayPanelProps {
+    pub webhook_id: String,
+    pub request_id: String,
+    /// Captured body, offered as the starting point for an override
+    pub body: String,
+    pub search_query: String,
+}
+
+/// Sends a captured request again and lists earlier replays.
+#[component]
+pub fn ReplayPanel(props: &ReplayPanelProps) -> Html {
+    let form = use_state(|| ReplayForm::new(&props.body));
+    let replays = use_state(Vec::<Replay>::new);
+    let sending = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    let opened = use_state(|| None::<String>);
+
+    let base_url = format!(
+        "/api/webhooks/{}/requests/{}",
+        props.webhook_id, props.request_id
+    );
+
+    {
+        let replays = replays.clone();
+        let url = format!("{}/replays", base_url);
+        use_effect_with(props.request_id.clone(), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(fetched) = resp.json::<Vec<Replay>>().await {
+                            replays.set(fetched);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_url = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ReplayForm {
+                url: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_timeout = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ReplayForm {
+                timeout_ms: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_set_headers = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ReplayForm {
+                set_headers: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_remove_headers = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ReplayForm {
+                remove_headers: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_override_body = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ReplayForm {
+                override_body: input.checked(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_body = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ReplayForm {
+                body: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let replays = replays.clone();
+        let sending = sending.clone();
+        let error = error.clone();
+        let opened = opened.clone();
+        let url = format!("{}/replay", base_url);
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let request = match form.to_request() {
+                Ok(request) => request,
+                Err(err) => {
+                    error.set(Some(err));
+                    return;
+                }
+            };
+
+            let replays = replays.clone();
+            let sending = sending.clone();
+            let error = error.clone();
+            let opened = opened.clone();
+            let url = url.clone();
+            sending.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::post(&url).json(&request) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        sending.set(false);
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(replay) = resp.json::<Replay>().await {
+                            opened.set(Some(replay.id.clone()));
+                            let mut updated = vec![replay];
+                            updated.extend((*replays).iter().cloned());
+                            replays.set(updated);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+                sending.set(false);
+            });
+        })
+    };
+
+    let on_open = |id: String| {
+        let opened = opened.clone();
+        Callback::from(move |_: MouseEvent| {
+            if opened.as_deref() == Some(id.as_str()) {
+                opened.set(None);
+            } else {
+                opened.set(Some(id.clone()));
+            }
+        })
+    };
+
+    html! {
+        <>
+            <form class="request-section" onsubmit={on_submit}>
+                <div class="section-title">{ "Replay" }</div>
+                <p class="settings-hint">
+                    { "Sends the stored method, headers and body to the URL as is. \
+                       Every replay is kept below with the answer and timing." }
+                </p>
+                <div class="settings-row">
+                    <div class="form-group">
+                        <label class="form-label">{ "Timeout (ms)" }</label>
+                        <input
+                            type="number"
+                            class="form-input"
+                            min="1"
+                            max="60000"
+                            value={form.timeout_ms.clone()}
+                            oninput={on_timeout}
+                        />
+                    </div>
+                    <div class="form-group">
+                        <label class="form-label">{ "Target URL" }</label>
+                        <input
+                            type="url"
+                            class="form-input"
+                            placeholder="e.g., http://localhost:8000/webhooks/github"
+                            value={form.url.clone()}
+                            oninput={on_url}
+                        />
+                    </div>
+                </div>
+                <div class="form-group">
+                    <label class="form-label">{ "Set headers" }</label>
+                    <textarea
+                        class="form-input form-textarea"
+                        rows="2"
+                        placeholder="X-Debug: 1"
+                        value={form.set_headers.clone()}
+                        oninput={on_set_headers}
+                    />
+                </div>
+                <div class="form-group">
+                    <label class="form-label">{ "Remove headers" }</label>
+                    <textarea
+                        class="form-input form-textarea"
+                        rows="2"
+                        placeholder="X-Hub-Signature-256"
+                        value={form.remove_headers.clone()}
+                        oninput={on_remove_headers}
+                    />
+                </div>
+                <div class="form-group">
+                    <label class="form-label">
+                        <input
+                            type="checkbox"
+                            checked={form.override_body}
+                            onchange={on_override_body}
+                        />
+                        { " Override body" }
+                    </label>
+                    if form.override_body {
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="6"
+                            value={form.body.clone()}
+                            oninput={on_body}
+                        />
+                    }
+                </div>
+                if let Some(message) = error.as_ref() {
+                    <div class="settings-error">{ message }</div>
+                }
+                <div class="settings-actions">
+                    <button type="submit" class="btn btn-primary btn-sm" disabled={*sending}>
+                        { if *sending { "Replaying…" } else { "Replay" } }
+                    </button>
+                </div>
+            </form>
+            if !replays.is_empty() {
+                <div class="request-section">
+                    <div class="section-title">{ "Replay History" }</div>
+                    { for replays.iter().map(|replay| {
+                        let is_open = opened.as_deref() == Some(replay.id.as_str());
+                        html! {
+                            <div class="replay" key={replay.id.clone()}>
+                                <button type="button" class="replay-header" onclick={on_open(replay.id.clone())}>
+                                    {
+                                        match replay.response.status {
+                                            Some(status) => html! {
+                                                <span class={classes!("status-badge", status_class(status))}>
+                                                    { status }
+                                                </span>
+                                            },
+                                            None => html! {
+                                                <span class={classes!("status-badge", "status-error")}>
+                                                    { "failed" }
+                                                </span>
+                                            },
+                                        }
+                                    }
+                                    <span class="delivery-url">
+                                        { format!("{} {}", replay.method, replay.response.url) }
+                                    </span>
+                                    if replay.body_override.is_some() {
+                                        <span class="tag-badge">{ "body overridden" }</span>
+                                    }
+                                    <span class="delivery-meta">{ &replay.replayed_at }</span>
+                                </button>
+                                if is_open {
+                                    <div class="key-value-list">
+                                        { for replay.headers.iter().map(|header| html! {
+                                            <div class="key-value-item">
+                                                <span class="key">{ &header.name }</span>
+                                                <span class="value">{ &header.value }</span>
+                                            </div>
+                                        }) }
+                                    </div>
+                                    <UpstreamDetails
+                                        upstream={replay.response.clone()}
+                                        title="Upstream"
+                                        search_query={props.search_query.clone()}
+                                    />
+                                }
+                            </div>
+                        }
+                    }) }
+                </div>
+            }
+        </>
+    }
+}
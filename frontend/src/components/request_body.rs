@@ -0,0 +1,367 @@
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use yew::prelude::*;
+
+use crate::components::json_schema_editor::SchemaViolation;
+use crate::components::webhook_request_details::{
+    BodyEncoding, QueryParam, RequestHeader, WebhookRequest, highlight_text,
+};
+
+/// Number of bytes shown in the hex dump of a binary body.
+const HEX_DUMP_LIMIT: usize = 4_096;
+
+/// Formats `bytes` as a classic offset / hex / ASCII dump, 16 bytes per line.
+fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", line * 16, hex.join(" "), ascii)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders the element tree produced by the backend XML parser as indented XML.
+fn render_xml(element: &serde_json::Value, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let name = element["name"].as_str().unwrap_or_default();
+
+    let attributes: String = element["attributes"]
+        .as_object()
+        .map(|attrs| {
+            attrs
+                .iter()
+                .map(|(key, value)| format!(" {}=\"{}\"", key, value.as_str().unwrap_or_default()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let text = element["text"].as_str().unwrap_or_default();
+    let children = element["children"].as_array().cloned().unwrap_or_default();
+
+    if children.is_empty() && text.is_empty() {
+        out.push_str(&format!("{}<{}{}/>\n", indent, name, attributes));
+    } else if children.is_empty() {
+        out.push_str(&format!(
+            "{}<{}{}>{}</{}>\n",
+            indent, name, attributes, text, name
+        ));
+    } else {
+        out.push_str(&format!("{}<{}{}>\n", indent, name, attributes));
+        if !text.is_empty() {
+            out.push_str(&format!("{}  {}\n", indent, text));
+        }
+        for child in children.iter() {
+            render_xml(child, depth + 1, out);
+        }
+        out.push_str(&format!("{}</{}>\n", indent, name));
+    }
+}
+
+/// Appends `value` pretty-printed like `serde_json::to_string_pretty`, one line at a
+/// time, each paired with the JSON Pointer of the value that starts on it.
+fn json_lines(
+    value: &serde_json::Value,
+    pointer: &str,
+    prefix: &str,
+    suffix: &str,
+    depth: usize,
+    out: &mut Vec<(String, Option<String>)>,
+) {
+    let indent = "  ".repeat(depth);
+    let (open, close, children): (&str, &str, Vec<(String, String, &serde_json::Value)>) =
+        match value {
+            serde_json::Value::Object(map) => (
+                "{",
+                "}",
+                map.iter()
+                    .map(|(key, child)| {
+                        let escaped = key.replace('~', "~0").replace('/', "~1");
+                        let label = serde_json::to_string(key).unwrap_or_default();
+                        (
+                            format!("{}/{}", pointer, escaped),
+                            format!("{}: ", label),
+                            child,
+                        )
+                    })
+                    .collect(),
+            ),
+            serde_json::Value::Array(items) => (
+                "[",
+                "]",
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, child)| (format!("{}/{}", pointer, index), String::new(), child))
+                    .collect(),
+            ),
+            scalar => {
+                out.push((
+                    format!("{}{}{}{}", indent, prefix, scalar, suffix),
+                    Some(pointer.to_string()),
+                ));
+                return;
+            }
+        };
+
+    if children.is_empty() {
+        out.push((
+            format!("{}{}{}{}{}", indent, prefix, open, close, suffix),
+            Some(pointer.to_string()),
+        ));
+        return;
+    }
+
+    out.push((
+        format!("{}{}{}", indent, prefix, open),
+        Some(pointer.to_string()),
+    ));
+    let last = children.len() - 1;
+    for (index, (child_pointer, child_prefix, child)) in children.into_iter().enumerate() {
+        let child_suffix = if index == last { "" } else { "," };
+        json_lines(
+            child,
+            &child_pointer,
+            &child_prefix,
+            child_suffix,
+            depth + 1,
+            out,
+        );
+    }
+    out.push((format!("{}{}{}", indent, close, suffix), None));
+}
+
+/// Pretty-printed JSON with the lines of values that broke the webhook's JSON Schema
+/// marked, and the reasons in their tooltip.
+fn json_view_with_violations(
+    value: &serde_json::Value,
+    violations: &[SchemaViolation],
+    query: &str,
+) -> Html {
+    let mut lines = Vec::new();
+    json_lines(value, "", "", "", 0, &mut lines);
+    let last = lines.len().saturating_sub(1);
+
+    html! {
+        <pre class="code-block">
+            { for lines.into_iter().enumerate().map(|(index, (text, pointer))| {
+                let reasons: Vec<&str> = violations
+                    .iter()
+                    .filter(|violation| pointer.as_deref() == Some(violation.path.as_str()))
+                    .map(|violation| violation.message.as_str())
+                    .collect();
+                let newline = if index == last { "" } else { "\n" };
+                html! {
+                    <>
+                        if reasons.is_empty() {
+                            { highlight_text(&text, query) }
+                        } else {
+                            <span class="schema-error-line" title={reasons.join("\n")}>
+                                { highlight_text(&text, query) }
+                            </span>
+                        }
+                        { newline }
+                    </>
+                }
+            }) }
+        </pre>
+    }
+}
+
+/// Hex dump of `bytes` with a download link, for bodies that are not text.
+fn binary_view(
+    bytes: &[u8],
+    base64: &str,
+    content_type: &str,
+    label: String,
+    filename: String,
+) -> Html {
+    let href = format!("data:{};base64,{}", content_type, base64);
+    let truncated = bytes.len() > HEX_DUMP_LIMIT;
+
+    html! {
+        <>
+            <div class="binary-body-bar">
+                <span class="binary-body-label">{ label }</span>
+                <a class="btn btn-primary btn-sm" href={href} download={filename}>
+                    { "Download" }
+                </a>
+            </div>
+            <pre class="code-block">
+                { hex_dump(&bytes[..bytes.len().min(HEX_DUMP_LIMIT)]) }
+            </pre>
+            if truncated {
+                <div class="binary-body-note">
+                    { format!("Showing the first {} of {} bytes", HEX_DUMP_LIMIT, bytes.len()) }
+                </div>
+            }
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RequestBodyProps {
+    pub request: WebhookRequest,
+    pub search_query: String,
+}
+
+/// Body section of a request card with a content-type-aware parsed view
+/// and the raw body as received.
+#[component]
+pub fn RequestBody(props: &RequestBodyProps) -> Html {
+    let show_raw = use_state(|| false);
+    let request = &props.request;
+    let query = &props.search_query;
+
+    let content_type = serde_json::from_str::<Vec<RequestHeader>>(&request.headers)
+        .ok()
+        .and_then(|list| {
+            list.into_iter()
+                .find(|header| header.name.eq_ignore_ascii_case("content-type"))
+                .map(|header| header.value)
+        })
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let has_parsed_view = request.content_encoding.is_some()
+        || matches!(
+            request.body_format.as_deref(),
+            Some("json" | "form" | "xml")
+        );
+
+    let parsed_view = || -> Html {
+        if request.body_encoding == BodyEncoding::Base64 {
+            let bytes = BASE64.decode(&request.body).unwrap_or_default();
+            return binary_view(
+                &bytes,
+                &request.body,
+                &content_type,
+                format!("Binary payload · {}", content_type),
+                format!("request-{}.bin", request.id),
+            );
+        }
+
+        match (request.body_format.as_deref(), &request.parsed_body) {
+            (Some("form"), Some(parsed)) => {
+                let fields: Vec<QueryParam> =
+                    serde_json::from_value(parsed.clone()).unwrap_or_default();
+                html! {
+                    <div class="key-value-list">
+                        { for fields.iter().map(|field| html! {
+                            <div class="key-value-item">
+                                <span class="key">{ highlight_text(&field.name, query) }</span>
+                                <span class="value">{ highlight_text(&field.value, query) }</span>
+                            </div>
+                        }) }
+                    </div>
+                }
+            }
+            (Some("xml"), Some(parsed)) => {
+                let mut xml = String::new();
+                render_xml(parsed, 0, &mut xml);
+                html! {
+                    <pre class="code-block">{ highlight_text(xml.trim_end(), query) }</pre>
+                }
+            }
+            _ => {
+                let invalid = request.schema_validation.as_ref().filter(|v| !v.valid);
+                if let (Some(validation), Ok(value)) = (
+                    invalid,
+                    serde_json::from_str::<serde_json::Value>(&request.body),
+                ) {
+                    return json_view_with_violations(&value, &validation.errors, query);
+                }
+
+                // Try to parse and pretty-print the body if it's JSON
+                let formatted_body = serde_json::from_str::<serde_json::Value>(&request.body)
+                    .ok()
+                    .and_then(|value| serde_json::to_string_pretty(&value).ok())
+                    .unwrap_or_else(|| request.body.clone());
+                html! {
+                    <pre class="code-block">{ highlight_text(&formatted_body, query) }</pre>
+                }
+            }
+        }
+    };
+
+    let raw_view = || -> Html {
+        if let (Some(raw), Some(coding)) = (&request.raw_body, &request.content_encoding) {
+            let bytes = BASE64.decode(raw).unwrap_or_default();
+            return binary_view(
+                &bytes,
+                raw,
+                "application/octet-stream",
+                format!("As received · Content-Encoding: {}", coding),
+                format!("request-{}.{}", request.id, coding),
+            );
+        }
+
+        if request.body_encoding == BodyEncoding::Base64 {
+            let bytes = BASE64.decode(&request.body).unwrap_or_default();
+            return binary_view(
+                &bytes,
+                &request.body,
+                &content_type,
+                format!("Binary payload · {}", content_type),
+                format!("request-{}.bin", request.id),
+            );
+        }
+
+        html! {
+            <pre class="code-block">{ highlight_text(&request.body, query) }</pre>
+        }
+    };
+
+    let on_parsed = {
+        let show_raw = show_raw.clone();
+        Callback::from(move |_: MouseEvent| show_raw.set(false))
+    };
+
+    let on_raw = {
+        let show_raw = show_raw.clone();
+        Callback::from(move |_: MouseEvent| show_raw.set(true))
+    };
+
+    html! {
+        <div class="request-section">
+            <div class="section-title body-section-title">
+                { "Body" }
+                if has_parsed_view {
+                    <div class="body-view-toggle">
+                        <button
+                            type="button"
+                            class={classes!(if *show_raw { None } else { Some("active") })}
+                            onclick={on_parsed}
+                        >
+                            { "Parsed" }
+                        </button>
+                        <button
+                            type="button"
+                            class={classes!(if *show_raw { Some("active") } else { None })}
+                            onclick={on_raw}
+                        >
+                            { "Raw" }
+                        </button>
+                    </div>
+                }
+            </div>
+            {
+                if has_parsed_view && *show_raw {
+                    raw_view()
+                } else {
+                    parsed_view()
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,68 @@
+use yew::prelude::*;
+
+use crate::components::webhook_request_details::{
+    SentResponse, format_duration, highlight_text, status_class,
+};
+
+#[derive(Properties, PartialEq)]
+pub struct ResponseDetailsProps {
+    pub response: SentResponse,
+    pub duration_us: Option<u64>,
+    pub search_query: String,
+}
+
+/// The reply HookSpy sent for a captured request.
+#[component]
+pub fn ResponseDetails(props: &ResponseDetailsProps) -> Html {
+    let response = &props.response;
+    let query = &props.search_query;
+
+    // Try to parse and pretty-print the body if it's JSON
+    let formatted_body = serde_json::from_str::<serde_json::Value>(&response.body)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| response.body.clone());
+
+    html! {
+        <>
+            <div class="request-section">
+                <div class="section-title">{ "Status" }</div>
+                <div class="key-value-list">
+                    <div class="key-value-item">
+                        <span class="key">
+                            <span class={classes!("status-badge", status_class(response.status))}>
+                                { response.status }
+                            </span>
+                        </span>
+                        <span class="value">
+                            {
+                                match props.duration_us {
+                                    Some(duration) => format!("Handled in {}", format_duration(duration)),
+                                    None => String::new(),
+                                }
+                            }
+                        </span>
+                    </div>
+                </div>
+                if let Some(ref error) = response.error {
+                    <div class="settings-error">{ error }</div>
+                }
+            </div>
+            <div class="request-section">
+                <div class="section-title">{ "Headers" }</div>
+                <div class="key-value-list">
+                    { for response.headers.iter().map(|header| html! {
+                        <div class="key-value-item">
+                            <span class="key">{ highlight_text(&header.name, query) }</span>
+                            <span class="value">{ highlight_text(&header.value, query) }</span>
+                        </div>
+                    }) }
+                </div>
+            </div>
+            <div class="request-section">
+                <div class="section-title">{ "Body" }</div>
+                <pre class="code-block">{ highlight_text(&formatted_body, query) }</pre>
+            </div>
+        </>
+    }
+}
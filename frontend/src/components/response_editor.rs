@@ -0,0 +1,350 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::webhook_list::Webhook;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseConfig {
+    pub status: u16,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<ResponseHeader>,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// Form fields, kept as the raw text the user typed.
+#[derive(Clone, PartialEq)]
+struct ResponseForm {
+    status: String,
+    content_type: String,
+    headers: String,
+    body: String,
+}
+
+impl ResponseForm {
+    fn from_config(config: Option<&ResponseConfig>) -> Self {
+        match config {
+            Some(config) => ResponseForm {
+                status: config.status.to_string(),
+                content_type: config.content_type.clone().unwrap_or_default(),
+                headers: config
+                    .headers
+                    .iter()
+                    .map(|header| format!("{}: {}", header.name, header.value))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                body: config.body.clone(),
+            },
+            None => ResponseForm {
+                status: "200".to_string(),
+                content_type: String::new(),
+                headers: String::new(),
+                body: String::new(),
+            },
+        }
+    }
+
+    /// Parses the form, one `Name: value` header per line.
+    fn to_config(&self) -> Result<ResponseConfig, String> {
+        let status = self
+            .status
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid status code \"{}\"", self.status.trim()))?;
+
+        let headers = self
+            .headers
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split_once(':')
+                    .map(|(name, value)| ResponseHeader {
+                        name: name.trim().to_string(),
+                        value: value.trim().to_string(),
+                    })
+                    .ok_or_else(|| format!("Expected \"Name: value\", got \"{}\"", line.trim()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let content_type = Some(self.content_type.trim().to_string()).filter(|ct| !ct.is_empty());
+
+        Ok(ResponseConfig {
+            status,
+            content_type,
+            headers,
+            body: self.body.clone(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ResponseEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the reply a webhook's capture URL sends back.
+#[component]
+pub fn ResponseEditor(ResponseEditorProps { webhook }: &ResponseEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.response.clone());
+    let form = use_state(|| ResponseForm::from_config(webhook.response.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let response = webhook.response.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            form.set(ResponseForm::from_config(response.as_ref()));
+            saved.set(response);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_status = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ResponseForm {
+                status: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_content_type = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(ResponseForm {
+                content_type: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_headers = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ResponseForm {
+                headers: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_body = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            form.set(ResponseForm {
+                body: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let config = match form.to_config() {
+                Ok(config) => config,
+                Err(err) => {
+                    error.set(Some(err));
+                    return;
+                }
+            };
+
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/response", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&config) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.response);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_reset = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let form = form.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/response", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        form.set(ResponseForm::from_config(None));
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = match saved.as_ref() {
+        Some(config) if config.headers.is_empty() => format!("Custom · {}", config.status),
+        Some(config) => format!(
+            "Custom · {} · {} header{}",
+            config.status,
+            config.headers.len(),
+            if config.headers.len() == 1 { "" } else { "s" }
+        ),
+        None => "Default · 200 with the captured request as JSON".to_string(),
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Response" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <div class="settings-row">
+                        <div class="form-group">
+                            <label class="form-label">{ "Status" }</label>
+                            <input
+                                type="number"
+                                class="form-input"
+                                min="200"
+                                max="599"
+                                value={form.status.clone()}
+                                oninput={on_status}
+                            />
+                        </div>
+                        <div class="form-group">
+                            <label class="form-label">{ "Content type" }</label>
+                            <input
+                                type="text"
+                                class="form-input"
+                                placeholder="e.g., application/json"
+                                value={form.content_type.clone()}
+                                oninput={on_content_type}
+                            />
+                        </div>
+                    </div>
+                    <div class="form-group">
+                        <label class="form-label">{ "Headers" }</label>
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="3"
+                            placeholder="X-Custom-Header: value"
+                            value={form.headers.clone()}
+                            oninput={on_headers}
+                        />
+                    </div>
+                    <div class="form-group">
+                        <label class="form-label">{ "Body" }</label>
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="6"
+                            value={form.body.clone()}
+                            oninput={on_body}
+                        />
+                    </div>
+                    <p class="settings-hint">
+                        { "The body, content type and header values are templates. Use " }
+                        <code>{ "{{ request.json.challenge }}" }</code>
+                        { ", " }
+                        <code>{ "{{ request.headers['x-request-id'] }}" }</code>
+                        { ", " }
+                        <code>{ "request.method" }</code>
+                        { ", " }
+                        <code>{ "request.path" }</code>
+                        { ", " }
+                        <code>{ "request.query" }</code>
+                        { ", " }
+                        <code>{ "request.body" }</code>
+                        { " and the helpers " }
+                        <code>{ "uuid()" }</code>
+                        { ", " }
+                        <code>{ "now()" }</code>
+                        { " and " }
+                        <code>{ "hmac(secret, message)" }</code>
+                        { "." }
+                    </p>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_reset}>
+                                { "Reset to default" }
+                            </button>
+                        }
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save response" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
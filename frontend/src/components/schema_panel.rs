@@ -0,0 +1,394 @@
+use gloo_net::http::Request;
+use gloo_timers::future::TimeoutFuture;
+use serde::Deserialize;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlSelectElement, window};
+use yew::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaFormat {
+    JsonSchema,
+    Rust,
+    Typescript,
+}
+
+impl SchemaFormat {
+    const ALL: [SchemaFormat; 3] = [
+        SchemaFormat::JsonSchema,
+        SchemaFormat::Rust,
+        SchemaFormat::Typescript,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SchemaFormat::JsonSchema => "json_schema",
+            SchemaFormat::Rust => "rust",
+            SchemaFormat::Typescript => "typescript",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            SchemaFormat::JsonSchema => "JSON Schema",
+            SchemaFormat::Rust => "Rust",
+            SchemaFormat::Typescript => "TypeScript",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct InferredSchema {
+    pub samples: u64,
+    pub event_types: Vec<String>,
+    pub format: SchemaFormat,
+    pub content: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeChangeKind {
+    Added,
+    Removed,
+    TypeChanged,
+}
+
+/// One way a captured JSON body departs from the baseline shape of its event type.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ShapeChange {
+    pub path: String,
+    pub change: ShapeChangeKind,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl ShapeChange {
+    pub fn describe(&self) -> String {
+        let expected = self.expected.as_deref().unwrap_or("?");
+        let actual = self.actual.as_deref().unwrap_or("?");
+        match self.change {
+            ShapeChangeKind::Added => format!("New field · {}", actual),
+            ShapeChangeKind::Removed => format!("Missing field · was {}", expected),
+            ShapeChangeKind::TypeChanged => format!("Type changed · {} → {}", expected, actual),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Baseline {
+    pub event_type: Option<String>,
+    pub samples: u64,
+    pub pinned: bool,
+    pub learning: bool,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SchemaPanelProps {
+    pub webhook_id: String,
+}
+
+/// Schema inferred from the webhook's captured JSON bodies, exported as JSON Schema,
+/// Rust or TypeScript, with the drift baselines each event type is checked against.
+#[component]
+pub fn SchemaPanel(props: &SchemaPanelProps) -> Html {
+    let format = use_state(|| SchemaFormat::JsonSchema);
+    let event_type = use_state(|| None::<String>);
+    let schema = use_state(|| None::<InferredSchema>);
+    let error = use_state(|| None::<String>);
+    let copied = use_state(|| false);
+    let baselines = use_state(Vec::<Baseline>::new);
+    // Bumped to infer again from the latest requests
+    let reload = use_state(|| 0u32);
+
+    {
+        let event_type = event_type.clone();
+        use_effect_with(props.webhook_id.clone(), move |_| {
+            event_type.set(None);
+            || ()
+        });
+    }
+
+    {
+        let schema = schema.clone();
+        let error = error.clone();
+        let webhook_id = props.webhook_id.clone();
+        use_effect_with(
+            (
+                props.webhook_id.clone(),
+                *format,
+                (*event_type).clone(),
+                *reload,
+            ),
+            move |(_, format, event_type, _)| {
+                let schema = schema.clone();
+                let error = error.clone();
+                let mut url = format!(
+                    "/api/webhooks/{}/schema?format={}",
+                    webhook_id,
+                    format.as_str()
+                );
+                if let Some(event_type) = event_type {
+                    url.push_str("&event_type=");
+                    url.push_str(&String::from(js_sys::encode_uri_component(event_type)));
+                }
+                wasm_bindgen_futures::spawn_local(async move {
+                    match Request::get(&url).send().await {
+                        Ok(resp) if resp.ok() => match resp.json::<InferredSchema>().await {
+                            Ok(fetched) => {
+                                schema.set(Some(fetched));
+                                error.set(None);
+                            }
+                            Err(err) => {
+                                web_sys::console::error_1(&err.to_string().into());
+                            }
+                        },
+                        Ok(resp) => {
+                            error.set(Some(format!("Request failed with {}", resp.status())));
+                        }
+                        Err(err) => {
+                            web_sys::console::error_1(&err.to_string().into());
+                        }
+                    }
+                });
+                || ()
+            },
+        );
+    }
+
+    {
+        let baselines = baselines.clone();
+        let webhook_id = props.webhook_id.clone();
+        use_effect_with((props.webhook_id.clone(), *reload), move |_| {
+            let baselines = baselines.clone();
+            let url = format!("/api/webhooks/{}/baselines", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(fetched) = resp.json::<Vec<Baseline>>().await {
+                            baselines.set(fetched);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_event_type = {
+        let event_type = event_type.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            event_type.set((!value.is_empty()).then_some(value));
+        })
+    };
+
+    let on_format = {
+        let format = format.clone();
+        move |selected: SchemaFormat| {
+            let format = format.clone();
+            Callback::from(move |_: MouseEvent| format.set(selected))
+        }
+    };
+
+    let on_refresh = {
+        let reload = reload.clone();
+        Callback::from(move |_: MouseEvent| reload.set(*reload + 1))
+    };
+
+    // Pins the selected event type's baseline from its captured payloads, or resets
+    // the given one so it is learned again; then fetches everything again.
+    let baseline_action = {
+        let reload = reload.clone();
+        let error = error.clone();
+        let webhook_id = props.webhook_id.clone();
+        move |event_type: Option<String>, pin: bool| {
+            let reload = reload.clone();
+            let error = error.clone();
+            let webhook_id = webhook_id.clone();
+            Callback::from(move |_: MouseEvent| {
+                let reload = reload.clone();
+                let error = error.clone();
+                let mut url = format!("/api/webhooks/{}/baselines", webhook_id);
+                let event_type = event_type.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let resp = if pin {
+                        let body = serde_json::json!({ "event_type": event_type });
+                        let req = match Request::put(&url).json(&body) {
+                            Ok(req) => req,
+                            Err(err) => {
+                                web_sys::console::error_1(&err.to_string().into());
+                                return;
+                            }
+                        };
+                        req.send().await
+                    } else {
+                        if let Some(event_type) = event_type.as_deref() {
+                            url.push_str("?event_type=");
+                            url.push_str(&String::from(js_sys::encode_uri_component(event_type)));
+                        }
+                        Request::delete(&url).send().await
+                    };
+
+                    match resp {
+                        Ok(resp) if resp.ok() => {
+                            error.set(None);
+                            reload.set(*reload + 1);
+                        }
+                        Ok(resp) => {
+                            let message = resp
+                                .json::<ErrorBody>()
+                                .await
+                                .map(|body| body.error)
+                                .unwrap_or_else(|_| {
+                                    format!("Request failed with {}", resp.status())
+                                });
+                            error.set(Some(message));
+                        }
+                        Err(err) => {
+                            web_sys::console::error_1(&err.to_string().into());
+                        }
+                    }
+                });
+            })
+        }
+    };
+
+    let on_copy = {
+        let schema = schema.clone();
+        let copied = copied.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(text) = schema.as_ref().map(|schema| schema.content.clone()) else {
+                return;
+            };
+            let copied = copied.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(window) = window() {
+                    let promise = window.navigator().clipboard().write_text(&text);
+                    if JsFuture::from(promise).await.is_ok() {
+                        copied.set(true);
+                        TimeoutFuture::new(2_000).await;
+                        copied.set(false);
+                    }
+                }
+            });
+        })
+    };
+
+    let event_types = schema
+        .as_ref()
+        .map(|schema| schema.event_types.clone())
+        .unwrap_or_default();
+
+    html! {
+        <div class="schema-panel">
+            <div class="delivery-toolbar">
+                <select class="form-input" onchange={on_event_type}>
+                    <option value="" selected={event_type.is_none()}>{ "All event types" }</option>
+                    { for event_types.iter().map(|name| html! {
+                        <option value={name.clone()} selected={event_type.as_deref() == Some(name.as_str())}>
+                            { name }
+                        </option>
+                    }) }
+                </select>
+                <div class="body-view-toggle">
+                    { for SchemaFormat::ALL.iter().map(|option| html! {
+                        <button
+                            type="button"
+                            class={classes!((*format == *option).then_some("active"))}
+                            onclick={on_format(*option)}
+                        >
+                            { option.label() }
+                        </button>
+                    }) }
+                </div>
+                <button type="button" class="btn btn-secondary btn-sm" onclick={on_refresh}>
+                    { "Refresh" }
+                </button>
+                <button type="button" class="btn btn-primary btn-sm" onclick={on_copy}>
+                    { if *copied { "Copied" } else { "Copy" } }
+                </button>
+            </div>
+            if let Some(message) = error.as_ref() {
+                <div class="settings-error">{ message }</div>
+            }
+            if let Some(schema) = schema.as_ref() {
+                if schema.samples == 0 {
+                    <div class="empty-state">
+                        <h3>{ "No JSON payloads yet" }</h3>
+                        <p>{ "Send some JSON to this webhook to infer its schema." }</p>
+                    </div>
+                } else {
+                    <p class="settings-hint">
+                        { format!(
+                            "Inferred from {} {}",
+                            schema.samples,
+                            if schema.samples == 1 { "payload" } else { "payloads" }
+                        ) }
+                    </p>
+                    <pre class="code-block">{ &schema.content }</pre>
+                }
+            }
+            <div class="section-title schema-baselines-title">{ "Drift baselines" }</div>
+            <p class="settings-hint">
+                { "Each event type's first JSON payloads are learned as its baseline. Later \
+                   payloads that add, drop or retype fields are flagged as drift." }
+            </p>
+            if schema.as_ref().is_some_and(|schema| schema.samples > 0) {
+                <div class="delivery-toolbar">
+                    <button
+                        type="button"
+                        class="btn btn-secondary btn-sm"
+                        onclick={baseline_action((*event_type).clone(), true)}
+                    >
+                        { match event_type.as_deref() {
+                            Some(name) => format!("Pin baseline for {}", name),
+                            None => "Pin baseline for requests without an event type".to_string(),
+                        } }
+                    </button>
+                </div>
+            }
+            <div class="key-value-list">
+                { for baselines.iter().map(|baseline| html! {
+                    <div class="key-value-item">
+                        <span class="key">
+                            { baseline.event_type.as_deref().unwrap_or("(no event type)") }
+                        </span>
+                        <span class="value">
+                            if baseline.pinned {
+                                <span class="tag-badge">{ "Pinned" }</span>
+                            } else if baseline.learning {
+                                <span class="status-badge status-warning">{ "Learning" }</span>
+                            } else {
+                                <span class="status-badge status-success">{ "Learned" }</span>
+                            }
+                            { format!(
+                                " {} {} · updated {} ",
+                                baseline.samples,
+                                if baseline.samples == 1 { "sample" } else { "samples" },
+                                baseline.updated_at
+                            ) }
+                            <button
+                                type="button"
+                                class="btn btn-danger btn-sm"
+                                onclick={baseline_action(baseline.event_type.clone(), false)}
+                            >
+                                { "Reset" }
+                            </button>
+                        </span>
+                    </div>
+                }) }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,295 @@
+use gloo_net::http::Request;
+use serde::Deserialize;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::components::response_editor::ResponseConfig;
+use crate::components::webhook_list::Webhook;
+use crate::components::webhook_request_details::WebhookRequest;
+
+/// Example shown in an empty editor.
+const SCRIPT_PLACEHOLDER: &str = r#"// `request` holds method, path, query, headers, body and json.
+if request.path == "/health" {
+    return #{ store: false, status: 204 };
+}
+#{ tags: [request.method], body: #{ ok: true } }"#;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ScriptOutcome {
+    pub response: Option<ResponseConfig>,
+    pub store: bool,
+    pub tags: Vec<String>,
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ScriptEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the Rhai script run on every captured request.
+#[component]
+pub fn ScriptEditor(ScriptEditorProps { webhook }: &ScriptEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.script.clone());
+    let source = use_state(|| webhook.script.clone().unwrap_or_default());
+    let error = use_state(|| None::<String>);
+    let outcome = use_state(|| None::<ScriptOutcome>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let source = source.clone();
+        let error = error.clone();
+        let outcome = outcome.clone();
+        let script = webhook.script.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            source.set(script.clone().unwrap_or_default());
+            saved.set(script);
+            error.set(None);
+            outcome.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_input = {
+        let source = source.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            source.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let source = source.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let script = (*source).clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/script", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&serde_json::json!({ "script": script })) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.script);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_remove = {
+        let source = source.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let outcome = outcome.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let source = source.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let outcome = outcome.clone();
+            let url = format!("/api/webhooks/{}/script", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        source.set(String::new());
+                        error.set(None);
+                        outcome.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    // Runs the script in the editor against the most recent captured request.
+    let on_test = {
+        let source = source.clone();
+        let error = error.clone();
+        let outcome = outcome.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let script = (*source).clone();
+            let error = error.clone();
+            let outcome = outcome.clone();
+            let webhook_id = webhook_id.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let latest = Request::get(&format!("/api/webhooks/{}/requests?size=1", webhook_id))
+                    .send()
+                    .await;
+                let request_id = match latest {
+                    Ok(resp) => resp
+                        .json::<Vec<WebhookRequest>>()
+                        .await
+                        .ok()
+                        .and_then(|requests| requests.into_iter().next())
+                        .map(|request| request.id),
+                    Err(_) => None,
+                };
+                let Some(request_id) = request_id else {
+                    error.set(Some("No captured request to test against yet".to_string()));
+                    return;
+                };
+
+                let url = format!("/api/webhooks/{}/script/test", webhook_id);
+                let req = match Request::post(&url).json(&serde_json::json!({
+                    "request_id": request_id,
+                    "script": script,
+                })) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        outcome.set(resp.json::<ScriptOutcome>().await.ok());
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = if saved.is_some() {
+        "Runs on every captured request"
+    } else {
+        "None"
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Script" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "A " }
+                        <a href="https://rhai.rs/book/" target="_blank" rel="noopener">{ "Rhai" }</a>
+                        { " script that may return a map with " }
+                        <code>{ "status" }</code>
+                        { ", " }
+                        <code>{ "headers" }</code>
+                        { ", " }
+                        <code>{ "body" }</code>
+                        { ", " }
+                        <code>{ "tags" }</code>
+                        { " and " }
+                        <code>{ "store" }</code>
+                        { ". A returned response replaces the configured one." }
+                    </p>
+                    <div class="form-group">
+                        <textarea
+                            class="form-input form-textarea"
+                            rows="10"
+                            placeholder={SCRIPT_PLACEHOLDER}
+                            value={(*source).clone()}
+                            oninput={on_input}
+                        />
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    if let Some(result) = outcome.as_ref() {
+                        <div class="script-outcome">
+                            <div class="section-title">{ "Test result" }</div>
+                            <pre class="code-block">
+                                {
+                                    serde_json::to_string_pretty(&serde_json::json!({
+                                        "store": result.store,
+                                        "tags": result.tags,
+                                        "response": result.response.as_ref().map(|r| serde_json::json!({
+                                            "status": r.status,
+                                            "content_type": r.content_type,
+                                            "headers": r.headers.iter().map(|h| format!("{}: {}", h.name, h.value)).collect::<Vec<_>>(),
+                                            "body": r.body,
+                                        })),
+                                        "error": result.error,
+                                    }))
+                                    .unwrap_or_default()
+                                }
+                            </pre>
+                            if !result.logs.is_empty() {
+                                <div class="section-title">{ "Logs" }</div>
+                                <pre class="code-block">{ result.logs.join("\n") }</pre>
+                            }
+                        </div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_remove}>
+                                { "Remove script" }
+                            </button>
+                        }
+                        <button type="button" class="btn btn-secondary btn-sm" onclick={on_test}>
+                            { "Test against latest request" }
+                        </button>
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save script" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,408 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::response_editor::{ResponseConfig, ResponseHeader};
+use crate::components::webhook_list::Webhook;
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceEnd {
+    #[default]
+    StickOnLast,
+    Loop,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseSequence {
+    pub steps: Vec<ResponseConfig>,
+    #[serde(default)]
+    pub after_last: SequenceEnd,
+}
+
+impl ResponseSequence {
+    /// Index of the step answering the `hit`-th request, as the server picks it.
+    pub fn step(&self, hit: u64) -> usize {
+        let last = self.steps.len().saturating_sub(1);
+        match self.after_last {
+            SequenceEnd::StickOnLast => (hit as usize).min(last),
+            SequenceEnd::Loop => (hit % self.steps.len().max(1) as u64) as usize,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SequencePosition {
+    pub hit: u64,
+    pub step: usize,
+}
+
+/// One step of the form, kept as the raw text the user typed.
+#[derive(Clone, PartialEq)]
+struct StepForm {
+    status: String,
+    content_type: String,
+    body: String,
+    /// Headers set through the API; the form has no field for them but keeps them
+    headers: Vec<ResponseHeader>,
+}
+
+impl StepForm {
+    fn blank() -> Self {
+        StepForm {
+            status: "200".to_string(),
+            content_type: String::new(),
+            body: String::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn from_config(config: &ResponseConfig) -> Self {
+        StepForm {
+            status: config.status.to_string(),
+            content_type: config.content_type.clone().unwrap_or_default(),
+            body: config.body.clone(),
+            headers: config.headers.clone(),
+        }
+    }
+
+    fn to_config(&self, index: usize) -> Result<ResponseConfig, String> {
+        let status = self.status.trim().parse::<u16>().map_err(|_| {
+            format!(
+                "Step {}: invalid status code \"{}\"",
+                index + 1,
+                self.status.trim()
+            )
+        })?;
+
+        Ok(ResponseConfig {
+            status,
+            content_type: Some(self.content_type.trim().to_string()).filter(|ct| !ct.is_empty()),
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+        })
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct SequenceForm {
+    steps: Vec<StepForm>,
+    after_last: SequenceEnd,
+}
+
+impl SequenceForm {
+    fn from_sequence(sequence: Option<&ResponseSequence>) -> Self {
+        match sequence {
+            Some(sequence) => SequenceForm {
+                steps: sequence.steps.iter().map(StepForm::from_config).collect(),
+                after_last: sequence.after_last,
+            },
+            None => SequenceForm {
+                steps: vec![StepForm::blank()],
+                after_last: SequenceEnd::default(),
+            },
+        }
+    }
+
+    fn to_sequence(&self) -> Result<ResponseSequence, String> {
+        Ok(ResponseSequence {
+            steps: self
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| step.to_config(index))
+                .collect::<Result<_, _>>()?,
+            after_last: self.after_last,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SequenceEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the series of replies a webhook steps through.
+#[component]
+pub fn SequenceEditor(SequenceEditorProps { webhook }: &SequenceEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.sequence.clone());
+    let cursor = use_state(|| webhook.sequence_cursor);
+    let form = use_state(|| SequenceForm::from_sequence(webhook.sequence.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let cursor = cursor.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let sequence = webhook.sequence.clone();
+        let sequence_cursor = webhook.sequence_cursor;
+        use_effect_with(webhook.id.clone(), move |_| {
+            form.set(SequenceForm::from_sequence(sequence.as_ref()));
+            saved.set(sequence);
+            cursor.set(sequence_cursor);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_step_field = |index: usize, set: fn(&mut StepForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = match e.target_dyn_into::<HtmlTextAreaElement>() {
+                Some(textarea) => textarea.value(),
+                None => e.target_unchecked_into::<HtmlInputElement>().value(),
+            };
+            let mut next = (*form).clone();
+            if let Some(step) = next.steps.get_mut(index) {
+                set(step, value);
+            }
+            form.set(next);
+        })
+    };
+
+    let on_remove_step = |index: usize| {
+        let form = form.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*form).clone();
+            next.steps.remove(index);
+            form.set(next);
+        })
+    };
+
+    let on_add_step = {
+        let form = form.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*form).clone();
+            next.steps.push(StepForm::blank());
+            form.set(next);
+        })
+    };
+
+    let on_after_last = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let after_last = match select.value().as_str() {
+                "loop" => SequenceEnd::Loop,
+                _ => SequenceEnd::StickOnLast,
+            };
+            form.set(SequenceForm {
+                after_last,
+                ..(*form).clone()
+            });
+        })
+    };
+
+    // Applies a webhook returned by one of the sequence endpoints.
+    let send = {
+        let saved = saved.clone();
+        let cursor = cursor.clone();
+        let error = error.clone();
+        move |req: Request| {
+            let saved = saved.clone();
+            let cursor = cursor.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.sequence);
+                            cursor.set(updated.sequence_cursor);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        }
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let error = error.clone();
+        let send = send.clone();
+        let url = format!("/api/webhooks/{}/sequence", webhook.id);
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let sequence = match form.to_sequence() {
+                Ok(sequence) => sequence,
+                Err(err) => {
+                    error.set(Some(err));
+                    return;
+                }
+            };
+
+            match Request::put(&url).json(&sequence) {
+                Ok(req) => send(req),
+                Err(err) => web_sys::console::error_1(&err.to_string().into()),
+            }
+        })
+    };
+
+    let on_reset = {
+        let send = send.clone();
+        let url = format!("/api/webhooks/{}/sequence/reset", webhook.id);
+        Callback::from(move |_: MouseEvent| match Request::post(&url).build() {
+            Ok(req) => send(req),
+            Err(err) => web_sys::console::error_1(&err.to_string().into()),
+        })
+    };
+
+    let on_remove = {
+        let form = form.clone();
+        let send = send.clone();
+        let url = format!("/api/webhooks/{}/sequence", webhook.id);
+        Callback::from(move |_: MouseEvent| {
+            form.set(SequenceForm::from_sequence(None));
+            match Request::delete(&url).build() {
+                Ok(req) => send(req),
+                Err(err) => web_sys::console::error_1(&err.to_string().into()),
+            }
+        })
+    };
+
+    let summary = match saved.as_ref() {
+        Some(sequence) => format!(
+            "Next · step {} of {}{}",
+            sequence.step(*cursor) + 1,
+            sequence.steps.len(),
+            match sequence.after_last {
+                SequenceEnd::Loop => " · loops",
+                SequenceEnd::StickOnLast => "",
+            }
+        ),
+        None => "None".to_string(),
+    };
+
+    let next_step = saved.as_ref().map(|sequence| sequence.step(*cursor));
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Sequence" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "Each captured request gets the next step, e.g. 500, 500, 503 and then \
+                           200. Steps take precedence over the response above and are templates \
+                           like it. Saving starts the sequence from the first step." }
+                    </p>
+                    { for form.steps.iter().enumerate().map(|(index, step)| html! {
+                        <div class={classes!("sequence-step", (next_step == Some(index)).then_some("next"))}>
+                            <div class="sequence-step-header">
+                                <span class="section-title">{ format!("Step {}", index + 1) }</span>
+                                if next_step == Some(index) {
+                                    <span class="tag-badge">{ "next" }</span>
+                                }
+                                if form.steps.len() > 1 {
+                                    <button
+                                        type="button"
+                                        class="btn btn-secondary btn-sm"
+                                        onclick={on_remove_step(index)}
+                                    >
+                                        { "Remove" }
+                                    </button>
+                                }
+                            </div>
+                            <div class="settings-row">
+                                <div class="form-group">
+                                    <label class="form-label">{ "Status" }</label>
+                                    <input
+                                        type="number"
+                                        class="form-input"
+                                        min="200"
+                                        max="599"
+                                        value={step.status.clone()}
+                                        oninput={on_step_field(index, |s, v| s.status = v)}
+                                    />
+                                </div>
+                                <div class="form-group">
+                                    <label class="form-label">{ "Content type" }</label>
+                                    <input
+                                        type="text"
+                                        class="form-input"
+                                        placeholder="e.g., application/json"
+                                        value={step.content_type.clone()}
+                                        oninput={on_step_field(index, |s, v| s.content_type = v)}
+                                    />
+                                </div>
+                            </div>
+                            <div class="form-group">
+                                <label class="form-label">{ "Body" }</label>
+                                <textarea
+                                    class="form-input form-textarea"
+                                    rows="2"
+                                    value={step.body.clone()}
+                                    oninput={on_step_field(index, |s, v| s.body = v)}
+                                />
+                            </div>
+                        </div>
+                    }) }
+                    <div class="settings-row">
+                        <div class="form-group">
+                            <label class="form-label">{ "After the last step" }</label>
+                            <select class="form-input" onchange={on_after_last}>
+                                <option
+                                    value="stick_on_last"
+                                    selected={form.after_last == SequenceEnd::StickOnLast}
+                                >
+                                    { "Keep sending it" }
+                                </option>
+                                <option value="loop" selected={form.after_last == SequenceEnd::Loop}>
+                                    { "Start over" }
+                                </option>
+                            </select>
+                        </div>
+                    </div>
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_remove}>
+                                { "Remove sequence" }
+                            </button>
+                            <button type="button" class="btn btn-secondary btn-sm" onclick={on_reset}>
+                                { "Reset to step 1" }
+                            </button>
+                        }
+                        <button type="button" class="btn btn-secondary btn-sm" onclick={on_add_step}>
+                            { "Add step" }
+                        </button>
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save sequence" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,355 @@
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::webhook_list::Webhook;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureProvider {
+    Github,
+    Stripe,
+    Slack,
+    Shopify,
+    Twilio,
+    HmacSha256,
+}
+
+impl SignatureProvider {
+    const ALL: [SignatureProvider; 6] = [
+        SignatureProvider::Github,
+        SignatureProvider::Stripe,
+        SignatureProvider::Slack,
+        SignatureProvider::Shopify,
+        SignatureProvider::Twilio,
+        SignatureProvider::HmacSha256,
+    ];
+
+    fn value(&self) -> &'static str {
+        match self {
+            SignatureProvider::Github => "github",
+            SignatureProvider::Stripe => "stripe",
+            SignatureProvider::Slack => "slack",
+            SignatureProvider::Shopify => "shopify",
+            SignatureProvider::Twilio => "twilio",
+            SignatureProvider::HmacSha256 => "hmac_sha256",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignatureProvider::Github => "GitHub",
+            SignatureProvider::Stripe => "Stripe",
+            SignatureProvider::Slack => "Slack",
+            SignatureProvider::Shopify => "Shopify",
+            SignatureProvider::Twilio => "Twilio",
+            SignatureProvider::HmacSha256 => "HMAC-SHA256",
+        }
+    }
+
+    fn from_value(value: &str) -> Self {
+        SignatureProvider::ALL
+            .into_iter()
+            .find(|provider| provider.value() == value)
+            .unwrap_or(SignatureProvider::Github)
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureConfig {
+    pub provider: SignatureProvider,
+    pub secret: String,
+    #[serde(default)]
+    pub header: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Verified,
+    Mismatched,
+    Missing,
+}
+
+impl SignatureStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignatureStatus::Verified => "Signature verified",
+            SignatureStatus::Mismatched => "Signature mismatch",
+            SignatureStatus::Missing => "Signature missing",
+        }
+    }
+
+    pub fn badge_class(&self) -> &'static str {
+        match self {
+            SignatureStatus::Verified => "status-success",
+            SignatureStatus::Mismatched => "status-error",
+            SignatureStatus::Missing => "status-warning",
+        }
+    }
+}
+
+/// Outcome of checking one captured request against the webhook's signing secret.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SignatureCheck {
+    pub provider: SignatureProvider,
+    pub status: SignatureStatus,
+    pub header: String,
+    pub received: Option<String>,
+    pub expected: Option<String>,
+    pub signed_payload: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// Form fields, kept as the raw text the user typed.
+#[derive(Clone, PartialEq)]
+struct SignatureForm {
+    provider: SignatureProvider,
+    secret: String,
+    header: String,
+}
+
+impl SignatureForm {
+    fn from_config(config: Option<&SignatureConfig>) -> Self {
+        match config {
+            Some(config) => SignatureForm {
+                provider: config.provider,
+                secret: config.secret.clone(),
+                header: config.header.clone().unwrap_or_default(),
+            },
+            None => SignatureForm {
+                provider: SignatureProvider::Github,
+                secret: String::new(),
+                header: String::new(),
+            },
+        }
+    }
+
+    fn to_config(&self) -> SignatureConfig {
+        let header = self.header.trim();
+        SignatureConfig {
+            provider: self.provider,
+            secret: self.secret.clone(),
+            header: (self.provider == SignatureProvider::HmacSha256 && !header.is_empty())
+                .then(|| header.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SignatureEditorProps {
+    pub webhook: Webhook,
+}
+
+/// Collapsible editor for the provider and secret captured requests are verified with.
+#[component]
+pub fn SignatureEditor(SignatureEditorProps { webhook }: &SignatureEditorProps) -> Html {
+    let expanded = use_state(|| false);
+    let saved = use_state(|| webhook.signature.clone());
+    let form = use_state(|| SignatureForm::from_config(webhook.signature.as_ref()));
+    let error = use_state(|| None::<String>);
+
+    // Start over whenever a different webhook is shown.
+    {
+        let saved = saved.clone();
+        let form = form.clone();
+        let error = error.clone();
+        let signature = webhook.signature.clone();
+        use_effect_with(webhook.id.clone(), move |_| {
+            form.set(SignatureForm::from_config(signature.as_ref()));
+            saved.set(signature);
+            error.set(None);
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let on_provider = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            form.set(SignatureForm {
+                provider: SignatureProvider::from_value(&select.value()),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_secret = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(SignatureForm {
+                secret: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_header = {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            form.set(SignatureForm {
+                header: input.value(),
+                ..(*form).clone()
+            });
+        })
+    };
+
+    let on_submit = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            let config = form.to_config();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/signature", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = match Request::put(&url).json(&config) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                        return;
+                    }
+                };
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(updated) = resp.json::<Webhook>().await {
+                            saved.set(updated.signature);
+                        }
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        let message = resp
+                            .json::<ErrorBody>()
+                            .await
+                            .map(|body| body.error)
+                            .unwrap_or_else(|_| format!("Request failed with {}", resp.status()));
+                        error.set(Some(message));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let on_stop = {
+        let form = form.clone();
+        let saved = saved.clone();
+        let error = error.clone();
+        let webhook_id = webhook.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let form = form.clone();
+            let saved = saved.clone();
+            let error = error.clone();
+            let url = format!("/api/webhooks/{}/signature", webhook_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        saved.set(None);
+                        form.set(SignatureForm::from_config(None));
+                        error.set(None);
+                    }
+                    Ok(resp) => {
+                        error.set(Some(format!("Request failed with {}", resp.status())));
+                    }
+                    Err(err) => {
+                        web_sys::console::error_1(&err.to_string().into());
+                    }
+                }
+            });
+        })
+    };
+
+    let summary = match saved.as_ref() {
+        Some(config) => format!("Verifying {} signatures", config.provider.label()),
+        None => "Off".to_string(),
+    };
+
+    html! {
+        <div class="settings-panel">
+            <button type="button" class="settings-panel-header" onclick={on_toggle}>
+                <span class="section-title">{ "Signature" }</span>
+                <span class="settings-panel-summary">{ summary }</span>
+                <span class="settings-panel-chevron">{ if *expanded { "▾" } else { "▸" } }</span>
+            </button>
+            if *expanded {
+                <form class="settings-panel-body" onsubmit={on_submit}>
+                    <p class="settings-hint">
+                        { "Every captured request is checked against the secret the way the provider \
+                           signs its webhooks, and marked verified, mismatched or missing. The request \
+                           details show the expected signature next to the received one. The caller's \
+                           reply is not affected." }
+                    </p>
+                    <div class="settings-row">
+                        <div class="form-group">
+                            <label class="form-label">{ "Provider" }</label>
+                            <select class="form-input" onchange={on_provider}>
+                                { for SignatureProvider::ALL.iter().map(|provider| html! {
+                                    <option value={provider.value()} selected={form.provider == *provider}>
+                                        { provider.label() }
+                                    </option>
+                                }) }
+                            </select>
+                        </div>
+                        <div class="form-group">
+                            <label class="form-label">
+                                { if form.provider == SignatureProvider::Twilio { "Auth token" } else { "Signing secret" } }
+                            </label>
+                            <input
+                                type="password"
+                                class="form-input"
+                                autocomplete="off"
+                                value={form.secret.clone()}
+                                oninput={on_secret}
+                            />
+                        </div>
+                    </div>
+                    if form.provider == SignatureProvider::HmacSha256 {
+                        <div class="form-group">
+                            <label class="form-label">{ "Signature header" }</label>
+                            <input
+                                type="text"
+                                class="form-input"
+                                placeholder="X-Signature"
+                                value={form.header.clone()}
+                                oninput={on_header}
+                            />
+                        </div>
+                    }
+                    if let Some(message) = error.as_ref() {
+                        <div class="settings-error">{ message }</div>
+                    }
+                    <div class="settings-actions">
+                        if saved.is_some() {
+                            <button type="button" class="btn btn-danger btn-sm" onclick={on_stop}>
+                                { "Stop verifying" }
+                            </button>
+                        }
+                        <button type="submit" class="btn btn-primary btn-sm">
+                            { "Save secret" }
+                        </button>
+                    </div>
+                </form>
+            }
+        </div>
+    }
+}
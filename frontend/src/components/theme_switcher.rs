@@ -55,14 +55,14 @@ const THEMES: &[ThemeOption] = &[
 fn apply_theme(theme_id: &str) {
     if let Some(win) = window() {
         // Set data-theme on <html>
-        if let Some(doc) = win.document() {
-            if let Some(root) = doc.document_element() {
-                // "gray" is the default — remove the attribute so :root rules apply
-                if theme_id == "gray" {
-                    let _ = root.remove_attribute("data-theme");
-                } else {
-                    let _ = root.set_attribute("data-theme", theme_id);
-                }
+        if let Some(doc) = win.document()
+            && let Some(root) = doc.document_element()
+        {
+            // "gray" is the default — remove the attribute so :root rules apply
+            if theme_id == "gray" {
+                let _ = root.remove_attribute("data-theme");
+            } else {
+                let _ = root.set_attribute("data-theme", theme_id);
             }
         }
         // Persist to localStorage
@@ -0,0 +1,106 @@
+use serde::Deserialize;
+use yew::prelude::*;
+
+use crate::components::webhook_request_details::{
+    BodyEncoding, RequestHeader, format_duration, highlight_text, status_class,
+};
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct UpstreamResponse {
+    pub url: String,
+    pub status: Option<u16>,
+    pub headers: Vec<RequestHeader>,
+    pub body: String,
+    #[serde(default)]
+    pub body_encoding: BodyEncoding,
+    pub latency_us: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct UpstreamDetailsProps {
+    pub upstream: UpstreamResponse,
+    /// Section heading, naming whoever answered
+    pub title: AttrValue,
+    pub search_query: String,
+}
+
+/// What a forward target, replay target or local target answered for a captured request.
+#[component]
+pub fn UpstreamDetails(props: &UpstreamDetailsProps) -> Html {
+    let upstream = &props.upstream;
+    let query = &props.search_query;
+
+    // Try to parse and pretty-print the body if it's JSON
+    let formatted_body = match upstream.body_encoding {
+        BodyEncoding::Utf8 => serde_json::from_str::<serde_json::Value>(&upstream.body)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+            .unwrap_or_else(|| upstream.body.clone()),
+        BodyEncoding::Base64 => upstream.body.clone(),
+    };
+
+    html! {
+        <>
+            <div class="request-section">
+                <div class="section-title">{ &props.title }</div>
+                <div class="key-value-list">
+                    <div class="key-value-item">
+                        <span class="key">
+                            {
+                                match upstream.status {
+                                    Some(status) => html! {
+                                        <span class={classes!("status-badge", status_class(status))}>
+                                            { status }
+                                        </span>
+                                    },
+                                    None => html! {
+                                        <span class={classes!("status-badge", "status-error")}>
+                                            { "failed" }
+                                        </span>
+                                    },
+                                }
+                            }
+                        </span>
+                        <span class="value">
+                            { highlight_text(&upstream.url, query) }
+                            { format!(" · {}", format_duration(upstream.latency_us)) }
+                        </span>
+                    </div>
+                </div>
+                if let Some(ref error) = upstream.error {
+                    <div class="settings-error">{ error }</div>
+                }
+            </div>
+            if !upstream.headers.is_empty() {
+                <div class="request-section">
+                    <div class="section-title">{ "Headers" }</div>
+                    <div class="key-value-list">
+                        { for upstream.headers.iter().map(|header| html! {
+                            <div class="key-value-item">
+                                <span class="key">{ highlight_text(&header.name, query) }</span>
+                                <span class="value">
+                                    if header.encoding == BodyEncoding::Base64 {
+                                        <span class="encoding-tag">{ "base64" }</span>
+                                    }
+                                    { highlight_text(&header.value, query) }
+                                </span>
+                            </div>
+                        }) }
+                    </div>
+                </div>
+            }
+            if upstream.status.is_some() {
+                <div class="request-section">
+                    <div class="section-title">
+                        { "Body" }
+                        if upstream.body_encoding == BodyEncoding::Base64 {
+                            <span class="encoding-tag">{ "base64" }</span>
+                        }
+                    </div>
+                    <pre class="code-block">{ highlight_text(&formatted_body, query) }</pre>
+                </div>
+            }
+        </>
+    }
+}
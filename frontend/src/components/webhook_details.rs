@@ -5,9 +5,19 @@ use web_sys::window;
 use yew::prelude::*;
 
 use crate::components::{
-    toast::Toast, webhook_list::Webhook, webhook_request_list::WebhookRequestList,
+    deliveries_panel::DeliveriesPanel, fanout_editor::FanOutEditor, fault_editor::FaultEditor,
+    forward_editor::ForwardEditor, json_schema_editor::JsonSchemaEditor,
+    response_editor::ResponseEditor, schema_panel::SchemaPanel, script_editor::ScriptEditor,
+    sequence_editor::SequenceEditor, signature_editor::SignatureEditor, toast::Toast,
+    webhook_list::Webhook, webhook_request_list::WebhookRequestList,
 };
 
+#[derive(Clone, Copy, PartialEq)]
+enum WebhookTab {
+    Requests,
+    Schema,
+}
+
 #[derive(Properties, PartialEq)]
 pub struct WebhookDetailsProps {
     pub webhook: Webhook,
@@ -19,7 +29,8 @@ pub fn WebhookDetails(WebhookDetailsProps { webhook }: &WebhookDetailsProps) ->
     let url_to_copy = url.clone();
 
     let show_toast = use_state(|| false);
-    let search_query = use_state(|| String::new());
+    let search_query = use_state(String::new);
+    let tab = use_state(|| WebhookTab::Requests);
     let input_ref = use_node_ref();
 
     // Reset the query and focus the input whenever the viewed webhook changes.
@@ -81,6 +92,13 @@ pub fn WebhookDetails(WebhookDetailsProps { webhook }: &WebhookDetailsProps) ->
         })
     };
 
+    let tab_class = |target: WebhookTab| classes!((*tab == target).then_some("active"));
+
+    let on_tab = |target: WebhookTab| {
+        let tab = tab.clone();
+        Callback::from(move |_: MouseEvent| tab.set(target))
+    };
+
     let has_query = !(*search_query).is_empty();
     let search_box_class = if has_query {
         "search-box has-value"
@@ -105,33 +123,64 @@ pub fn WebhookDetails(WebhookDetailsProps { webhook }: &WebhookDetailsProps) ->
                 </div>
             </div>
 
-            <div class="search-filter">
-                <div class={search_box_class}>
-                    <span class="search-icon">
-                        <svg width="14" height="14" viewBox="0 0 14 14" fill="none" xmlns="http://www.w3.org/2000/svg">
-                            <circle cx="6" cy="6" r="4.5" stroke="currentColor" stroke-width="1.5"/>
-                            <path d="M9.5 9.5L12.5 12.5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
-                        </svg>
-                    </span>
-                    <input
-                        ref={input_ref}
-                        class="search-input"
-                        type="text"
-                        placeholder="Search requests…"
-                        value={(*search_query).clone()}
-                        oninput={on_search}
-                    />
-                    if has_query {
-                        <button class="search-clear-btn" onclick={on_clear} type="button" aria-label="Clear search">
-                            <svg width="12" height="12" viewBox="0 0 12 12" fill="none" xmlns="http://www.w3.org/2000/svg">
-                                <path d="M1 1L11 11M11 1L1 11" stroke="currentColor" stroke-width="1.75" stroke-linecap="round"/>
+            <ResponseEditor webhook={webhook.clone()} />
+            <SequenceEditor webhook={webhook.clone()} />
+            <ForwardEditor webhook={webhook.clone()} />
+            <FanOutEditor webhook={webhook.clone()} />
+            <DeliveriesPanel webhook={webhook.clone()} />
+            <SignatureEditor webhook={webhook.clone()} />
+            <JsonSchemaEditor webhook={webhook.clone()} />
+            <ScriptEditor webhook={webhook.clone()} />
+            <FaultEditor webhook={webhook.clone()} />
+
+            <div class="exchange-tabs">
+                <button
+                    type="button"
+                    class={tab_class(WebhookTab::Requests)}
+                    onclick={on_tab(WebhookTab::Requests)}
+                >
+                    { "Requests" }
+                </button>
+                <button
+                    type="button"
+                    class={tab_class(WebhookTab::Schema)}
+                    onclick={on_tab(WebhookTab::Schema)}
+                >
+                    { "Schema" }
+                </button>
+            </div>
+
+            if *tab == WebhookTab::Schema {
+                <SchemaPanel webhook_id={webhook.id.clone()} />
+            } else {
+                <div class="search-filter">
+                    <div class={search_box_class}>
+                        <span class="search-icon">
+                            <svg width="14" height="14" viewBox="0 0 14 14" fill="none" xmlns="http://www.w3.org/2000/svg">
+                                <circle cx="6" cy="6" r="4.5" stroke="currentColor" stroke-width="1.5"/>
+                                <path d="M9.5 9.5L12.5 12.5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
                             </svg>
-                        </button>
-                    }
+                        </span>
+                        <input
+                            ref={input_ref}
+                            class="search-input"
+                            type="text"
+                            placeholder="Search requests…"
+                            value={(*search_query).clone()}
+                            oninput={on_search}
+                        />
+                        if has_query {
+                            <button class="search-clear-btn" onclick={on_clear} type="button" aria-label="Clear search">
+                                <svg width="12" height="12" viewBox="0 0 12 12" fill="none" xmlns="http://www.w3.org/2000/svg">
+                                    <path d="M1 1L11 11M11 1L1 11" stroke="currentColor" stroke-width="1.75" stroke-linecap="round"/>
+                                </svg>
+                            </button>
+                        }
+                    </div>
                 </div>
-            </div>
 
-            <WebhookRequestList webhook_id={webhook.id.clone()} search_query={(*search_query).clone()} />
+                <WebhookRequestList webhook_id={webhook.id.clone()} search_query={(*search_query).clone()} />
+            }
 
             <Toast message="Copied to clipboard!" visible={*show_toast} />
         </>
@@ -4,6 +4,12 @@ use serde::Deserialize;
 use yew::prelude::*;
 use yew_router::prelude::Link;
 
+use crate::components::fanout_editor::FanOutConfig;
+use crate::components::fault_editor::FaultProfile;
+use crate::components::forward_editor::ForwardTarget;
+use crate::components::response_editor::ResponseConfig;
+use crate::components::sequence_editor::ResponseSequence;
+use crate::components::signature_editor::SignatureConfig;
 use crate::hooks::use_clock_tick;
 use crate::routes::Route;
 
@@ -14,6 +20,24 @@ pub struct Webhook {
     pub url: String,
     pub created_at: String,
     pub has_unread: bool,
+    #[serde(default)]
+    pub response: Option<ResponseConfig>,
+    #[serde(default)]
+    pub script: Option<String>,
+    #[serde(default)]
+    pub fault_profile: Option<FaultProfile>,
+    #[serde(default)]
+    pub sequence: Option<ResponseSequence>,
+    #[serde(default)]
+    pub sequence_cursor: u64,
+    #[serde(default)]
+    pub forward: Option<ForwardTarget>,
+    #[serde(default)]
+    pub fanout: Option<FanOutConfig>,
+    #[serde(default)]
+    pub signature: Option<SignatureConfig>,
+    #[serde(default)]
+    pub json_schema: Option<serde_json::Value>,
 }
 
 #[derive(Properties, PartialEq)]
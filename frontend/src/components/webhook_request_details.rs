@@ -1,34 +1,210 @@
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
 use chrono::TimeZone;
 use chrono_humanize::HumanTime;
 use serde::Deserialize;
-use std::collections::HashMap;
 use yew::prelude::*;
 
 use crate::hooks::use_clock_tick;
 
+use crate::components::fault_editor::InjectedFault;
+use crate::components::json_schema_editor::SchemaValidation;
+use crate::components::replay_panel::ReplayPanel;
+use crate::components::request_body::RequestBody;
+use crate::components::response_details::ResponseDetails;
+use crate::components::response_editor::ResponseHeader;
+use crate::components::schema_panel::ShapeChange;
+use crate::components::sequence_editor::SequencePosition;
+use crate::components::signature_editor::SignatureCheck;
 use crate::components::tooltip::Tooltip;
+use crate::components::upstream_details::{UpstreamDetails, UpstreamResponse};
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct QueryParam {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyEncoding {
+    #[default]
+    Utf8,
+    Base64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct RequestHeader {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub encoding: BodyEncoding,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct WebhookRequestPart {
+    pub id: String,
+    pub position: u64,
+    pub name: Option<String>,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub headers: String,
+    pub size: u64,
+    pub text: Option<String>,
+    pub download_url: String,
+}
+
+/// Context attributes of a CloudEvent carried by the request.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct CloudEvent {
+    pub position: u64,
+    /// `binary`, `structured` or `batch`
+    pub mode: String,
+    pub specversion: Option<String>,
+    pub id: Option<String>,
+    pub source: Option<String>,
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub subject: Option<String>,
+    pub time: Option<String>,
+    pub datacontenttype: Option<String>,
+    pub dataschema: Option<String>,
+    #[serde(default)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+    pub data: Option<String>,
+}
+
+impl CloudEvent {
+    /// Spec attributes that were sent, in the order the spec lists them.
+    fn attributes(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("id", &self.id),
+            ("source", &self.source),
+            ("type", &self.event_type),
+            ("subject", &self.subject),
+            ("time", &self.time),
+            ("datacontenttype", &self.datacontenttype),
+            ("dataschema", &self.dataschema),
+            ("specversion", &self.specversion),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_deref().map(|value| (name, value)))
+        .collect()
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SentResponse {
+    pub status: u16,
+    pub headers: Vec<ResponseHeader>,
+    pub body: String,
+    pub error: Option<String>,
+}
 
 #[derive(Clone, PartialEq, Deserialize)]
 pub struct WebhookRequest {
     pub id: String,
     pub webhook_id: String,
     pub method: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub query_params: Vec<QueryParam>,
     pub headers: String,
     pub body: String,
+    #[serde(default)]
+    pub body_encoding: BodyEncoding,
     pub received_at: String,
     pub caller_ip: Option<String>,
     pub duration_us: Option<u64>,
+    #[serde(default)]
+    pub parts: Vec<WebhookRequestPart>,
+    #[serde(default)]
+    pub content_encoding: Option<String>,
+    #[serde(default)]
+    pub raw_body: Option<String>,
+    #[serde(default)]
+    pub body_format: Option<String>,
+    #[serde(default)]
+    pub parsed_body: Option<serde_json::Value>,
+    #[serde(default)]
+    pub response: Option<SentResponse>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub script_error: Option<String>,
+    #[serde(default)]
+    pub fault: Option<InjectedFault>,
+    #[serde(default)]
+    pub sequence: Option<SequencePosition>,
+    #[serde(default)]
+    pub upstream: Option<UpstreamResponse>,
+    #[serde(default)]
+    pub local_response: Option<UpstreamResponse>,
+    #[serde(default)]
+    pub signature: Option<SignatureCheck>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub event_type: Option<String>,
+    #[serde(default)]
+    pub cloud_events: Vec<CloudEvent>,
+    #[serde(default)]
+    pub schema_validation: Option<SchemaValidation>,
+    #[serde(default)]
+    pub drift: Vec<ShapeChange>,
+}
+
+/// What the request list is narrowed to.
+#[derive(Clone, PartialEq)]
+pub enum RequestFilter {
+    /// Sender and, optionally, event
+    Event {
+        provider: String,
+        event_type: Option<String>,
+    },
+    /// Requests whose JSON body broke the webhook's JSON Schema
+    SchemaInvalid,
+    /// Requests whose JSON body drifted from its baseline shape
+    Drifted,
+}
+
+impl RequestFilter {
+    pub fn label(&self) -> String {
+        match self {
+            RequestFilter::Event {
+                provider,
+                event_type: Some(event_type),
+            } => format!("{} · {}", provider, event_type),
+            RequestFilter::Event { provider, .. } => provider.clone(),
+            RequestFilter::SchemaInvalid => "schema invalid".to_string(),
+            RequestFilter::Drifted => "drifted".to_string(),
+        }
+    }
+}
+
+/// Which side of the exchange a request card shows.
+#[derive(Clone, Copy, PartialEq)]
+enum ExchangeTab {
+    Request,
+    Response,
+    Upstream,
+    Local,
+    Replay,
 }
 
 #[derive(Properties, PartialEq)]
 pub struct WebhookRequestProps {
     pub request: WebhookRequest,
     pub search_query: String,
+    /// Called when the event, schema or drift badge is clicked, to narrow the list to matching requests
+    pub on_filter: Callback<RequestFilter>,
 }
 
 /// Splits `text` around case-insensitive occurrences of `query` and returns
 /// an `Html` fragment where each match is wrapped in `<mark class="search-highlight">`.
-fn highlight_text(text: &str, query: &str) -> Html {
+pub(crate) fn highlight_text(text: &str, query: &str) -> Html {
     if query.is_empty() {
         return html! { {text} };
     }
@@ -84,32 +260,73 @@ fn highlight_text(text: &str, query: &str) -> Html {
     html! { <>{ for parts.into_iter() }</> }
 }
 
+/// Formats a byte count as B / KB / MB.
+fn format_size(size: usize) -> String {
+    if size < 1_024 {
+        format!("{} B", size)
+    } else if size < 1_048_576 {
+        format!("{:.2} KB", size as f64 / 1_024.0)
+    } else {
+        format!("{:.2} MB", size as f64 / 1_048_576.0)
+    }
+}
+
+/// Formats a duration in microseconds as µs / ms / s.
+pub(crate) fn format_duration(duration: u64) -> String {
+    if duration < 1_000 {
+        format!("{} µs", duration)
+    } else if duration < 1_000_000 {
+        format!("{:.2} ms", duration as f64 / 1_000.0)
+    } else {
+        format!("{:.2} s", duration as f64 / 1_000_000.0)
+    }
+}
+
+/// Maps a response status code to the badge class that colours it.
+pub(crate) fn status_class(status: u16) -> &'static str {
+    match status {
+        200..=299 => "status-success",
+        300..=499 => "status-warning",
+        _ => "status-error",
+    }
+}
+
+/// Maps an HTTP method to the badge class that colours it.
+fn method_class(method: &str) -> &'static str {
+    match method.to_uppercase().as_str() {
+        "GET" => "method-get",
+        "POST" => "method-post",
+        "PUT" => "method-put",
+        "PATCH" => "method-patch",
+        "DELETE" => "method-delete",
+        "HEAD" => "method-head",
+        "OPTIONS" => "method-options",
+        _ => "method-other",
+    }
+}
+
 #[component]
 pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
     let expanded = use_state(|| false);
+    let tab = use_state(|| ExchangeTab::Request);
     // Tick every 30 s so relative timestamps ("7 minutes ago") stay
     // fresh without making any HTTP requests.
     let _tick = use_clock_tick(30_000);
 
-    let headers: Result<HashMap<String, String>, _> = serde_json::from_str(&props.request.headers);
+    let headers: Result<Vec<RequestHeader>, _> = serde_json::from_str(&props.request.headers);
 
-    // Try to parse and pretty-print the body if it's JSON
-    let formatted_body =
-        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&props.request.body) {
-            serde_json::to_string_pretty(&json_value).unwrap_or_else(|_| props.request.body.clone())
-        } else {
-            props.request.body.clone()
-        };
-
-    let body_size = props.request.body.len();
-    let size_label = if body_size < 1_024 {
-        format!("{} B", body_size)
-    } else if body_size < 1_048_576 {
-        format!("{:.2} KB", body_size as f64 / 1_024.0)
-    } else {
-        format!("{:.2} MB", body_size as f64 / 1_048_576.0)
+    // Binary bodies arrive base64 encoded; decode them for sizing and the hex dump.
+    let binary_body = match props.request.body_encoding {
+        BodyEncoding::Base64 => Some(BASE64.decode(&props.request.body).unwrap_or_default()),
+        BodyEncoding::Utf8 => None,
     };
 
+    let body_size = binary_body
+        .as_ref()
+        .map(|bytes| bytes.len())
+        .unwrap_or(props.request.body.len());
+    let size_label = format_size(body_size);
+
     let expanded_class = if *expanded { "expanded" } else { "" };
 
     let onclick = {
@@ -117,8 +334,52 @@ pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
         Callback::from(move |_| expanded.set(!*expanded))
     };
 
+    let on_tab = |target: ExchangeTab| {
+        let tab = tab.clone();
+        Callback::from(move |_: MouseEvent| tab.set(target))
+    };
+
+    let tab_class = |target: ExchangeTab| classes!((*tab == target).then_some("active"));
+
     let query = props.search_query.clone();
 
+    let event_filter = props
+        .request
+        .provider
+        .as_ref()
+        .map(|provider| RequestFilter::Event {
+            provider: provider.clone(),
+            event_type: props.request.event_type.clone(),
+        });
+
+    let on_event_badge = {
+        let on_filter = props.on_filter.clone();
+        let event_filter = event_filter.clone();
+        Callback::from(move |e: MouseEvent| {
+            // Filtering should not also fold the card open or shut
+            e.stop_propagation();
+            if let Some(filter) = event_filter.clone() {
+                on_filter.emit(filter);
+            }
+        })
+    };
+
+    let on_schema_badge = {
+        let on_filter = props.on_filter.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            on_filter.emit(RequestFilter::SchemaInvalid);
+        })
+    };
+
+    let on_drift_badge = {
+        let on_filter = props.on_filter.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            on_filter.emit(RequestFilter::Drifted);
+        })
+    };
+
     html! {
         <div
             class={format!("request-card {} {}", expanded_class, props.request.id.clone())}
@@ -126,22 +387,15 @@ pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
 
             <div class="request-header">
                 <div class="request-meta" {onclick}>
-                    <span class="method-badge method-post">
+                    <span class={classes!("method-badge", method_class(&props.request.method))}>
                         { highlight_text(&props.request.method, &query) }
                     </span>
                     {
                         if let Some(duration) = props.request.duration_us {
-                            let label = if duration < 1_000 {
-                                format!("{} µs", duration)
-                            } else if duration < 1_000_000 {
-                                format!("{:.2} ms", duration as f64 / 1_000.0)
-                            } else {
-                                format!("{:.2} s", duration as f64 / 1_000_000.0)
-                            };
                             html! {
                                 <Tooltip text="Time taken to process this request">
                                     <span class="duration-badge">
-                                        { label }
+                                        { format_duration(duration) }
                                     </span>
                                 </Tooltip>
                             }
@@ -149,6 +403,60 @@ pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
                             html! {}
                         }
                     }
+                    if let Some(ref response) = props.request.response {
+                        <Tooltip text="Status HookSpy replied with">
+                            <span class={classes!("status-badge", status_class(response.status))}>
+                                { response.status }
+                            </span>
+                        </Tooltip>
+                    }
+                    if let Some(ref position) = props.request.sequence {
+                        <Tooltip text={format!("Hit {} of the response sequence", position.hit + 1)}>
+                            <span class="tag-badge">{ format!("Step {}", position.step + 1) }</span>
+                        </Tooltip>
+                    }
+                    if let Some(ref filter) = event_filter {
+                        <Tooltip text="Show only requests for this event">
+                            <button type="button" class="event-badge" onclick={on_event_badge}>
+                                { highlight_text(&filter.label(), &query) }
+                            </button>
+                        </Tooltip>
+                    }
+                    if let Some(ref validation) = props.request.schema_validation {
+                        if validation.valid {
+                            <Tooltip text="Body matches the webhook's JSON Schema">
+                                <span class="status-badge status-success">{ "Schema valid" }</span>
+                            </Tooltip>
+                        } else {
+                            <Tooltip text="Show only requests failing the JSON Schema">
+                                <button type="button" class="status-badge status-error schema-badge" onclick={on_schema_badge}>
+                                    { format!("Schema invalid · {}", validation.errors.len()) }
+                                </button>
+                            </Tooltip>
+                        }
+                    }
+                    if !props.request.drift.is_empty() {
+                        <Tooltip text="Show only requests that drifted from their baseline shape">
+                            <button type="button" class="status-badge status-warning schema-badge" onclick={on_drift_badge}>
+                                { format!("Drift · {}", props.request.drift.len()) }
+                            </button>
+                        </Tooltip>
+                    }
+                    if let Some(ref check) = props.request.signature {
+                        <Tooltip text={format!("{} signature check", check.provider.label())}>
+                            <span class={classes!("status-badge", check.status.badge_class())}>
+                                { check.status.label() }
+                            </span>
+                        </Tooltip>
+                    }
+                    if let Some(ref fault) = props.request.fault {
+                        <Tooltip text="Fault injected by the webhook's fault profile">
+                            <span class="fault-badge">{ fault.label() }</span>
+                        </Tooltip>
+                    }
+                    { for props.request.tags.iter().map(|tag| html! {
+                        <span class="tag-badge">{ highlight_text(tag, &query) }</span>
+                    }) }
                     <Tooltip text="Payload size of the request body">
                         <span class="size-badge">
                             { size_label.clone() }
@@ -178,6 +486,160 @@ pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
                 <span class="expand-icon">{ "▼" }</span>
             </div>
             <div class="request-body">
+                <div class="exchange-tabs">
+                    <button
+                        type="button"
+                        class={tab_class(ExchangeTab::Request)}
+                        onclick={on_tab(ExchangeTab::Request)}
+                    >
+                        { "Request" }
+                    </button>
+                    if let Some(ref response) = props.request.response {
+                        <button
+                            type="button"
+                            class={tab_class(ExchangeTab::Response)}
+                            onclick={on_tab(ExchangeTab::Response)}
+                        >
+                            { format!("Response · {}", response.status) }
+                        </button>
+                    }
+                    if let Some(ref upstream) = props.request.upstream {
+                        <button
+                            type="button"
+                            class={tab_class(ExchangeTab::Upstream)}
+                            onclick={on_tab(ExchangeTab::Upstream)}
+                        >
+                            {
+                                match upstream.status {
+                                    Some(status) => format!("Upstream · {}", status),
+                                    None => "Upstream · failed".to_string(),
+                                }
+                            }
+                        </button>
+                    }
+                    if let Some(ref local) = props.request.local_response {
+                        <button
+                            type="button"
+                            class={tab_class(ExchangeTab::Local)}
+                            onclick={on_tab(ExchangeTab::Local)}
+                        >
+                            {
+                                match local.status {
+                                    Some(status) => format!("Local · {}", status),
+                                    None => "Local · failed".to_string(),
+                                }
+                            }
+                        </button>
+                    }
+                    <button
+                        type="button"
+                        class={tab_class(ExchangeTab::Replay)}
+                        onclick={on_tab(ExchangeTab::Replay)}
+                    >
+                        { "Replay" }
+                    </button>
+                </div>
+                if let (ExchangeTab::Response, Some(response)) = (*tab, props.request.response.as_ref()) {
+                    <ResponseDetails
+                        response={response.clone()}
+                        duration_us={props.request.duration_us}
+                        search_query={query.clone()}
+                    />
+                } else if let (ExchangeTab::Upstream, Some(upstream)) = (*tab, props.request.upstream.as_ref()) {
+                    <UpstreamDetails upstream={upstream.clone()} title="Upstream" search_query={query.clone()} />
+                } else if let (ExchangeTab::Local, Some(local)) = (*tab, props.request.local_response.as_ref()) {
+                    <UpstreamDetails upstream={local.clone()} title="Local target" search_query={query.clone()} />
+                } else if *tab == ExchangeTab::Replay {
+                    <ReplayPanel
+                        webhook_id={props.request.webhook_id.clone()}
+                        request_id={props.request.id.clone()}
+                        body={
+                            match props.request.body_encoding {
+                                BodyEncoding::Utf8 => props.request.body.clone(),
+                                BodyEncoding::Base64 => String::new(),
+                            }
+                        }
+                        search_query={query.clone()}
+                    />
+                } else {
+                if let Some(ref error) = props.request.script_error {
+                    <div class="request-section">
+                        <div class="section-title">{ "Script Error" }</div>
+                        <div class="settings-error">{ error }</div>
+                    </div>
+                }
+                if let Some(validation) = props.request.schema_validation.as_ref().filter(|v| !v.valid) {
+                    <div class="request-section">
+                        <div class="section-title">{ "JSON Schema" }</div>
+                        <div class="key-value-list">
+                            { for validation.errors.iter().map(|violation| html! {
+                                <div class="key-value-item">
+                                    <span class="key schema-error-path">
+                                        { if violation.path.is_empty() { "(body)" } else { violation.path.as_str() } }
+                                    </span>
+                                    <span class="value">
+                                        { highlight_text(&violation.message, &query) }
+                                        <code class="schema-keyword">{ &violation.schema_path }</code>
+                                    </span>
+                                </div>
+                            }) }
+                        </div>
+                    </div>
+                }
+                if !props.request.drift.is_empty() {
+                    <div class="request-section">
+                        <div class="section-title">{ "Shape Drift" }</div>
+                        <div class="key-value-list">
+                            { for props.request.drift.iter().map(|change| html! {
+                                <div class="key-value-item">
+                                    <span class="key schema-error-path">
+                                        { if change.path.is_empty() { "(body)" } else { change.path.as_str() } }
+                                    </span>
+                                    <span class="value">{ change.describe() }</span>
+                                </div>
+                            }) }
+                        </div>
+                    </div>
+                }
+                if let Some(ref check) = props.request.signature {
+                    <div class="request-section">
+                        <div class="section-title">{ format!("{} Signature", check.provider.label()) }</div>
+                        <div class="key-value-list">
+                            <div class="key-value-item">
+                                <span class="key">{ "Status" }</span>
+                                <span class="value">
+                                    <span class={classes!("status-badge", check.status.badge_class())}>
+                                        { check.status.label() }
+                                    </span>
+                                </span>
+                            </div>
+                            <div class="key-value-item">
+                                <span class="key">{ "Received" }</span>
+                                <span class="value signature-value">
+                                    {
+                                        match check.received.as_ref() {
+                                            Some(received) => html! { highlight_text(received, &query) },
+                                            None => html! { format!("no {} header", check.header) },
+                                        }
+                                    }
+                                </span>
+                            </div>
+                            if let Some(ref expected) = check.expected {
+                                <div class="key-value-item">
+                                    <span class="key">{ "Expected" }</span>
+                                    <span class="value signature-value">{ expected }</span>
+                                </div>
+                            }
+                        </div>
+                        if let Some(ref detail) = check.detail {
+                            <div class="settings-error">{ detail }</div>
+                        }
+                        if let Some(ref payload) = check.signed_payload {
+                            <div class="form-label">{ "Signed payload" }</div>
+                            <pre class="code-block">{ payload }</pre>
+                        }
+                    </div>
+                }
                 {
                     if let Some(ref ip) = props.request.caller_ip {
                         html! {
@@ -194,23 +656,59 @@ pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
                         html! {}
                     }
                 }
+                {
+                    if props.request.path.is_some() || props.request.query.is_some() {
+                        let target = format!(
+                            "{}{}",
+                            props.request.path.as_deref().unwrap_or("/"),
+                            props.request.query.as_deref().map(|q| format!("?{}", q)).unwrap_or_default(),
+                        );
+                        html! {
+                            <div class="request-section">
+                                <div class="section-title">{ "Path" }</div>
+                                <div class="key-value-list">
+                                    <div class="key-value-item">
+                                        <span class="value">{ highlight_text(&target, &query) }</span>
+                                    </div>
+                                </div>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                if !props.request.query_params.is_empty() {
+                    <div class="request-section">
+                        <div class="section-title">{ "Query Parameters" }</div>
+                        <div class="key-value-list">
+                            { for props.request.query_params.iter().map(|param| html! {
+                                <div class="key-value-item">
+                                    <span class="key">{ highlight_text(&param.name, &query) }</span>
+                                    <span class="value">{ highlight_text(&param.value, &query) }</span>
+                                </div>
+                            }) }
+                        </div>
+                    </div>
+                }
                 <div class="request-section">
                     <div class="section-title">{ "Headers" }</div>
                     <div class="key-value-list">
                         {
                             match headers {
-                                Ok(ref map) => {
-                                    let mut items: Vec<_> = map.iter().collect();
-                                    items.sort_by_key(|(key, _)| key.to_lowercase());
-                                    items.into_iter().map(|(key, value)| {
-                                        html! {
-                                            <div class="key-value-item">
-                                                <span class="key">{ highlight_text(key, &query) }</span>
-                                                <span class="value">{ highlight_text(value, &query) }</span>
-                                            </div>
-                                        }
-                                    }).collect::<Html>()
-                                },
+                                // Rendered in the order they were received; repeated names stay separate.
+                                Ok(ref list) => list.iter().map(|header| {
+                                    html! {
+                                        <div class="key-value-item">
+                                            <span class="key">{ highlight_text(&header.name, &query) }</span>
+                                            <span class="value">
+                                                if header.encoding == BodyEncoding::Base64 {
+                                                    <span class="encoding-tag">{ "base64" }</span>
+                                                }
+                                                { highlight_text(&header.value, &query) }
+                                            </span>
+                                        </div>
+                                    }
+                                }).collect::<Html>(),
                                 Err(_) => html! {
                                     <div class="key-value-item">
                                         <span class="key">{ "Invalid headers" }</span>
@@ -220,12 +718,99 @@ pub fn WebhookRequestDetails(props: &WebhookRequestProps) -> Html {
                         }
                     </div>
                 </div>
-                <div class="request-section">
-                    <div class="section-title">{ "Body" }</div>
-                    <pre class="code-block">
-                        { highlight_text(&formatted_body, &query) }
-                    </pre>
-                </div>
+                if let Some(first) = props.request.cloud_events.first() {
+                    <div class="request-section">
+                        <div class="section-title">
+                            {
+                                if first.mode == "batch" {
+                                    format!("CloudEvents · batch of {}", props.request.cloud_events.len())
+                                } else {
+                                    format!("CloudEvent · {} mode", first.mode)
+                                }
+                            }
+                        </div>
+                        { for props.request.cloud_events.iter().map(|event| html! {
+                            <div class="cloud-event" key={event.position}>
+                                if first.mode == "batch" {
+                                    <div class="form-label">{ format!("Event {}", event.position + 1) }</div>
+                                }
+                                <div class="key-value-list">
+                                    { for event.attributes().into_iter().map(|(name, value)| html! {
+                                        <div class="key-value-item">
+                                            <span class="key">{ name }</span>
+                                            <span class="value">{ highlight_text(value, &query) }</span>
+                                        </div>
+                                    }) }
+                                    { for event.extensions.iter().map(|(name, value)| html! {
+                                        <div class="key-value-item">
+                                            <span class="key">
+                                                <span class="encoding-tag">{ "ext" }</span>
+                                                { name }
+                                            </span>
+                                            <span class="value">
+                                                {
+                                                    match value {
+                                                        serde_json::Value::String(text) => highlight_text(text, &query),
+                                                        other => highlight_text(&other.to_string(), &query),
+                                                    }
+                                                }
+                                            </span>
+                                        </div>
+                                    }) }
+                                </div>
+                                if let Some(ref data) = event.data {
+                                    <div class="form-label">{ "Data" }</div>
+                                    <pre class="code-block">{ highlight_text(data, &query) }</pre>
+                                }
+                            </div>
+                        }) }
+                    </div>
+                }
+                if !props.request.parts.is_empty() {
+                    <div class="request-section">
+                        <div class="section-title">{ "Multipart Parts" }</div>
+                        <table class="parts-table">
+                            <thead>
+                                <tr>
+                                    <th>{ "Name" }</th>
+                                    <th>{ "Filename" }</th>
+                                    <th>{ "Content Type" }</th>
+                                    <th>{ "Size" }</th>
+                                    <th>{ "Value" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for props.request.parts.iter().map(|part| html! {
+                                    <tr key={part.id.clone()}>
+                                        <td>{ highlight_text(part.name.as_deref().unwrap_or("—"), &query) }</td>
+                                        <td>{ highlight_text(part.filename.as_deref().unwrap_or("—"), &query) }</td>
+                                        <td>{ part.content_type.clone().unwrap_or_else(|| "—".to_string()) }</td>
+                                        <td>{ format_size(part.size as usize) }</td>
+                                        <td>
+                                            {
+                                                if let Some(ref text) = part.text {
+                                                    html! { <span class="part-text">{ highlight_text(text, &query) }</span> }
+                                                } else {
+                                                    html! {
+                                                        <a
+                                                            class="btn btn-primary btn-sm"
+                                                            href={part.download_url.clone()}
+                                                            download={part.filename.clone().unwrap_or_else(|| format!("part-{}", part.position))}
+                                                        >
+                                                            { "Download" }
+                                                        </a>
+                                                    }
+                                                }
+                                            }
+                                        </td>
+                                    </tr>
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                }
+                <RequestBody request={props.request.clone()} search_query={query.clone()} />
+                }
             </div>
         </div>
     }
@@ -5,10 +5,11 @@ use web_sys::window;
 use yew::prelude::*;
 
 use futures_util::StreamExt;
-use gloo_net::websocket::{Message, futures::WebSocket};
 
+use crate::components::webhook_request_details::RequestFilter;
 use crate::components::webhook_request_details::WebhookRequest;
 use crate::components::webhook_request_details::WebhookRequestDetails;
+use crate::notifications::notifications;
 
 #[derive(Properties, PartialEq)]
 pub struct WebhookRequestListProps {
@@ -16,16 +17,26 @@ pub struct WebhookRequestListProps {
     pub search_query: String,
 }
 
-fn websocket_url(path: String) -> String {
-    let window = window().expect("no window");
-    let location = window.location();
-
-    let protocol = location.protocol().unwrap(); // "http:" or "https:"
-    let host = location.host().unwrap(); // "example.com:443"
-
-    let ws_protocol = if protocol == "https:" { "wss" } else { "ws" };
-
-    format!("{ws_protocol}://{host}{path}")
+/// Request list URL, narrowed by `filter`.
+fn requests_url(webhook_id: &str, filter: Option<&RequestFilter>) -> String {
+    let mut url = format!("/api/webhooks/{}/requests", webhook_id);
+    match filter {
+        Some(RequestFilter::Event {
+            provider,
+            event_type,
+        }) => {
+            url.push_str("?provider=");
+            url.push_str(&String::from(js_sys::encode_uri_component(provider)));
+            if let Some(event_type) = event_type {
+                url.push_str("&event_type=");
+                url.push_str(&String::from(js_sys::encode_uri_component(event_type)));
+            }
+        }
+        Some(RequestFilter::SchemaInvalid) => url.push_str("?schema_valid=false"),
+        Some(RequestFilter::Drifted) => url.push_str("?drifted=true"),
+        None => {}
+    }
+    url
 }
 
 fn request_matches(request: &WebhookRequest, query: &str) -> bool {
@@ -34,6 +45,15 @@ fn request_matches(request: &WebhookRequest, query: &str) -> bool {
     }
     let q = query.to_lowercase();
     request.method.to_lowercase().contains(q.as_str())
+        || request
+            .path
+            .as_deref()
+            .map(|path| path.to_lowercase().contains(q.as_str()))
+            .unwrap_or(false)
+        || request.query_params.iter().any(|p| {
+            p.name.to_lowercase().contains(q.as_str())
+                || p.value.to_lowercase().contains(q.as_str())
+        })
         || request.body.to_lowercase().contains(q.as_str())
         || request.headers.to_lowercase().contains(q.as_str())
         || request
@@ -45,17 +65,23 @@ fn request_matches(request: &WebhookRequest, query: &str) -> bool {
 
 #[component]
 pub fn WebhookRequestList(props: &WebhookRequestListProps) -> Html {
-    let webhook_requests = use_state(|| vec![]);
+    let webhook_requests = use_state(std::vec::Vec::new);
+    // Kept with the webhook it was picked for, so switching webhooks drops it
+    let request_filter = use_state(|| None::<(String, RequestFilter)>);
+    let filter = request_filter
+        .as_ref()
+        .filter(|(webhook_id, _)| *webhook_id == props.webhook_id)
+        .map(|(_, filter)| filter.clone());
+
     {
         let webhook_requests = webhook_requests.clone();
         let webhook_id = props.webhook_id.clone();
-        use_effect_with(webhook_id.clone(), move |_| {
+        let filter = filter.clone();
+        use_effect_with((webhook_id.clone(), filter.clone()), move |_| {
             let webhook_requests = webhook_requests.clone();
-            let webhook_id = webhook_id.clone();
+            let url = requests_url(&webhook_id, filter.as_ref());
             wasm_bindgen_futures::spawn_local(async move {
-                let resp = Request::get(format!("/api/webhooks/{}/requests", webhook_id).as_str())
-                    .send()
-                    .await;
+                let resp = Request::get(url.as_str()).send().await;
                 match resp {
                     Ok(resp) => {
                         if resp.status() == 401 {
@@ -88,54 +114,44 @@ pub fn WebhookRequestList(props: &WebhookRequestListProps) -> Html {
         let webhook_requests = webhook_requests.clone();
         let webhook_id = props.webhook_id.clone();
 
-        use_effect_with(webhook_id.clone(), move |current_webhook_id| {
-            let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        use_effect_with(
+            (webhook_id.clone(), filter.clone()),
+            move |(current_webhook_id, filter)| {
+                let (abort_handle, abort_registration) = AbortHandle::new_pair();
 
-            let ws_url =
-                websocket_url(format!("/ws/webhooks/{}/notifications", current_webhook_id));
-            let webhook_id_for_async = current_webhook_id.clone();
-            let webhook_requests = webhook_requests.clone();
+                let notifications_path = format!("/webhooks/{}/notifications", current_webhook_id);
+                let requests_url = requests_url(current_webhook_id, filter.as_ref());
+                let webhook_requests = webhook_requests.clone();
+
+                let future = async move {
+                    let mut messages = notifications(&notifications_path);
 
-            let future = async move {
-                let ws = WebSocket::open(&ws_url).unwrap();
-                let (_write, mut read) = ws.split();
-
-                while let Some(msg) = read.next().await {
-                    match msg {
-                        Ok(Message::Text(_)) => {
-                            if let Ok(resp) = Request::get(
-                                format!("/api/webhooks/{}/requests", webhook_id_for_async).as_str(),
-                            )
-                            .send()
-                            .await
-                            {
-                                if resp.status() == 401 {
-                                    if let Some(win) = window() {
-                                        let _ = win.location().set_href("/");
-                                    }
-                                } else if let Ok(data) = resp.json().await {
-                                    webhook_requests.set(data);
+                    while messages.next().await.is_some() {
+                        if let Ok(resp) = Request::get(requests_url.as_str()).send().await {
+                            if resp.status() == 401 {
+                                if let Some(win) = window() {
+                                    let _ = win.location().set_href("/");
                                 }
+                            } else if let Ok(data) = resp.json().await {
+                                webhook_requests.set(data);
                             }
                         }
-                        Err(err) => web_sys::console::error_1(&err.to_string().into()),
-                        _ => {}
                     }
-                }
-            };
+                };
 
-            let abortable_future = Abortable::new(future, abort_registration);
+                let abortable_future = Abortable::new(future, abort_registration);
 
-            wasm_bindgen_futures::spawn_local(async move {
-                // Ignore the result (it returns Err(Aborted) if cancelled, which is expected)
-                let _ = abortable_future.await;
-            });
+                wasm_bindgen_futures::spawn_local(async move {
+                    // Ignore the result (it returns Err(Aborted) if cancelled, which is expected)
+                    let _ = abortable_future.await;
+                });
 
-            // Cleanup: Abort the task on unmount or ID change
-            move || {
-                abort_handle.abort();
-            }
-        });
+                // Cleanup: Abort the task on unmount, ID or filter change
+                move || {
+                    abort_handle.abort();
+                }
+            },
+        );
     }
 
     let search_query = props.search_query.clone();
@@ -148,8 +164,30 @@ pub fn WebhookRequestList(props: &WebhookRequestListProps) -> Html {
     let count = filtered.len();
     let total = webhook_requests.len();
 
+    let on_filter = {
+        let request_filter = request_filter.clone();
+        let webhook_id = props.webhook_id.clone();
+        Callback::from(move |filter: RequestFilter| {
+            request_filter.set(Some((webhook_id.clone(), filter)));
+        })
+    };
+
+    let on_clear_filter = {
+        let request_filter = request_filter.clone();
+        Callback::from(move |_: MouseEvent| request_filter.set(None))
+    };
+
     html! {
         <div class="requests-list" key={props.webhook_id.clone()}>
+            if let Some(ref filter) = filter {
+                <div class="event-filter">
+                    { "Showing only" }
+                    <span class="event-badge">{ filter.label() }</span>
+                    <button type="button" class="btn btn-secondary btn-sm" onclick={on_clear_filter}>
+                        { "Show all" }
+                    </button>
+                </div>
+            }
             if !search_query.is_empty() {
                 <div class="search-results-info">
                     { format!("{} {} of {}", count, if count == 1 { "result" } else { "results" }, total) }
@@ -166,6 +204,7 @@ pub fn WebhookRequestList(props: &WebhookRequestListProps) -> Html {
                 <WebhookRequestDetails
                     request={(*request).clone()}
                     search_query={search_query.clone()}
+                    on_filter={on_filter.clone()}
                 />
             }) }
         </div>
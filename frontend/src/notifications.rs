@@ -0,0 +1,136 @@
+use futures_util::StreamExt;
+use futures_util::stream::{self, LocalBoxStream};
+use gloo_net::eventsource::futures::{EventSource, EventSourceSubscription};
+use gloo_net::websocket::{Message, futures::WebSocket};
+use web_sys::window;
+
+/// Text messages of a notification stream such as `/webhooks/{id}/notifications`.
+///
+/// Listens on the WebSocket under `/ws`, and switches to the Server-Sent Events
+/// stream under `/sse` when the socket cannot be opened or fails before its first
+/// message, as it does behind proxies that block upgrades.
+pub fn notifications(path: &str) -> LocalBoxStream<'static, String> {
+    let source = match WebSocket::open(&websocket_url(&format!("/ws{}", path))) {
+        Ok(socket) => Source::Socket {
+            socket,
+            received: false,
+            path: path.to_string(),
+        },
+        Err(err) => {
+            web_sys::console::error_1(
+                &format!("Failed to open notifications WS, using SSE: {:?}", err).into(),
+            );
+            match open_event_source(path) {
+                Some(source) => source,
+                None => return stream::empty().boxed_local(),
+            }
+        }
+    };
+
+    stream::unfold(source, next_message).boxed_local()
+}
+
+enum Source {
+    Socket {
+        socket: WebSocket,
+        /// Whether a message arrived, i.e. the socket is known to get through
+        received: bool,
+        path: String,
+    },
+    Events {
+        // Closed when dropped, so it lives as long as the subscription
+        _events: EventSource,
+        subscription: EventSourceSubscription,
+    },
+}
+
+async fn next_message(source: Source) -> Option<(String, Source)> {
+    let mut source = source;
+    loop {
+        source = match source {
+            Source::Socket {
+                mut socket,
+                received,
+                path,
+            } => match socket.next().await? {
+                Ok(Message::Text(text)) => {
+                    let source = Source::Socket {
+                        socket,
+                        received: true,
+                        path,
+                    };
+                    return Some((text, source));
+                }
+                Ok(Message::Bytes(_)) => Source::Socket {
+                    socket,
+                    received: true,
+                    path,
+                },
+                Err(err) if !received => {
+                    web_sys::console::error_1(
+                        &format!("Notifications WS failed, using SSE: {}", err).into(),
+                    );
+                    open_event_source(&path)?
+                }
+                Err(err) => {
+                    web_sys::console::error_1(&err.to_string().into());
+                    Source::Socket {
+                        socket,
+                        received,
+                        path,
+                    }
+                }
+            },
+            Source::Events {
+                _events,
+                mut subscription,
+            } => match subscription.next().await? {
+                Ok((_, event)) => {
+                    let text = event.data().as_string();
+                    let source = Source::Events {
+                        _events,
+                        subscription,
+                    };
+                    match text {
+                        Some(text) => return Some((text, source)),
+                        None => source,
+                    }
+                }
+                // The browser reconnects by itself; this means it gave up
+                Err(err) => {
+                    web_sys::console::error_1(&err.to_string().into());
+                    return None;
+                }
+            },
+        };
+    }
+}
+
+fn open_event_source(path: &str) -> Option<Source> {
+    let opened = EventSource::new(&format!("/sse{}", path)).and_then(|mut events| {
+        let subscription = events.subscribe("message")?;
+        Ok(Source::Events {
+            _events: events,
+            subscription,
+        })
+    });
+
+    match opened {
+        Ok(source) => Some(source),
+        Err(err) => {
+            web_sys::console::error_1(
+                &format!("Failed to open notifications SSE: {:?}", err).into(),
+            );
+            None
+        }
+    }
+}
+
+fn websocket_url(path: &str) -> String {
+    let window = window().expect("no window");
+    let location = window.location();
+    let protocol = location.protocol().unwrap();
+    let host = location.host().unwrap();
+    let ws_protocol = if protocol == "https:" { "wss" } else { "ws" };
+    format!("{ws_protocol}://{host}{path}")
+}
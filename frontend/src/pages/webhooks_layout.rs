@@ -5,24 +5,30 @@ use futures_util::StreamExt;
 use futures_util::stream::AbortHandle;
 use futures_util::stream::Abortable;
 use gloo_net::http::Request;
-use gloo_net::websocket::{Message, futures::WebSocket};
+use gloo_timers::future::TimeoutFuture;
+use serde::Deserialize;
 use web_sys::window;
 use yew::html::ChildrenProps;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::schema_panel::ShapeChange;
 use crate::components::theme_switcher::ThemeSwitcher;
+use crate::components::toast::Toast;
 use crate::components::tooltip::Tooltip;
 use crate::components::webhook_list::Webhook;
 use crate::components::webhook_list::WebhookList;
+use crate::notifications::notifications;
 
-fn websocket_url(path: &str) -> String {
-    let window = window().expect("no window");
-    let location = window.location();
-    let protocol = location.protocol().unwrap();
-    let host = location.host().unwrap();
-    let ws_protocol = if protocol == "https:" { "wss" } else { "ws" };
-    format!("{ws_protocol}://{host}{path}")
+/// JSON event on the user notification stream; plain messages are webhook ids.
+#[derive(Deserialize)]
+struct UserEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    webhook_id: String,
+    event_type: Option<String>,
+    #[serde(default)]
+    changes: Vec<ShapeChange>,
 }
 
 #[component]
@@ -34,11 +40,12 @@ pub fn WebhooksLayout(props: &ChildrenProps) -> Html {
         _ => None,
     };
 
-    let webhooks = use_state(|| vec![]);
+    let webhooks = use_state(std::vec::Vec::new);
+    let drift_notice = use_state(|| None::<String>);
 
     // Mirrors kept in sync so the long-lived WS async loop can always read current values
     // without being re-created on every state change.
-    let webhooks_ref = use_mut_ref(|| vec![]);
+    let webhooks_ref = use_mut_ref(std::vec::Vec::new);
     let selected_id_ref = use_mut_ref(|| None::<String>);
 
     // Keep the selected-id mirror in sync with the derived route value.
@@ -128,62 +135,78 @@ pub fn WebhooksLayout(props: &ChildrenProps) -> Html {
         });
     }
 
-    // Single user-level WS subscription opened once on mount.
+    // Single user-level notification subscription opened once on mount.
     // Reads current list and selected id through refs so the loop is never re-created.
     {
         let webhooks = webhooks.clone();
         let webhooks_ref = webhooks_ref.clone();
         let selected_id_ref = selected_id_ref.clone();
+        let drift_notice = drift_notice.clone();
         use_effect_with((), move |_| {
             let (abort_handle, abort_registration) = AbortHandle::new_pair();
 
-            let ws_url = websocket_url("/ws/user/notifications");
-
             let future = async move {
-                match WebSocket::open(&ws_url) {
-                    Ok(ws) => {
-                        let (_write, mut read) = ws.split();
-                        while let Some(msg) = read.next().await {
-                            match msg {
-                                Ok(Message::Text(webhook_id)) => {
-                                    // Don't mark as unread if the user is already viewing it.
-                                    let is_selected = selected_id_ref
-                                        .borrow()
-                                        .as_deref()
-                                        .map(|id| id == webhook_id)
-                                        .unwrap_or(false);
-
-                                    if !is_selected {
-                                        // Read the current list from the ref (always up-to-date).
-                                        let updated: Vec<Webhook> = webhooks_ref
-                                            .borrow()
-                                            .iter()
-                                            .map(|w| {
-                                                if w.id == webhook_id {
-                                                    Webhook {
-                                                        has_unread: true,
-                                                        ..w.clone()
-                                                    }
-                                                } else {
-                                                    w.clone()
-                                                }
-                                            })
-                                            .collect();
-                                        webhooks.set(updated);
-                                    }
-                                }
-                                Err(err) => {
-                                    web_sys::console::error_1(&err.to_string().into());
+                let mut messages = notifications("/user/notifications");
+                while let Some(text) = messages.next().await {
+                    match text {
+                        text if text.starts_with('{') => {
+                            let Ok(event) = serde_json::from_str::<UserEvent>(&text) else {
+                                continue;
+                            };
+                            if event.kind != "drift" {
+                                continue;
+                            }
+                            let name = webhooks_ref
+                                .borrow()
+                                .iter()
+                                .find(|w| w.id == event.webhook_id)
+                                .map(|w| w.name.clone())
+                                .unwrap_or(event.webhook_id);
+                            drift_notice.set(Some(format!(
+                                "{}: {} payload drifted · {} {}",
+                                name,
+                                event.event_type.as_deref().unwrap_or("JSON"),
+                                event.changes.len(),
+                                if event.changes.len() == 1 {
+                                    "change"
+                                } else {
+                                    "changes"
                                 }
-                                _ => {}
+                            )));
+                            let drift_notice = drift_notice.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                TimeoutFuture::new(5_000).await;
+                                drift_notice.set(None);
+                            });
+                        }
+                        webhook_id => {
+                            // Don't mark as unread if the user is already viewing it.
+                            let is_selected = selected_id_ref
+                                .borrow()
+                                .as_deref()
+                                .map(|id| id == webhook_id)
+                                .unwrap_or(false);
+
+                            if !is_selected {
+                                // Read the current list from the ref (always up-to-date).
+                                let updated: Vec<Webhook> = webhooks_ref
+                                    .borrow()
+                                    .iter()
+                                    .map(|w| {
+                                        if w.id == webhook_id {
+                                            Webhook {
+                                                has_unread: true,
+                                                ..w.clone()
+                                            }
+                                        } else {
+                                            w.clone()
+                                        }
+                                    })
+                                    .collect();
+                                webhooks.set(updated);
                             }
                         }
                     }
-                    Err(err) => {
-                        web_sys::console::error_1(
-                            &format!("Failed to open user notifications WS: {:?}", err).into(),
-                        );
-                    }
                 }
             };
 
@@ -337,6 +360,11 @@ pub fn WebhooksLayout(props: &ChildrenProps) -> Html {
                     }
                 }
             />
+
+            <Toast
+                message={(*drift_notice).clone().unwrap_or_default()}
+                visible={drift_notice.is_some()}
+            />
         </>
     }
 }